@@ -1,34 +1,145 @@
 use bevy::prelude::*;
 
-use crate::{cpu_net::Cell, organism::OrganismSet};
+use crate::{
+    cell::CellOf,
+    cpu_net::{Brains, Cell},
+    organism::OrganismSet,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, update_outputs.in_set(OrganismSet::ProcessInput));
-    app.add_systems(Update, update_inputs.in_set(OrganismSet::ProcessOutput));
-    //todo
+    app.init_resource::<DataSettings>();
+    app.add_systems(
+        FixedUpdate,
+        update_outputs.in_set(OrganismSet::ProcessInput),
+    );
+    app.add_systems(
+        FixedUpdate,
+        update_inputs.in_set(OrganismSet::ProcessOutput),
+    );
 }
 
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct DataSettings {
+    /// Fraction of a stored value lost every second. `0` keeps values forever.
+    pub decay: f32,
+    /// A register's gate is open while the network drives it above this.
+    pub gate_threshold: f32,
+}
+
+impl Default for DataSettings {
+    fn default() -> Self {
+        Self {
+            decay: 0.,
+            gate_threshold: 0.,
+        }
+    }
+}
+
+/// Four registers of working memory, fed back into the network every tick.
+///
+/// The cell reads eight outputs: a value for each register, then a gate for each register.
+/// A register only takes its value while its gate is open, and holds it otherwise.
 #[derive(Component, Default)]
 pub struct DataCell {
     data: [f32; 4],
 }
 
-fn update_outputs(data_cells: Query<(&DataCell, &Cell)>) {
-    for (cell, outputs) in data_cells {
-        outputs.set(0, cell.data[0]);
-        outputs.set(1, cell.data[0]);
-        outputs.set(2, cell.data[0]);
-        outputs.set(3, cell.data[0]);
+impl DataCell {
+    pub fn registers(&self) -> &[f32; 4] {
+        &self.data
+    }
+
+    /// Stores `value` in `register` if `gate` is open.
+    pub fn write(&mut self, register: usize, value: f32, gate: f32, settings: &DataSettings) {
+        if gate > settings.gate_threshold {
+            self.data[register] = value;
+        }
+    }
+
+    pub fn decay(&mut self, settings: &DataSettings, delta: f32) {
+        if settings.decay <= 0. {
+            return;
+        }
+        let keep = (1. - settings.decay.min(1.)).powf(delta);
+        for value in &mut self.data {
+            *value *= keep;
+        }
+    }
+}
+
+fn update_outputs(data_cells: Query<(&DataCell, &Cell, &CellOf)>, mut brains: ResMut<Brains>) {
+    for (cell, outputs, cell_of) in data_cells {
+        let Some(values) = brains.values_mut(cell_of.0) else {
+            continue;
+        };
+        for (i, value) in cell.data.iter().enumerate() {
+            outputs.set(values, i, *value);
+        }
+    }
+}
+
+fn update_inputs(
+    data_cells: Query<(&mut DataCell, &Cell, &CellOf)>,
+    brains: Res<Brains>,
+    settings: Res<DataSettings>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (mut cell, input, cell_of) in data_cells {
+        cell.decay(&settings, delta);
+        let Some(values) = brains.values(cell_of.0) else {
+            continue;
+        };
+        let registers = cell.data.len();
+        for register in 0..registers {
+            let value = input.get(values, register);
+            let gate = input.get(values, registers + register);
+            cell.write(register, value, gate, &settings);
+        }
     }
 }
 
-fn update_inputs(data_cells: Query<(&mut DataCell, &Cell)>) {
-    for (mut cell, input) in data_cells {
-        cell.data[0] = input.get(0);
-        cell.data[1] = input.get(1);
-        cell.data[2] = input.get(2);
-        cell.data[3] = input.get(3);
+#[cfg(test)]
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_registers_are_independent() {
+    let settings = DataSettings::default();
+    let mut cell = DataCell::default();
+    for (register, value) in [0.1, -0.2, 0.3, -0.4].into_iter().enumerate() {
+        cell.write(register, value, 1., &settings);
     }
+    assert_eq!(cell.registers(), &[0.1, -0.2, 0.3, -0.4]);
+}
+
+#[test]
+fn test_closed_gates_hold_and_open_gates_clear() {
+    let settings = DataSettings::default();
+    let mut cell = DataCell::default();
+    cell.write(0, 0.8, 1., &settings);
+
+    cell.write(0, 0.3, -1., &settings);
+    assert_eq!(cell.registers()[0], 0.8, "A closed gate keeps the register");
+
+    cell.write(0, 0., 1., &settings);
+    assert_eq!(
+        cell.registers()[0],
+        0.,
+        "An open gate can clear the register"
+    );
+}
+
+#[test]
+fn test_registers_decay() {
+    let settings = DataSettings {
+        decay: 0.5,
+        ..default()
+    };
+    let mut cell = DataCell::default();
+    cell.write(2, 1., 1., &settings);
+    cell.decay(&settings, 1.);
+    assert_eq!(cell.registers()[2], 0.5);
 
-    //todo
+    cell.decay(&DataSettings::default(), 1.);
+    assert_eq!(cell.registers()[2], 0.5);
 }
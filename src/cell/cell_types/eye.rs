@@ -1,15 +1,248 @@
 use bevy::prelude::*;
 
-use crate::{cpu_net::Cell, organism::OrganismSet};
+use crate::{
+    cell::{CellKind, CellOf},
+    cpu_net::{Brains, Cell},
+    game::grid::{RayTarget, Tile, WorldGrid},
+    organism::{OrganismSet, cell_position},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, update_outputs.in_set(OrganismSet::ProcessInput));
+    app.init_resource::<VisionSettings>();
+    app.add_systems(
+        FixedUpdate,
+        update_outputs.in_set(OrganismSet::ProcessInput),
+    );
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct VisionSettings {
+    /// How far an eye can see, in tiles
+    pub range: f32,
+    /// Width of the view cone, in radians
+    pub field_of_view: f32,
+    /// Rays spread evenly across the field of view
+    pub rays: u32,
+}
+
+impl Default for VisionSettings {
+    fn default() -> Self {
+        Self {
+            range: 10.,
+            field_of_view: 60_f32.to_radians(),
+            rays: 5,
+        }
+    }
 }
 
 #[derive(Component, Default)]
 pub struct Eye {}
 
-fn update_outputs(eyes: Query<(&Eye, &Cell)>) {
+/// Something an eye can see.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sighting {
+    Food,
+    Wall,
+    Cell(CellKind),
+}
+
+impl Sighting {
+    /// The value written to the eye's second input. Nothing seen is `0`.
+    pub fn signal(self) -> f32 {
+        match self {
+            Sighting::Food => 1.,
+            Sighting::Wall => -1.,
+            Sighting::Cell(kind) => match kind {
+                CellKind::Launcher => -0.6,
+                CellKind::Eye => -0.2,
+                CellKind::Foot => 0.2,
+                CellKind::Data => 0.4,
+                CellKind::Mouth => 0.6,
+            },
+        }
+    }
+}
+
+/// Eyes look away from the center of their organism. An eye on the center looks forward.
+fn facing(eye: &Transform, organism: &Transform) -> Vec2 {
+    let forward = organism.rotation * Vec3::Y;
+    (organism.rotation * eye.translation)
+        .xy()
+        .normalize_or(forward.xy())
+}
+
+/// Directions of the rays an eye facing `facing` casts, spread evenly across the field of
+/// view from right to left. A single ray looks straight ahead.
+fn rays(facing: Vec2, settings: &VisionSettings) -> impl Iterator<Item = Vec2> {
+    let rays = settings.rays.max(1);
+    let field_of_view = settings.field_of_view;
+    (0..rays).map(move |i| {
+        let offset = if rays == 1 {
+            0.
+        } else {
+            field_of_view * (i as f32 / (rays - 1) as f32 - 0.5)
+        };
+        Vec2::from_angle(offset).rotate(facing)
+    })
+}
+
+/// The closeness (`1` touching, `0` at the edge of the range) and [`Sighting`] of the
+/// nearest thing any ray hits.
+///
+/// `kind_of` gives the kind of a cell the rays hit, or `None` for cells of the eye's own
+/// organism, which it looks through.
+fn look(
+    grid: &WorldGrid,
+    origin: Vec2,
+    facing: Vec2,
+    settings: &VisionSettings,
+    kind_of: impl Fn(Entity) -> Option<CellKind>,
+) -> Option<(f32, Sighting)> {
+    let hit = rays(facing, settings)
+        .filter_map(|direction| {
+            grid.raycast(origin, direction, settings.range, |seen| {
+                kind_of(seen).is_none()
+            })
+        })
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))?;
+    let sighting = match hit.target {
+        RayTarget::Tile(Tile::Food) => Sighting::Food,
+        RayTarget::Tile(_) => Sighting::Wall,
+        RayTarget::Cell(seen) => Sighting::Cell(kind_of(seen)?),
+    };
+    Some((1. - hit.distance / settings.range, sighting))
+}
+
+/// Writes the closeness and [`Sighting::signal`] of the nearest thing in view, or `0`s when
+/// there's nothing.
+fn update_outputs(
+    eyes: Query<(&Cell, &CellOf, &Transform), With<Eye>>,
+    organisms: Query<&Transform, Without<CellOf>>,
+    cells: Query<(&Cell, &CellOf)>,
+    mut brains: ResMut<Brains>,
+    grid: Res<WorldGrid>,
+    settings: Res<VisionSettings>,
+) {
+    for (cell, cell_of, eye) in eyes {
+        let Ok(organism) = organisms.get(cell_of.0) else {
+            continue;
+        };
+        let (closeness, signal) = look(
+            &grid,
+            cell_position(organism, eye),
+            facing(eye, organism),
+            &settings,
+            |seen| {
+                cells
+                    .get(seen)
+                    .ok()
+                    .filter(|(_, seen_of)| seen_of.0 != cell_of.0)
+                    .map(|(seen, _)| seen.kind())
+            },
+        )
+        .map_or((0., 0.), |(closeness, sighting)| {
+            (closeness, sighting.signal())
+        });
+
+        let Some(values) = brains.values_mut(cell_of.0) else {
+            continue;
+        };
+        cell.set(values, 0, closeness);
+        cell.set(values, 1, signal);
+    }
+}
+
+#[cfg(test)]
+use pretty_assertions::assert_eq;
+
+#[cfg(test)]
+fn entity(index: u32) -> Entity {
+    Entity::from_raw_u32(index).unwrap()
+}
+
+#[test]
+fn test_eye_faces_away_from_center() {
+    let organism = Transform::from_xyz(3., 3., 0.);
+    let eye = Transform::from_xyz(0., 2., 0.);
+    assert_eq!(facing(&eye, &organism), Vec2::Y);
+
+    let rotated = Transform::from_xyz(3., 3., 0.)
+        .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+    assert!(facing(&eye, &rotated).distance(Vec2::NEG_X) < 0.001);
+    assert!(facing(&Transform::default(), &rotated).distance(Vec2::NEG_X) < 0.001);
+}
+
+#[test]
+fn test_rays_fan_across_the_field_of_view() {
+    let settings = VisionSettings {
+        field_of_view: 90_f32.to_radians(),
+        rays: 3,
+        ..default()
+    };
+    let directions: Vec<Vec2> = rays(Vec2::Y, &settings).collect();
+    let expected = [Vec2::new(1., 1.), Vec2::Y, Vec2::new(-1., 1.)];
+    assert_eq!(directions.len(), expected.len());
+    for (direction, expected) in directions.iter().zip(expected) {
+        assert!(direction.distance(expected.normalize()) < 0.001);
+    }
+
+    let single = VisionSettings {
+        rays: 1,
+        ..settings
+    };
+    assert_eq!(rays(Vec2::X, &single).collect::<Vec<_>>(), vec![Vec2::X]);
+}
+
+#[test]
+fn test_eyes_see_the_nearest_food_wall_or_cell() {
+    let settings = VisionSettings {
+        range: 8.,
+        rays: 1,
+        ..default()
+    };
+    let mut grid = WorldGrid::new(40, 40);
+    grid.set_tile(IVec2::new(4, 0), Tile::Food);
+    grid.set_tile(IVec2::new(6, 0), Tile::Wall);
+    let nothing = |_| None;
+
+    // rays reach a tile half a tile before its center
+    assert_eq!(
+        look(&grid, Vec2::ZERO, Vec2::X, &settings, nothing),
+        Some((1. - 3.5 / 8., Sighting::Food))
+    );
+    assert_eq!(
+        look(&grid, Vec2::new(5., 0.), Vec2::X, &settings, nothing),
+        Some((1. - 0.5 / 8., Sighting::Wall))
+    );
+    assert_eq!(
+        look(&grid, Vec2::ZERO, Vec2::NEG_X, &settings, nothing),
+        None
+    );
+
+    let foot = entity(1);
+    grid.insert_occupant(IVec2::new(2, 0), foot);
+    let kind_of = |cell: Entity| (cell == foot).then_some(CellKind::Foot);
+    assert_eq!(
+        look(&grid, Vec2::ZERO, Vec2::X, &settings, kind_of),
+        Some((1. - 1.5 / 8., Sighting::Cell(CellKind::Foot)))
+    );
+}
+
+#[test]
+fn test_eyes_look_through_their_own_cells() {
+    let settings = VisionSettings {
+        range: 8.,
+        rays: 1,
+        ..default()
+    };
+    let mut grid = WorldGrid::new(40, 40);
+    let (own, other) = (entity(1), entity(2));
+    grid.insert_occupant(IVec2::new(1, 0), own);
+    grid.insert_occupant(IVec2::new(3, 0), other);
+    let kind_of = |cell: Entity| (cell == other).then_some(CellKind::Mouth);
 
-    //todo
+    let (closeness, sighting) = look(&grid, Vec2::ZERO, Vec2::X, &settings, kind_of).unwrap();
+    assert_eq!(sighting, Sighting::Cell(CellKind::Mouth));
+    assert_eq!(closeness, 1. - 2.5 / 8.);
+    assert_eq!(sighting.signal(), 0.6);
 }
@@ -1,34 +1,40 @@
 use bevy::prelude::*;
 
 use crate::{
-    cell::{CellOf, Cells},
-    cpu_net::Cell,
-    organism::OrganismSet,
+    cell::CellOf,
+    cpu_net::{Brains, Cell},
+    organism::{OrganismSet, PhysicsSettings, Thrust},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, update_inputs.in_set(OrganismSet::ProcessOutput));
+    app.add_systems(
+        FixedUpdate,
+        update_inputs.in_set(OrganismSet::ProcessOutput),
+    );
 }
 
 #[derive(Component, Default)]
 pub struct Foot {}
 
+/// Outputs are the x and y of the thrust, relative to the organism.
 fn update_inputs(
-    feet: Query<(&Foot, &Cell, &CellOf)>,
-    mut organisms: Query<&mut Transform, With<Cells>>,
-    time: Res<Time>,
+    feet: Query<(&Cell, &CellOf, &Transform), With<Foot>>,
+    mut organisms: Query<(&mut Thrust, &Transform), Without<CellOf>>,
+    brains: Res<Brains>,
+    settings: Res<PhysicsSettings>,
 ) {
-    let delta = time.delta_secs();
-    for (_, input, cell_of) in feet {
-        let Ok(mut organism_trns) = organisms.get_mut(cell_of.0) else {
+    for (input, cell_of, foot) in feet {
+        let Ok((mut thrust, organism)) = organisms.get_mut(cell_of.0) else {
             continue;
         };
-        let dir_x = input.get(0);
-        let dir_y = input.get(1);
+        let Some(values) = brains.values(cell_of.0) else {
+            continue;
+        };
+        let dir_x = input.get(values, 0).clamp(-1., 1.);
+        let dir_y = input.get(values, 1).clamp(-1., 1.);
 
-        organism_trns.translation.x += dir_x.clamp(-1., 1.) * delta;
-        organism_trns.translation.x += dir_y.clamp(-1., 1.) * delta;
+        let force = Vec3::new(dir_x, dir_y, 0.) * settings.foot_force;
+        let lever = organism.rotation * foot.translation;
+        thrust.push_at((organism.rotation * force).xy(), lever.xy());
     }
-
-    //todo
 }
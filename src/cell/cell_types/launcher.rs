@@ -1,27 +1,209 @@
 use bevy::prelude::*;
 
-use crate::{cpu_net::Cell, organism::OrganismSet};
+use crate::{
+    cell::CellOf,
+    cpu_net::{Brains, Cell},
+    game::projectile::FireProjectile,
+    organism::{Energy, OrganismSet, cell_position},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, update_inputs.in_set(OrganismSet::ProcessOutput));
+    app.init_resource::<LauncherSettings>();
+    app.add_systems(
+        FixedUpdate,
+        update_inputs.in_set(OrganismSet::ProcessOutput),
+    );
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct LauncherSettings {
+    /// Seconds between shots
+    pub cooldown: f32,
+    /// Energy a shot costs the organism
+    pub energy_cost: f32,
+}
+
+impl Default for LauncherSettings {
+    fn default() -> Self {
+        Self {
+            cooldown: 1.,
+            energy_cost: 0.5,
+        }
+    }
 }
 
 #[derive(Component, Default)]
-pub struct Launcher {}
+pub struct Launcher {
+    /// Seconds until this launcher can fire again
+    cooldown: f32,
+}
+
+impl Launcher {
+    /// Counts the cooldown down by `delta` seconds.
+    pub fn tick(&mut self, delta: f32) {
+        self.cooldown = (self.cooldown - delta).max(0.);
+    }
+
+    /// Fires if the network asks to, the launcher has cooled down, and the organism can
+    /// pay for the shot.
+    ///
+    /// `outputs` are the launcher's outputs: `fire`, then the x and y of the firing
+    /// direction relative to an organism turned by `rotation`. Returns the direction in the
+    /// world, having charged `energy` and restarted the cooldown.
+    pub fn fire(
+        &mut self,
+        outputs: [f32; 3],
+        rotation: Quat,
+        energy: &mut Energy,
+        settings: &LauncherSettings,
+    ) -> Option<Vec2> {
+        let [fire, dir_x, dir_y] = outputs;
+        if fire <= 0. || self.cooldown > 0. || energy.current() < settings.energy_cost {
+            return None;
+        }
+        let direction = (rotation * Vec3::new(dir_x.clamp(-1., 1.), dir_y.clamp(-1., 1.), 0.)).xy();
+        if direction == Vec2::ZERO {
+            return None;
+        }
 
-fn update_inputs(feet: Query<(&Launcher, &Cell)>, time: Res<Time>) {
+        energy.spend(settings.energy_cost);
+        self.cooldown = settings.cooldown;
+        Some(direction)
+    }
+}
+
+fn update_inputs(
+    launchers: Query<(&mut Launcher, &Cell, &CellOf, &Transform)>,
+    mut organisms: Query<(&mut Energy, &Transform), Without<Launcher>>,
+    brains: Res<Brains>,
+    settings: Res<LauncherSettings>,
+    mut shots: MessageWriter<FireProjectile>,
+    time: Res<Time>,
+) {
     let delta = time.delta_secs();
-    for (_, input) in feet {
-        let should_fire = input.get(0);
-        if should_fire <= 0. {
+    for (mut launcher, input, cell_of, transform) in launchers {
+        launcher.tick(delta);
+
+        let Some(values) = brains.values(cell_of.0) else {
             continue;
+        };
+        let Ok((mut energy, organism)) = organisms.get_mut(cell_of.0) else {
+            continue;
+        };
+        let outputs = [0, 1, 2].map(|i| input.get(values, i));
+        if let Some(direction) = launcher.fire(outputs, organism.rotation, &mut energy, &settings) {
+            shots.write(FireProjectile {
+                owner: cell_of.0,
+                origin: cell_position(organism, transform),
+                direction,
+            });
         }
-        let dir_x = input.get(1);
-        let dir_y = input.get(2);
-        //will fire in the future
-        // organism_trns.translation.x += dir_x.clamp(-1., 1.) * delta;
-        // organism_trns.translation.x += dir_y.clamp(-1., 1.) * delta;
     }
+}
+
+#[cfg(test)]
+use {pretty_assertions::assert_eq, std::f32::consts::FRAC_PI_2};
+
+#[test]
+fn test_launchers_wait_for_their_cooldown() {
+    let settings = LauncherSettings::default();
+    let mut energy = Energy::new(10., 10.);
+    let mut launcher = Launcher::default();
+    let fire = [1., 1., 0.];
+
+    assert!(
+        launcher
+            .fire(fire, Quat::IDENTITY, &mut energy, &settings)
+            .is_some()
+    );
+    assert!(
+        launcher
+            .fire(fire, Quat::IDENTITY, &mut energy, &settings)
+            .is_none()
+    );
+
+    launcher.tick(settings.cooldown * 0.5);
+    assert!(
+        launcher
+            .fire(fire, Quat::IDENTITY, &mut energy, &settings)
+            .is_none()
+    );
+    launcher.tick(settings.cooldown * 0.5);
+    assert!(
+        launcher
+            .fire(fire, Quat::IDENTITY, &mut energy, &settings)
+            .is_some()
+    );
+}
+
+#[test]
+fn test_shots_cost_energy() {
+    let settings = LauncherSettings {
+        cooldown: 0.,
+        energy_cost: 0.5,
+    };
+    let mut energy = Energy::new(0.8, 10.);
+    let mut launcher = Launcher::default();
+    let fire = [1., 1., 0.];
+
+    assert!(
+        launcher
+            .fire(fire, Quat::IDENTITY, &mut energy, &settings)
+            .is_some()
+    );
+    assert_eq!(energy.current(), 0.3);
+    // not enough left for another
+    assert!(
+        launcher
+            .fire(fire, Quat::IDENTITY, &mut energy, &settings)
+            .is_none()
+    );
+    assert_eq!(energy.current(), 0.3);
+}
+
+#[test]
+fn test_launchers_only_fire_when_asked() {
+    let settings = LauncherSettings::default();
+    let mut energy = Energy::new(10., 10.);
+    let mut launcher = Launcher::default();
+
+    assert!(
+        launcher
+            .fire([0., 1., 0.], Quat::IDENTITY, &mut energy, &settings)
+            .is_none()
+    );
+    // nowhere to aim
+    assert!(
+        launcher
+            .fire([1., 0., 0.], Quat::IDENTITY, &mut energy, &settings)
+            .is_none()
+    );
+    assert_eq!(energy.current(), 10.);
+}
+
+#[test]
+fn test_shots_aim_along_the_outputs_turned_with_the_organism() {
+    let settings = LauncherSettings {
+        cooldown: 0.,
+        energy_cost: 0.,
+    };
+    let mut energy = Energy::new(10., 10.);
+    let mut launcher = Launcher::default();
+    let angle = 0.5_f32;
+    let aim = [1., angle.cos(), angle.sin()];
+
+    let straight = launcher
+        .fire(aim, Quat::IDENTITY, &mut energy, &settings)
+        .unwrap();
+    assert!(straight.distance(Vec2::from_angle(angle)) < 0.001);
+
+    let turned = Quat::from_rotation_z(FRAC_PI_2);
+    let direction = launcher.fire(aim, turned, &mut energy, &settings).unwrap();
+    assert!(direction.distance(Vec2::from_angle(angle + FRAC_PI_2)) < 0.001);
 
-    //todo
+    // outputs past the unit range are clamped
+    let far = launcher
+        .fire([1., 5., 0.], Quat::IDENTITY, &mut energy, &settings)
+        .unwrap();
+    assert_eq!(far, Vec2::X);
 }
@@ -13,8 +13,17 @@ pub use launcher::*;
 mod eye;
 pub use eye::*;
 
+mod mouth;
+pub use mouth::*;
+
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((data::plugin, foot::plugin, launcher::plugin, eye::plugin));
+    app.add_plugins((
+        data::plugin,
+        foot::plugin,
+        launcher::plugin,
+        eye::plugin,
+        mouth::plugin,
+    ));
 }
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+use crate::{
+    cell::CellOf,
+    game::grid::{Tile, WorldGrid},
+    organism::{Energy, EnergySettings, OrganismSet, cell_position},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(FixedUpdate, eat_food.in_set(OrganismSet::ProcessOutput));
+}
+
+#[derive(Component, Default)]
+pub struct Mouth {}
+
+fn eat_food(
+    mouths: Query<(&Transform, &CellOf), With<Mouth>>,
+    mut organisms: Query<(&mut Energy, &Transform), Without<CellOf>>,
+    mut grid: ResMut<WorldGrid>,
+    settings: Res<EnergySettings>,
+) {
+    for (transform, cell_of) in mouths {
+        let Ok((mut energy, organism)) = organisms.get_mut(cell_of.0) else {
+            continue;
+        };
+        let pos = grid.world_to_tile(cell_position(organism, transform));
+        let food: Vec<IVec2> = grid
+            .neighbourhood(pos, 1)
+            .filter(|(_, tile, _)| *tile == Tile::Food)
+            .map(|(pos, _, _)| pos)
+            .collect();
+
+        for pos in food {
+            grid.set_tile(pos, Tile::Empty);
+            energy.gain(settings.food_energy);
+        }
+    }
+}
+
+#[cfg(test)]
+use {bevy::ecs::system::RunSystemOnce, pretty_assertions::assert_eq};
+
+#[test]
+fn test_mouths_eat_adjacent_food() {
+    let mut world = World::new();
+    let settings = EnergySettings::default();
+    let mut grid = WorldGrid::new(20, 20);
+    // the mouth ends up at (3, 3)
+    let (near, far) = ([IVec2::new(2, 2), IVec2::new(4, 3)], IVec2::new(6, 3));
+    for pos in near.into_iter().chain([far]) {
+        grid.set_tile(pos, Tile::Food);
+    }
+    world.insert_resource(grid);
+    world.insert_resource(settings.clone());
+
+    let organism = world
+        .spawn((Energy::new(0., 100.), Transform::from_xyz(2., 3., 0.)))
+        .id();
+    world.spawn((
+        Mouth::default(),
+        CellOf(organism),
+        Transform::from_xyz(1., 0., 0.),
+    ));
+
+    world.run_system_once(eat_food).unwrap();
+
+    assert_eq!(
+        world.get::<Energy>(organism).unwrap().current(),
+        settings.food_energy * 2.
+    );
+    let grid = world.resource::<WorldGrid>();
+    for pos in near {
+        assert_eq!(grid.tile(pos), Tile::Empty);
+    }
+    assert_eq!(grid.tile(far), Tile::Food);
+}
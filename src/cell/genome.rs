@@ -2,24 +2,30 @@ use bevy::prelude::*;
 
 use crate::{
     cell::CellKind,
-    genome::{Input, NeuronTopology, Output},
+    genome::{NeuronArena, NeuronId},
 };
 
 #[derive(Debug, Clone)]
 pub struct CellGenome {
     pub kind: CellKind,
     /// These are only topological. They don't have or process data.
-    pub inputs: Vec<NeuronTopology<Input>>,
+    pub inputs: Vec<NeuronId>,
     /// These are only topological. They don't have or process data.
-    pub outputs: Vec<NeuronTopology<Output>>,
+    pub outputs: Vec<NeuronId>,
 }
 impl CellGenome {
-    pub fn network_inputs(&self) -> &[NeuronTopology<Input>] {
+    pub fn network_inputs(&self) -> &[NeuronId] {
         &self.inputs
     }
-    pub fn network_outputs(&self) -> &[NeuronTopology<Output>] {
+    pub fn network_outputs(&self) -> &[NeuronId] {
         &self.outputs
     }
+    /// Removes this cell's neurons, and every connection to them, from `neurons`.
+    pub fn remove_neurons(&self, neurons: &mut NeuronArena) {
+        for id in self.inputs.iter().chain(&self.outputs) {
+            neurons.remove(*id);
+        }
+    }
 }
 
 // #[derive(Clone)]
@@ -1,12 +1,17 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
-#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Debug)]
+#[derive(
+    Component, Reflect, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Debug, Serialize, Deserialize,
+)]
 pub enum CellKind {
     Launcher,
     Eye,
     Foot,
     Data,
+    /// Eats food on adjacent tiles. Has no neurons.
+    Mouth,
 }
 impl CellKind {
     pub fn requirements(&self) -> CellRequirements {
@@ -16,18 +21,33 @@ impl CellKind {
             Eye => 2,
             Foot => 0,
             Data => 4,
+            Mouth => 0,
         };
         let num_outputs = match self {
             Launcher => 3,
             Eye => 0,
             Foot => 2,
-            Data => 4,
+            // a value and a gate per register
+            Data => 8,
+            Mouth => 0,
         };
         CellRequirements {
             num_inputs,
             num_outputs,
         }
     }
+
+    /// Energy this cell costs its organism every second.
+    pub fn upkeep(&self) -> f32 {
+        use CellKind::*;
+        match self {
+            Launcher => 0.3,
+            Eye => 0.2,
+            Foot => 0.25,
+            Data => 0.15,
+            Mouth => 0.1,
+        }
+    }
 }
 
 pub struct CellRequirements {
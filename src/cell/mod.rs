@@ -34,6 +34,32 @@ impl Cells {
 #[relationship(relationship_target = Cells)]
 pub struct CellOf(pub Entity);
 
+/// Where a cell sits in its organism's genome.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellLocation(pub IVec2);
+
+/// Cells die when this reaches zero.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct CellHealth(pub f32);
+
+impl Default for CellHealth {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+impl CellHealth {
+    /// Takes `damage`, returning whether that killed the cell.
+    pub fn hurt(&mut self, damage: f32) -> bool {
+        self.0 -= damage;
+        self.is_dead()
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.0 <= 0.
+    }
+}
+
 #[derive(Resource)]
 pub struct CellAssets {
     pub cell: Handle<Mesh>,
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+    tasks::{ComputeTaskPool, TaskPool},
+};
+
+use super::CpuNetwork;
+
+/// Every organism's network, evaluated as one batch.
+///
+/// Neuron values of the whole population share one buffer, a contiguous slice per organism.
+/// Sensors write their inputs into it, [`Brains::process`] runs every network in parallel,
+/// and actuators read their outputs back out.
+#[derive(Resource, Default)]
+pub struct Brains {
+    values: Vec<f32>,
+    slots: Vec<BrainSlot>,
+    lookup: HashMap<Entity, usize>,
+    /// Slots of removed organisms, still taking up room until the next [`Brains::compact`]
+    dead: usize,
+    /// Networks inserted so far, to tell them apart
+    inserted: u64,
+}
+
+struct BrainSlot {
+    /// `None` once the organism is removed
+    organism: Option<Entity>,
+    network: Arc<CpuNetwork>,
+    start: usize,
+    revision: u64,
+}
+
+impl BrainSlot {
+    fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.start + self.network.len()
+    }
+}
+
+impl Brains {
+    /// Adds `organism`'s network with all values at `0`, replacing any it already had.
+    pub fn insert(&mut self, organism: Entity, network: CpuNetwork) {
+        self.remove(organism);
+
+        let start = self.values.len();
+        self.values.resize(start + network.len(), 0.);
+        self.lookup.insert(organism, self.slots.len());
+        self.inserted += 1;
+        self.slots.push(BrainSlot {
+            organism: Some(organism),
+            network: Arc::new(network),
+            start,
+            revision: self.inserted,
+        });
+    }
+
+    pub fn remove(&mut self, organism: Entity) {
+        if let Some(slot) = self.lookup.remove(&organism) {
+            self.slots[slot].organism = None;
+            self.dead += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lookup.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lookup.is_empty()
+    }
+
+    /// Drops the values of removed organisms, keeping everyone else's.
+    pub fn compact(&mut self) {
+        if self.dead == 0 {
+            return;
+        }
+        let mut values = Vec::with_capacity(self.values.len());
+        self.slots.retain(|slot| slot.organism.is_some());
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            let start = values.len();
+            values.extend_from_slice(&self.values[slot.range()]);
+            slot.start = start;
+            if let Some(organism) = slot.organism {
+                self.lookup.insert(organism, i);
+            }
+        }
+        self.values = values;
+        self.dead = 0;
+    }
+
+    /// `organism`'s network and its current values.
+    pub fn get(&self, organism: Entity) -> Option<(&CpuNetwork, &[f32])> {
+        let slot = &self.slots[*self.lookup.get(&organism)?];
+        Some((&slot.network, &self.values[slot.range()]))
+    }
+
+    /// Changes every time `organism` is given a new network.
+    pub fn revision(&self, organism: Entity) -> Option<u64> {
+        Some(self.slots[*self.lookup.get(&organism)?].revision)
+    }
+
+    pub fn values(&self, organism: Entity) -> Option<&[f32]> {
+        self.get(organism).map(|(_, values)| values)
+    }
+
+    pub fn values_mut(&mut self, organism: Entity) -> Option<&mut [f32]> {
+        let slot = &self.slots[*self.lookup.get(&organism)?];
+        Some(&mut self.values[slot.range()])
+    }
+
+    /// Zeroes every network's inputs.
+    pub fn clear_inputs(&mut self) {
+        for slot in &self.slots {
+            slot.network.clear_inputs(&mut self.values[slot.range()]);
+        }
+    }
+
+    /// Evaluates every network, spread across the compute task pool.
+    pub fn process(&mut self) {
+        let mut jobs = Vec::with_capacity(self.slots.len());
+        let mut rest = self.values.as_mut_slice();
+        for slot in &self.slots {
+            let (values, tail) = std::mem::take(&mut rest).split_at_mut(slot.network.len());
+            rest = tail;
+            if slot.organism.is_some() {
+                jobs.push((&*slot.network, values));
+            }
+        }
+        if jobs.is_empty() {
+            return;
+        }
+
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let per_task = jobs.len().div_ceil(pool.thread_num().max(1) * 4);
+        pool.scope(|scope| {
+            for chunk in jobs.chunks_mut(per_task) {
+                scope.spawn(async move {
+                    for (network, values) in chunk {
+                        network.process(values);
+                    }
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::genome::Genome,
+    pretty_assertions::assert_eq,
+    rand::{Rng, SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_batch_matches_single_networks() {
+    let mut rng = StdRng::seed_from_u64(12);
+    let mut brains = Brains::default();
+    let mut singles = Vec::new();
+
+    for i in 0..40 {
+        let mut genome = Genome::sandbox(&mut rng);
+        genome.scramble(&mut rng);
+        let organism = Entity::from_raw_u32(i + 1).unwrap();
+
+        let (network, _) = CpuNetwork::new(&genome);
+        let inputs: Vec<f32> = (0..network.len())
+            .map(|_| rng.random_range(-1.0..=1.0))
+            .collect();
+        brains.insert(organism, network);
+        brains
+            .values_mut(organism)
+            .unwrap()
+            .copy_from_slice(&inputs);
+
+        let (network, _) = CpuNetwork::new(&genome);
+        singles.push((organism, network, inputs));
+    }
+
+    brains.process();
+    for (organism, network, values) in &mut singles {
+        network.process(values);
+        assert_eq!(brains.values(*organism).unwrap(), values.as_slice());
+    }
+}
+
+#[test]
+fn test_compact_keeps_survivors() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut brains = Brains::default();
+    let organisms: Vec<Entity> = (1..=3).map(|i| Entity::from_raw_u32(i).unwrap()).collect();
+
+    for (i, organism) in organisms.iter().enumerate() {
+        let (network, _) = CpuNetwork::new(&Genome::sandbox(&mut rng));
+        brains.insert(*organism, network);
+        brains.values_mut(*organism).unwrap().fill(i as f32);
+    }
+
+    brains.remove(organisms[1]);
+    brains.compact();
+
+    assert_eq!(brains.len(), 2);
+    assert!(brains.values(organisms[1]).is_none());
+    assert!(
+        brains
+            .values(organisms[0])
+            .unwrap()
+            .iter()
+            .all(|v| *v == 0.)
+    );
+    assert!(
+        brains
+            .values(organisms[2])
+            .unwrap()
+            .iter()
+            .all(|v| *v == 2.)
+    );
+}
+
+#[test]
+fn test_new_networks_get_new_revisions() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut brains = Brains::default();
+    let (first, second) = (
+        Entity::from_raw_u32(1).unwrap(),
+        Entity::from_raw_u32(2).unwrap(),
+    );
+    let genome = Genome::sandbox(&mut rng);
+
+    brains.insert(first, CpuNetwork::new(&genome).0);
+    brains.insert(second, CpuNetwork::new(&genome).0);
+    let before = brains.revision(first).unwrap();
+    assert_ne!(before, brains.revision(second).unwrap());
+
+    // compacting moves values around, but the networks stay the same
+    brains.insert(first, CpuNetwork::new(&genome).0);
+    let after = brains.revision(first).unwrap();
+    brains.compact();
+    assert_ne!(before, after);
+    assert_eq!(brains.revision(first), Some(after));
+
+    brains.remove(second);
+    assert_eq!(brains.revision(second), None);
+}
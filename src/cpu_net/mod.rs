@@ -1,161 +1,585 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock},
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
 };
 
-use bevy::prelude::*;
-use uuid::Uuid;
+mod batch;
+pub use batch::*;
 
-mod neuron;
-pub use neuron::*;
+#[cfg(test)]
+mod recursive;
 
 use crate::{
     cell::CellKind,
-    genome::{Genome, NeuronInputType, NeuronTopology, TakesInput},
+    genome::{
+        BrainDiagram, DiagramCell, DiagramConnection, DiagramNeuron, Genome, Incoming, NeuronArena,
+        NeuronId, NeuronKind, activations::Activation,
+    },
 };
 
+/// A cell's slots in its organism's [`CpuNetwork`].
+///
+/// `values` are the organism's neuron values, usually borrowed from [`Brains`].
 #[derive(Component)]
 pub struct Cell {
     kind: CellKind,
-    inputs: Vec<CpuNeuron>,
-    outputs: Vec<CpuNeuron>,
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
 }
 
 impl Cell {
     pub fn kind(&self) -> CellKind {
         self.kind
     }
-    pub fn set(&self, index: usize, value: f32) {
-        let input = &self.inputs[index];
-        let mut inner = input.inner.write().unwrap();
-
-        inner.value = Some(value);
-    }
-    pub fn get(&self, index: usize) -> f32 {
-        let output = &self.outputs[index];
-        output.process()
+    pub fn set(&self, values: &mut [f32], index: usize, value: f32) {
+        values[self.inputs[index]] = value;
     }
-    pub fn reset(&self) {
-        for output in &self.outputs {
-            output.propagate_reset()
-        }
+    /// The value of an output from the last [`CpuNetwork::process`].
+    pub fn get(&self, values: &[f32], index: usize) -> f32 {
+        values[self.outputs[index]]
     }
 
-    pub fn input_neurons(&self) -> &[CpuNeuron] {
+    pub fn input_neurons(&self) -> &[usize] {
         &self.inputs
     }
 
-    pub fn output_neurons(&self) -> &[CpuNeuron] {
+    pub fn output_neurons(&self) -> &[usize] {
         &self.outputs
     }
 }
 
+/// An organism's brain, compiled to a flat program.
+///
+/// The program only describes the network. Neuron values live in a separate slice of
+/// [`CpuNetwork::len`] floats: input neurons first, then every other neuron in
+/// topological order, so a single pass over the slice evaluates the whole network.
+///
+/// Recurrent connections read from delay slots at the end of the slice, which hold their
+/// source's value from the previous [`CpuNetwork::process`]. Values are never reset, so
+/// this is the network's memory from one tick to the next.
 pub struct CpuNetwork {
-    pub cells: HashMap<IVec2, Cell>,
+    ids: Vec<NeuronId>,
+    num_inputs: usize,
+    /// Edges of the `i`th computed neuron are `edge_starts[i]..edge_starts[i + 1]`
+    edge_starts: Vec<usize>,
+    edge_sources: Vec<usize>,
+    edge_weights: Vec<f32>,
+    biases: Vec<f32>,
+    activations: Vec<Activation>,
+    /// The neuron copied into each delay slot
+    delayed: Vec<usize>,
 }
 
 impl CpuNetwork {
-    pub fn new(genome: &Genome) -> Self {
-        let mut neuron_bank = HashMap::new();
-
-        let mut output_map = HashMap::new();
+    /// Compiles `genome`, returning the network and the cells that read and write it.
+    pub fn new(genome: &Genome) -> (Self, HashMap<IVec2, Cell>) {
+        let mut network = Self {
+            ids: Vec::new(),
+            num_inputs: 0,
+            edge_starts: vec![0],
+            edge_sources: Vec::new(),
+            edge_weights: Vec::new(),
+            biases: Vec::new(),
+            activations: Vec::new(),
+            delayed: Vec::new(),
+        };
+        let mut indices = HashMap::new();
+        let mut recurrent = Vec::new();
 
-        for (cell, cell_genome) in genome.cells().map() {
-            let mut new_outputs = Vec::new();
-
-            for output_neuron in &cell_genome.outputs {
-                new_outputs.push(process_topology(output_neuron, &mut neuron_bank));
+        for cell_genome in genome.cells().map().values() {
+            for input in &cell_genome.inputs {
+                indices.insert(*input, network.push_neuron(*input));
             }
-            output_map.insert(*cell, new_outputs);
         }
+        network.num_inputs = network.ids.len();
 
-        let mut cells = HashMap::with_capacity(genome.cells().len());
-
-        for (cell_location, cell_genome) in genome.cells().map() {
-            let mut new_inputs = Vec::new();
-
-            for input_neuron in &cell_genome.inputs {
-                let id = input_neuron.id();
-                match neuron_bank.remove(&id) {
-                    Some(neuron) => {
-                        new_inputs.push(neuron);
-                    }
-                    None => new_inputs.push(CpuNeuron::input()),
-                }
-            }
-            let outputs = output_map.remove(cell_location).unwrap();
+        let neurons = genome.neurons();
+        let incoming = neurons.enabled_incoming();
 
+        let mut cells = HashMap::with_capacity(genome.cells().len());
+        for (location, cell_genome) in genome.cells().map() {
+            let inputs = cell_genome
+                .inputs
+                .iter()
+                .map(|input| indices[input])
+                .collect();
+            let outputs = cell_genome
+                .outputs
+                .iter()
+                .map(|output| {
+                    network.compile(*output, neurons, &incoming, &mut indices, &mut recurrent)
+                })
+                .collect();
             cells.insert(
-                *cell_location,
+                *location,
                 Cell {
                     kind: cell_genome.kind,
-                    inputs: new_inputs,
+                    inputs,
                     outputs,
                 },
             );
         }
-        Self { cells }
+
+        // a neuron only read through recurrent connections still has to be computed,
+        // and may bring more recurrent connections with it
+        let mut i = 0;
+        while let Some((_, source)) = recurrent.get(i).copied() {
+            network.compile(source, neurons, &incoming, &mut indices, &mut recurrent);
+            i += 1;
+        }
+
+        let mut slots = HashMap::new();
+        for (edge, source) in recurrent {
+            let source = indices[&source];
+            let slot = *slots.entry(source).or_insert_with(|| {
+                network.delayed.push(source);
+                network.delayed.len() - 1
+            });
+            network.edge_sources[edge] = network.ids.len() + slot;
+        }
+
+        (network, cells)
     }
-}
 
-fn process_topology<T: TakesInput>(
-    neuron: &NeuronTopology<T>,
-    neurons: &mut HashMap<Uuid, CpuNeuron>,
-) -> CpuNeuron {
-    let read = neuron.read();
+    fn push_neuron(&mut self, id: NeuronId) -> usize {
+        self.ids.push(id);
+        self.ids.len() - 1
+    }
 
-    let mut cpu_inputs = Vec::new();
+    /// Appends `neuron` after everything it reads from, returning its index.
+    ///
+    /// Walks the genome depth-first with an explicit stack, laying out each neuron once all
+    /// of its senders are. Recurrent connections don't need their source first. They're
+    /// left pointing nowhere and pushed to `recurrent`, to be aimed at a delay slot once
+    /// everything is laid out.
+    fn compile(
+        &mut self,
+        neuron: NeuronId,
+        neurons: &NeuronArena,
+        incoming: &Incoming,
+        indices: &mut HashMap<NeuronId, usize>,
+        recurrent: &mut Vec<(usize, NeuronId)>,
+    ) -> usize {
+        if let Some(index) = indices.get(&neuron) {
+            return *index;
+        }
+        // each entry is a neuron and how many of its senders have been looked at
+        let mut stack = vec![(neuron, 0)];
+        // a cycle the cleaner missed is cut here rather than walked forever
+        let mut on_stack = HashSet::from([neuron]);
 
-    for input in read.inputs() {
-        match &input.input_type {
-            NeuronInputType::Hidden(hidden) => {
-                let Some(hidden) = hidden.upgrade() else {
-                    continue;
-                };
-                let id = hidden.id();
-                match neurons.get(&id) {
-                    Some(neuron) => {
-                        cpu_inputs.push((neuron.clone(), input.weight));
-                    }
-                    None => {
-                        let new_neuron = process_topology(&hidden, neurons);
-
-                        cpu_inputs.push((new_neuron.clone(), input.weight));
-                        neurons.insert(id, new_neuron);
-                    }
+        while let Some((current, next)) = stack.last_mut() {
+            let current = *current;
+            if let Some(edge) = incoming.of(current).get(*next) {
+                *next += 1;
+                let connection = &neurons.connections()[*edge];
+                // every input was laid out up front
+                if !connection.recurrent
+                    && !indices.contains_key(&connection.from)
+                    && neurons[connection.from].kind != NeuronKind::Input
+                    && on_stack.insert(connection.from)
+                {
+                    stack.push((connection.from, 0));
                 }
+                continue;
             }
-            NeuronInputType::Input(input_neuron) => {
-                let Some(input_neuron) = input_neuron.upgrade() else {
+            stack.pop();
+            on_stack.remove(&current);
+
+            for edge in incoming.of(current) {
+                let connection = &neurons.connections()[*edge];
+                if connection.recurrent {
+                    recurrent.push((self.edge_sources.len(), connection.from));
+                    self.edge_sources.push(usize::MAX);
+                } else if let Some(source) = indices.get(&connection.from) {
+                    self.edge_sources.push(*source);
+                } else {
                     continue;
+                }
+                self.edge_weights.push(connection.weight);
+            }
+            self.edge_starts.push(self.edge_sources.len());
+            self.biases.push(neurons[current].bias);
+            self.activations.push(neurons[current].activation);
+
+            let index = self.push_neuron(current);
+            indices.insert(current, index);
+        }
+
+        indices[&neuron]
+    }
+
+    /// Evaluates every neuron from the current inputs.
+    pub fn process(&self, values: &mut [f32]) {
+        let (neurons, delayed) = values.split_at_mut(self.ids.len());
+        for (i, neuron) in (self.num_inputs..self.ids.len()).enumerate() {
+            let mut sum = 0.;
+            for edge in self.edge_starts[i]..self.edge_starts[i + 1] {
+                let source = self.edge_sources[edge];
+                let value = match source.checked_sub(self.ids.len()) {
+                    Some(slot) => delayed[slot],
+                    None => neurons[source],
                 };
-                let id = input_neuron.id();
-                match neurons.get(&id) {
-                    Some(neuron) => {
-                        cpu_inputs.push((neuron.clone(), input.weight));
-                    }
-                    None => {
-                        let new_neuron = CpuNeuron::input();
-                        cpu_inputs.push((new_neuron.clone(), input.weight));
-                        neurons.insert(id, new_neuron);
-                    }
+                sum += value * self.edge_weights[edge];
+            }
+            neurons[neuron] = self.activations[i].apply(sum) + self.biases[i];
+        }
+        for (slot, source) in delayed.iter_mut().zip(&self.delayed) {
+            *slot = neurons[*source];
+        }
+    }
+
+    /// Zeroes the inputs, so cells that don't write this tick read as `0`.
+    pub fn clear_inputs(&self, values: &mut [f32]) {
+        values[..self.num_inputs].fill(0.);
+    }
+
+    /// The number of values the network reads and writes, delay slots included.
+    pub fn len(&self) -> usize {
+        self.ids.len() + self.delayed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The number of neurons, leaving out delay slots.
+    pub fn num_neurons(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Input neurons come first, so these are `0..num_inputs`.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// The genome id of a neuron
+    pub fn id(&self, neuron: usize) -> NeuronId {
+        self.ids[neuron]
+    }
+
+    /// Input neurons don't have an activation.
+    pub fn activation(&self, neuron: usize) -> Option<Activation> {
+        let computed = neuron.checked_sub(self.num_inputs)?;
+        Some(self.activations[computed])
+    }
+
+    /// The neurons `neuron` reads from, with their weights.
+    ///
+    /// Recurrent connections report the neuron behind their delay slot.
+    pub fn inputs(&self, neuron: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let edges = match neuron.checked_sub(self.num_inputs) {
+            Some(computed) => self.edge_starts[computed]..self.edge_starts[computed + 1],
+            None => 0..0,
+        };
+        edges.map(|edge| {
+            let source = self.edge_sources[edge];
+            let source = match source.checked_sub(self.ids.len()) {
+                Some(slot) => self.delayed[slot],
+                None => source,
+            };
+            (source, self.edge_weights[edge])
+        })
+    }
+
+    /// Diagrams the compiled network, so only neurons a cell ends up reading are in it.
+    ///
+    /// `cells` are the network's cells with their location in the genome. Passing the
+    /// network's `values` labels every neuron with its value from the last tick.
+    pub fn diagram<'a>(
+        &self,
+        cells: impl IntoIterator<Item = (IVec2, &'a Cell)>,
+        values: Option<&[f32]>,
+    ) -> BrainDiagram {
+        let mut cells: Vec<DiagramCell> = cells
+            .into_iter()
+            .map(|(location, cell)| DiagramCell {
+                kind: cell.kind,
+                location,
+                inputs: cell.inputs.iter().map(|neuron| self.id(*neuron)).collect(),
+                outputs: cell.outputs.iter().map(|neuron| self.id(*neuron)).collect(),
+            })
+            .collect();
+        cells.sort_by_key(|cell| (cell.location.x, cell.location.y));
+
+        let outputs: HashSet<NeuronId> = cells
+            .iter()
+            .flat_map(|cell| cell.outputs.iter().copied())
+            .collect();
+        let neurons = (0..self.num_neurons())
+            .map(|neuron| {
+                let id = self.id(neuron);
+                let computed = neuron.checked_sub(self.num_inputs);
+                DiagramNeuron {
+                    id,
+                    kind: match computed {
+                        None => NeuronKind::Input,
+                        Some(_) if outputs.contains(&id) => NeuronKind::Output,
+                        Some(_) => NeuronKind::Hidden,
+                    },
+                    bias: computed.map(|computed| self.biases[computed]),
+                    activation: self.activation(neuron),
+                    value: values.map(|values| values[neuron]),
+                }
+            })
+            .collect();
+
+        let mut connections = Vec::new();
+        for neuron in self.num_inputs..self.num_neurons() {
+            let computed = neuron - self.num_inputs;
+            for edge in self.edge_starts[computed]..self.edge_starts[computed + 1] {
+                let source = self.edge_sources[edge];
+                let (source, recurrent) = match source.checked_sub(self.ids.len()) {
+                    Some(slot) => (self.delayed[slot], true),
+                    None => (source, false),
+                };
+                connections.push(DiagramConnection {
+                    from: self.id(source),
+                    to: self.id(neuron),
+                    weight: self.edge_weights[edge],
+                    recurrent,
+                });
+            }
+        }
+
+        BrainDiagram {
+            cells,
+            neurons,
+            connections,
+        }
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::genome::decycler::{Cleaner, CycleMode},
+    pretty_assertions::assert_eq,
+    rand::{Rng, SeedableRng, rngs::StdRng},
+    recursive::RecursiveNetwork,
+};
+
+#[test]
+fn test_flat_network_matches_recursive() {
+    let mut rng = StdRng::seed_from_u64(11);
+
+    for _ in 0..50 {
+        let mut genome = Genome::sandbox(&mut rng);
+        for _ in 0..rng.random_range(0..20) {
+            genome.scramble(&mut rng);
+        }
+
+        let (network, cells) = CpuNetwork::new(&genome);
+        let reference = RecursiveNetwork::new(&genome);
+        let mut values = vec![0.; network.len()];
+
+        for _ in 0..5 {
+            network.clear_inputs(&mut values);
+            for (location, cell) in &cells {
+                for i in 0..cell.input_neurons().len() {
+                    let value = rng.random_range(-1.0..=1.0);
+                    cell.set(&mut values, i, value);
+                    reference.cells[location].set(i, value);
+                }
+            }
+            network.process(&mut values);
+
+            for (location, cell) in &cells {
+                let expected = &reference.cells[location];
+                for i in 0..cell.output_neurons().len() {
+                    assert_eq!(cell.get(&values, i), expected.get(i));
                 }
-                //todo
+            }
+            for cell in reference.cells.values() {
+                cell.reset();
             }
         }
     }
-    let cpu_neuron_inputs = CpuNeuronInputs {
-        inputs: cpu_inputs,
-        bias: read.bias(),
-        activation: read.activation(),
-    };
-    let inner = CpuNeuronInner {
-        id: Uuid::new_v4(),
-        inputs: Some(cpu_neuron_inputs),
-        value: None,
-    };
+}
+
+#[test]
+fn test_unset_inputs_read_as_zero() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let genome = Genome::simple_linear(&mut rng);
+    let (network, cells) = CpuNetwork::new(&genome);
+    let eye = &cells[&IVec2::new(0, 0)];
+    let mut values = vec![0.; network.len()];
+
+    eye.set(&mut values, 0, 1.);
+    network.clear_inputs(&mut values);
+    assert_eq!(values[eye.input_neurons()[0]], 0.);
+
+    // inputs are laid out before everything they feed
+    for neuron in 0..network.len() {
+        assert!(network.inputs(neuron).all(|(source, _)| source < neuron));
+    }
+}
+
+#[test]
+fn test_recurrent_connection_reads_last_tick() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut genome = Genome::simple_linear(&mut rng);
+    genome.set_cycle_mode(CycleMode::Recurrent);
 
-    CpuNeuron {
-        inner: Arc::new(RwLock::new(inner)),
+    // the hidden neuron adds its own last value to the eye's, counting up
+    let hidden = genome.hidden_neurons()[0];
+    genome.neurons.connect(hidden, hidden, 1.);
+    let ids = genome
+        .neurons()
+        .iter()
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    for id in ids {
+        let neuron = genome.neurons.get_mut(id).unwrap();
+        neuron.bias = 0.;
+        neuron.activation = Activation::Linear;
     }
+    Cleaner::new(&mut genome).clean();
+
+    let (network, cells) = CpuNetwork::new(&genome);
+    let eye = &cells[&IVec2::new(0, 0)];
+    let launcher = &cells[&IVec2::new(1, 0)];
+    let mut values = vec![0.; network.len()];
+
+    let eye_inputs = eye.input_neurons().len() as f32;
+    for tick in 1..=4 {
+        network.clear_inputs(&mut values);
+        for i in 0..eye.input_neurons().len() {
+            eye.set(&mut values, i, 1.);
+        }
+        network.process(&mut values);
+        assert_eq!(launcher.get(&values, 0), eye_inputs * tick as f32);
+    }
+
+    // the loop reports its real source rather than the delay slot
+    let hidden = network
+        .inputs(launcher.output_neurons()[0])
+        .next()
+        .unwrap()
+        .0;
+    assert!(network.inputs(hidden).any(|(source, _)| source == hidden));
+}
+
+#[test]
+fn test_recurrent_genomes_compile() {
+    let mut rng = StdRng::seed_from_u64(17);
+
+    for _ in 0..50 {
+        let mut genome = Genome::sandbox(&mut rng);
+        genome.set_cycle_mode(CycleMode::Recurrent);
+        for _ in 0..rng.random_range(0..30) {
+            genome.scramble(&mut rng);
+        }
+
+        let (network, _) = CpuNetwork::new(&genome);
+        let mut values = vec![0.; network.len()];
+        for _ in 0..5 {
+            network.process(&mut values);
+        }
+
+        // non-recurrent edges still only read neurons computed before them
+        let computed = network.ids.len();
+        for neuron in 0..computed {
+            for edge in neuron
+                .checked_sub(network.num_inputs)
+                .map_or(0..0, |i| network.edge_starts[i]..network.edge_starts[i + 1])
+            {
+                let source = network.edge_sources[edge];
+                assert!(source < neuron || source >= computed);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_disabled_connections_carry_no_signal() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+    let ids = genome
+        .neurons()
+        .iter()
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    for id in ids {
+        let neuron = genome.neurons.get_mut(id).unwrap();
+        neuron.bias = 0.;
+        neuron.activation = Activation::Linear;
+    }
+    // the first eye input is cut off from the hidden neuron
+    let cut = genome
+        .neurons()
+        .connections()
+        .iter()
+        .position(|c| c.to == hidden)
+        .unwrap();
+    genome.neurons.connection_mut(cut).enabled = false;
+
+    let (network, cells) = CpuNetwork::new(&genome);
+    let eye = &cells[&IVec2::new(0, 0)];
+    let launcher = &cells[&IVec2::new(1, 0)];
+    let mut values = vec![0.; network.len()];
+    for i in 0..eye.input_neurons().len() {
+        eye.set(&mut values, i, 1.);
+    }
+    network.process(&mut values);
+
+    let eye_inputs = eye.input_neurons().len() as f32;
+    assert_eq!(launcher.get(&values, 0), eye_inputs - 1.);
+    // the connection is still in the genome, to be enabled again
+    assert_eq!(
+        genome.neurons().inputs(hidden).count(),
+        eye.input_neurons().len()
+    );
+}
+
+#[test]
+fn test_deep_networks_build_without_recursion() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut genome = Genome::simple_linear(&mut rng);
+
+    // stretch the single hidden neuron into a long chain, looping back on itself
+    let first = genome.hidden_neurons()[0];
+    let mut last = first;
+    let outputs = genome
+        .cells()
+        .get(&IVec2::new(1, 0))
+        .unwrap()
+        .outputs
+        .clone();
+    genome.neurons.retain_connections(|c| c.from != first);
+    for _ in 0..50_000 {
+        let next = genome.add_hidden(&mut rng);
+        genome.neurons.connect(last, next, 1.);
+        last = next;
+    }
+    for output in outputs {
+        genome.neurons.connect(last, output, 1.);
+    }
+    genome.neurons.connect(last, first, 1.);
+    let ids = genome
+        .neurons()
+        .iter()
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    for id in ids {
+        let neuron = genome.neurons.get_mut(id).unwrap();
+        neuron.bias = 0.;
+        neuron.activation = Activation::Linear;
+    }
+
+    Cleaner::new(&mut genome).clean();
+    assert_eq!(genome.neurons().inputs(first).count(), 2);
+
+    let (network, cells) = CpuNetwork::new(&genome);
+    let mut values = vec![0.; network.len()];
+    for cell in cells.values() {
+        for i in 0..cell.input_neurons().len() {
+            cell.set(&mut values, i, 0.5);
+        }
+    }
+    network.process(&mut values);
+
+    // the eye's inputs are summed once, then passed down the chain unchanged
+    let eye_inputs = cells[&IVec2::new(0, 0)].input_neurons().len() as f32;
+    assert_eq!(cells[&IVec2::new(1, 0)].get(&values, 0), eye_inputs * 0.5);
 }
@@ -0,0 +1,197 @@
+//! The original recursive evaluator, kept to check [`super::CpuNetwork`] against.
+//!
+//! Only how it reads the genome has changed since, so keep evaluation here as it is, even
+//! where the network's own evaluator moves on.
+
+use std::sync::{Arc, RwLock};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::genome::{Genome, Incoming, NeuronArena, NeuronId, NeuronKind, activations::Activation};
+
+pub struct RecursiveCell {
+    inputs: Vec<CpuNeuron>,
+    outputs: Vec<CpuNeuron>,
+}
+
+impl RecursiveCell {
+    pub fn set(&self, index: usize, value: f32) {
+        let mut inner = self.inputs[index].inner.write().unwrap();
+        inner.value = Some(value);
+    }
+    pub fn get(&self, index: usize) -> f32 {
+        self.outputs[index].process()
+    }
+    pub fn reset(&self) {
+        for output in &self.outputs {
+            output.propagate_reset()
+        }
+    }
+}
+
+pub struct RecursiveNetwork {
+    pub cells: HashMap<IVec2, RecursiveCell>,
+}
+
+impl RecursiveNetwork {
+    pub fn new(genome: &Genome) -> Self {
+        let mut neuron_bank = HashMap::new();
+        let incoming = genome.neurons().enabled_incoming();
+
+        let mut output_map = HashMap::new();
+
+        for (cell, cell_genome) in genome.cells().map() {
+            let mut new_outputs = Vec::new();
+
+            for output_neuron in &cell_genome.outputs {
+                new_outputs.push(process_topology(
+                    *output_neuron,
+                    genome.neurons(),
+                    &incoming,
+                    &mut neuron_bank,
+                ));
+            }
+            output_map.insert(*cell, new_outputs);
+        }
+
+        let mut cells = HashMap::with_capacity(genome.cells().len());
+
+        for (cell_location, cell_genome) in genome.cells().map() {
+            let mut new_inputs = Vec::new();
+
+            for input_neuron in &cell_genome.inputs {
+                match neuron_bank.remove(input_neuron) {
+                    Some(neuron) => {
+                        new_inputs.push(neuron);
+                    }
+                    None => new_inputs.push(CpuNeuron::input()),
+                }
+            }
+            let outputs = output_map.remove(cell_location).unwrap();
+
+            cells.insert(
+                *cell_location,
+                RecursiveCell {
+                    inputs: new_inputs,
+                    outputs,
+                },
+            );
+        }
+        Self { cells }
+    }
+}
+
+fn process_topology(
+    neuron: NeuronId,
+    arena: &NeuronArena,
+    incoming: &Incoming,
+    neurons: &mut HashMap<NeuronId, CpuNeuron>,
+) -> CpuNeuron {
+    let mut cpu_inputs = Vec::new();
+
+    for edge in incoming.of(neuron) {
+        let input = &arena.connections()[*edge];
+        let id = input.from;
+        match neurons.get(&id) {
+            Some(neuron) => {
+                cpu_inputs.push((neuron.clone(), input.weight));
+            }
+            None => {
+                let new_neuron = if arena[id].kind == NeuronKind::Input {
+                    CpuNeuron::input()
+                } else {
+                    process_topology(id, arena, incoming, neurons)
+                };
+
+                cpu_inputs.push((new_neuron.clone(), input.weight));
+                neurons.insert(id, new_neuron);
+            }
+        }
+    }
+    let cpu_neuron_inputs = CpuNeuronInputs {
+        inputs: cpu_inputs,
+        bias: arena[neuron].bias,
+        activation: arena[neuron].activation,
+    };
+    let inner = CpuNeuronInner {
+        inputs: Some(cpu_neuron_inputs),
+        value: None,
+    };
+
+    CpuNeuron {
+        inner: Arc::new(RwLock::new(inner)),
+    }
+}
+
+pub struct CpuNeuronInputs {
+    pub inputs: Vec<(CpuNeuron, f32)>,
+    pub bias: f32,
+    pub activation: Activation,
+}
+
+pub struct CpuNeuronInner {
+    pub inputs: Option<CpuNeuronInputs>,
+    pub value: Option<f32>,
+}
+
+#[derive(Clone)]
+pub struct CpuNeuron {
+    inner: Arc<RwLock<CpuNeuronInner>>,
+}
+
+impl CpuNeuron {
+    pub fn input() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(CpuNeuronInner {
+                inputs: None,
+                value: None,
+            })),
+        }
+    }
+    pub fn propagate_reset(&self) {
+        {
+            let read_lock = self.inner.read().unwrap();
+            if read_lock.value.is_none() {
+                return;
+            }
+            if let Some(inputs) = &read_lock.inputs {
+                for (neuron, _) in &inputs.inputs {
+                    neuron.propagate_reset();
+                }
+            }
+        }
+
+        {
+            let mut write_lock = self.inner.write().unwrap();
+            write_lock.value = None
+        }
+    }
+    pub fn process(&self) -> f32 {
+        let determined_value = {
+            let read_lock = self.inner.read().unwrap();
+            if let Some(value) = read_lock.value {
+                return value;
+            }
+
+            let mut running_sum = 0.;
+
+            let Some(neuron_inputs) = &read_lock.inputs else {
+                return running_sum;
+            };
+
+            for (neuron, weight) in &neuron_inputs.inputs {
+                let value = neuron.process();
+                running_sum += value * *weight;
+            }
+
+            neuron_inputs.activation.apply(running_sum) + neuron_inputs.bias
+        };
+
+        {
+            let mut write_lock = self.inner.write().unwrap();
+            write_lock.value = Some(determined_value);
+        }
+
+        determined_value
+    }
+}
@@ -1,3 +1,146 @@
-use bevy::prelude::*;
+mod tile;
+pub use tile::*;
 
-pub(super) fn plugin(app: &mut App) {}
+mod world_grid;
+pub use world_grid::*;
+
+use bevy::{
+    color::palettes::tailwind::{GREEN_500, STONE_500},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    cell::{CellAssets, CellOf},
+    game::WorldRng,
+    organism::{OrganismSet, cell_position},
+};
+
+/// Tiles sit just behind organisms.
+const TILE_LAYER: f32 = -0.5;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct GridSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Food tiles spawned per second
+    pub food_spawn_rate: f32,
+    /// Food stops spawning once this many food tiles exist
+    pub max_food: usize,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            width: 120,
+            height: 80,
+            food_spawn_rate: 4.,
+            max_food: 400,
+        }
+    }
+}
+
+impl FromWorld for WorldGrid {
+    fn from_world(world: &mut World) -> Self {
+        let settings = world.resource::<GridSettings>();
+        WorldGrid::walled(settings.width, settings.height)
+    }
+}
+
+#[derive(Resource)]
+struct TileAssets {
+    food: Handle<ColorMaterial>,
+    wall: Handle<ColorMaterial>,
+}
+
+impl FromWorld for TileAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        Self {
+            food: materials.add(Color::from(GREEN_500)),
+            wall: materials.add(Color::from(STONE_500)),
+        }
+    }
+}
+
+/// The entity drawing each non-empty tile
+#[derive(Resource, Default)]
+struct TileVisuals(HashMap<IVec2, Entity>);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GridSettings>()
+        .init_resource::<WorldGrid>()
+        .init_resource::<TileAssets>()
+        .init_resource::<TileVisuals>();
+
+    app.add_systems(
+        FixedUpdate,
+        (spawn_food, index_cells).before(OrganismSet::ProcessInput),
+    );
+    app.add_systems(PostUpdate, sync_tile_visuals);
+}
+
+fn spawn_food(
+    mut grid: ResMut<WorldGrid>,
+    settings: Res<GridSettings>,
+    time: Res<Time>,
+    mut rng: ResMut<WorldRng>,
+    mut pending: Local<f32>,
+) {
+    *pending += settings.food_spawn_rate * time.delta_secs();
+    while *pending >= 1. {
+        *pending -= 1.;
+        if grid.food_count() >= settings.max_food {
+            continue;
+        }
+        if let Some(pos) = grid.random_free(&mut **rng) {
+            grid.set_tile(pos, Tile::Food);
+        }
+    }
+}
+
+/// Rebuilds the spatial index of which cell stands on which tile.
+fn index_cells(
+    mut grid: ResMut<WorldGrid>,
+    cells: Query<(Entity, &Transform, &CellOf)>,
+    organisms: Query<&Transform, Without<CellOf>>,
+) {
+    grid.clear_occupants();
+    for (cell, transform, cell_of) in &cells {
+        let Ok(organism) = organisms.get(cell_of.0) else {
+            continue;
+        };
+        let pos = grid.world_to_tile(cell_position(organism, transform));
+        grid.insert_occupant(pos, cell);
+    }
+}
+
+fn sync_tile_visuals(
+    mut commands: Commands,
+    mut grid: ResMut<WorldGrid>,
+    mut visuals: ResMut<TileVisuals>,
+    cell_assets: Res<CellAssets>,
+    tile_assets: Res<TileAssets>,
+) {
+    let changed: Vec<IVec2> = grid.drain_changed().collect();
+    for pos in changed {
+        if let Some(visual) = visuals.0.remove(&pos) {
+            commands.entity(visual).despawn();
+        }
+        let (name, material) = match grid.tile(pos) {
+            Tile::Empty => continue,
+            Tile::Food => ("Food", tile_assets.food.clone()),
+            Tile::Wall => ("Wall", tile_assets.wall.clone()),
+        };
+        let world = grid.tile_to_world(pos);
+        let visual = commands
+            .spawn((
+                Name::new(name),
+                Mesh2d(cell_assets.cell.clone()),
+                MeshMaterial2d(material),
+                Transform::from_xyz(world.x, world.y, TILE_LAYER),
+            ))
+            .id();
+        visuals.0.insert(pos, visual);
+    }
+}
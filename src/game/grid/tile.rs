@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+/// What occupies a single square of the world grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum Tile {
+    #[default]
+    Empty,
+    Food,
+    Wall,
+}
+
+impl Tile {
+    /// Whether an organism's cell can move onto this tile.
+    pub fn is_passable(self) -> bool {
+        !matches!(self, Tile::Wall)
+    }
+}
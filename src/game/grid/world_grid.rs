@@ -0,0 +1,312 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::Rng;
+
+use crate::game::grid::Tile;
+
+/// Number of random probes [`WorldGrid::random_free`] makes before giving up.
+const FREE_SPOT_ATTEMPTS: usize = 64;
+
+/// Distance between samples taken by [`WorldGrid::raycast`], in tiles.
+const RAY_STEP: f32 = 0.25;
+
+/// What a ray ran into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RayTarget {
+    Tile(Tile),
+    Cell(Entity),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub distance: f32,
+    pub pos: IVec2,
+    pub target: RayTarget,
+}
+
+/// The bounded arena organisms live in.
+///
+/// Tiles are one world unit wide and centered on integer coordinates, so a tile
+/// lines up with the cells of an organism. The arena is centered on the origin.
+#[derive(Resource)]
+pub struct WorldGrid {
+    width: u32,
+    height: u32,
+    tiles: Vec<Tile>,
+    /// Which organism cell sits on a tile. Rebuilt every tick.
+    occupants: HashMap<IVec2, Entity>,
+    /// Tiles changed since the visuals last caught up.
+    changed: Vec<IVec2>,
+    food: usize,
+}
+
+impl WorldGrid {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Tile::Empty; (width * height) as usize],
+            occupants: HashMap::new(),
+            changed: Vec::new(),
+            food: 0,
+        }
+    }
+
+    /// Creates an arena with a wall around its border.
+    pub fn walled(width: u32, height: u32) -> Self {
+        let mut grid = Self::new(width, height);
+        let (min, max) = (grid.min(), grid.max());
+        for x in min.x..=max.x {
+            grid.set_tile(IVec2::new(x, min.y), Tile::Wall);
+            grid.set_tile(IVec2::new(x, max.y), Tile::Wall);
+        }
+        for y in min.y..=max.y {
+            grid.set_tile(IVec2::new(min.x, y), Tile::Wall);
+            grid.set_tile(IVec2::new(max.x, y), Tile::Wall);
+        }
+        grid
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The bottom left tile of the arena
+    pub fn min(&self) -> IVec2 {
+        -IVec2::new(self.width as i32 / 2, self.height as i32 / 2)
+    }
+    /// The top right tile of the arena (inclusive)
+    pub fn max(&self) -> IVec2 {
+        self.min() + IVec2::new(self.width as i32 - 1, self.height as i32 - 1)
+    }
+
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        let (min, max) = (self.min(), self.max());
+        pos.x >= min.x && pos.y >= min.y && pos.x <= max.x && pos.y <= max.y
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        if !self.in_bounds(pos) {
+            return None;
+        }
+        let local = pos - self.min();
+        Some(local.y as usize * self.width as usize + local.x as usize)
+    }
+
+    /// Anything outside of the arena is treated as a wall.
+    pub fn tile(&self, pos: IVec2) -> Tile {
+        self.index(pos).map(|i| self.tiles[i]).unwrap_or(Tile::Wall)
+    }
+
+    /// Returns false if `pos` is out of bounds.
+    pub fn set_tile(&mut self, pos: IVec2, tile: Tile) -> bool {
+        let Some(i) = self.index(pos) else {
+            return false;
+        };
+        let previous = std::mem::replace(&mut self.tiles[i], tile);
+        if previous == tile {
+            return true;
+        }
+        if previous == Tile::Food {
+            self.food -= 1;
+        }
+        if tile == Tile::Food {
+            self.food += 1;
+        }
+        self.changed.push(pos);
+        true
+    }
+
+    pub fn food_count(&self) -> usize {
+        self.food
+    }
+
+    pub fn world_to_tile(&self, world: Vec2) -> IVec2 {
+        world.round().as_ivec2()
+    }
+    pub fn tile_to_world(&self, pos: IVec2) -> Vec2 {
+        pos.as_vec2()
+    }
+
+    /// The organism cell currently standing on `pos`.
+    pub fn occupant(&self, pos: IVec2) -> Option<Entity> {
+        self.occupants.get(&pos).copied()
+    }
+
+    /// An empty tile that no cell is standing on.
+    pub fn is_free(&self, pos: IVec2) -> bool {
+        self.tile(pos) == Tile::Empty && self.occupant(pos).is_none()
+    }
+
+    /// Every tile within `radius` (chebyshev distance) of `center`, excluding `center` itself.
+    pub fn neighbourhood(
+        &self,
+        center: IVec2,
+        radius: i32,
+    ) -> impl Iterator<Item = (IVec2, Tile, Option<Entity>)> + '_ {
+        (-radius..=radius)
+            .flat_map(move |y| (-radius..=radius).map(move |x| center + IVec2::new(x, y)))
+            .filter(move |pos| *pos != center && self.in_bounds(*pos))
+            .map(|pos| (pos, self.tile(pos), self.occupant(pos)))
+    }
+
+    /// Walks from `origin` along `direction` until it hits food, a wall or a cell.
+    ///
+    /// Cells for which `ignore` returns true are looked through.
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        range: f32,
+        ignore: impl Fn(Entity) -> bool,
+    ) -> Option<RayHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return None;
+        }
+        let start = self.world_to_tile(origin);
+        let mut last = start;
+        let mut distance = RAY_STEP;
+        while distance <= range {
+            let pos = self.world_to_tile(origin + direction * distance);
+            if pos != last {
+                last = pos;
+                let target = match (self.tile(pos), self.occupant(pos)) {
+                    (Tile::Empty, Some(cell)) if !ignore(cell) => Some(RayTarget::Cell(cell)),
+                    (Tile::Empty, _) => None,
+                    (tile, _) => Some(RayTarget::Tile(tile)),
+                };
+                if let Some(target) = target {
+                    return Some(RayHit {
+                        distance,
+                        pos,
+                        target,
+                    });
+                }
+            }
+            distance += RAY_STEP;
+        }
+        None
+    }
+
+    pub fn random_free(&self, rng: &mut impl Rng) -> Option<IVec2> {
+        let (min, max) = (self.min(), self.max());
+        (0..FREE_SPOT_ATTEMPTS)
+            .map(|_| {
+                IVec2::new(
+                    rng.random_range(min.x..=max.x),
+                    rng.random_range(min.y..=max.y),
+                )
+            })
+            .find(|pos| self.is_free(*pos))
+    }
+
+    pub(super) fn clear_occupants(&mut self) {
+        self.occupants.clear();
+    }
+    pub(crate) fn insert_occupant(&mut self, pos: IVec2, cell: Entity) {
+        if self.in_bounds(pos) {
+            self.occupants.insert(pos, cell);
+        }
+    }
+
+    pub(super) fn drain_changed(&mut self) -> std::vec::Drain<'_, IVec2> {
+        self.changed.drain(..)
+    }
+}
+
+#[cfg(test)]
+use {
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_grid_bounds_are_centered() {
+    let grid = WorldGrid::new(10, 6);
+    assert_eq!(grid.min(), IVec2::new(-5, -3));
+    assert_eq!(grid.max(), IVec2::new(4, 2));
+    assert!(grid.in_bounds(IVec2::ZERO));
+    assert!(!grid.in_bounds(IVec2::new(5, 0)));
+    assert_eq!(grid.tile(IVec2::new(5, 0)), Tile::Wall);
+}
+
+#[test]
+fn test_walled_grid_has_border() {
+    let grid = WorldGrid::walled(8, 8);
+    assert_eq!(grid.tile(grid.min()), Tile::Wall);
+    assert_eq!(grid.tile(grid.max()), Tile::Wall);
+    assert_eq!(grid.tile(IVec2::new(0, grid.max().y)), Tile::Wall);
+    assert_eq!(grid.tile(IVec2::ZERO), Tile::Empty);
+}
+
+#[test]
+fn test_food_count_tracks_tiles() {
+    let mut grid = WorldGrid::new(8, 8);
+    grid.set_tile(IVec2::ZERO, Tile::Food);
+    grid.set_tile(IVec2::ZERO, Tile::Food);
+    grid.set_tile(IVec2::ONE, Tile::Food);
+    assert_eq!(grid.food_count(), 2);
+
+    grid.set_tile(IVec2::ZERO, Tile::Wall);
+    assert_eq!(grid.food_count(), 1);
+    assert_eq!(grid.drain_changed().count(), 3);
+}
+
+#[test]
+fn test_neighbourhood_reports_occupants() {
+    let mut grid = WorldGrid::new(8, 8);
+    let cell = Entity::from_raw_u32(7).unwrap();
+    grid.insert_occupant(IVec2::new(1, 0), cell);
+    grid.set_tile(IVec2::new(-1, -1), Tile::Food);
+
+    let around: Vec<_> = grid.neighbourhood(IVec2::ZERO, 1).collect();
+    assert_eq!(around.len(), 8);
+    assert!(around.contains(&(IVec2::new(1, 0), Tile::Empty, Some(cell))));
+    assert!(around.contains(&(IVec2::new(-1, -1), Tile::Food, None)));
+    assert!(!grid.is_free(IVec2::new(1, 0)));
+}
+
+#[test]
+fn test_random_free_skips_walls() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let grid = WorldGrid::walled(4, 4);
+    for _ in 0..20 {
+        let pos = grid.random_free(&mut rng).unwrap();
+        assert_eq!(grid.tile(pos), Tile::Empty);
+    }
+}
+
+#[test]
+fn test_raycast_hits_nearest_tile() {
+    let mut grid = WorldGrid::walled(20, 20);
+    grid.set_tile(IVec2::new(4, 0), Tile::Food);
+
+    let hit = grid.raycast(Vec2::ZERO, Vec2::X, 20., |_| false).unwrap();
+    assert_eq!(hit.pos, IVec2::new(4, 0));
+    assert_eq!(hit.target, RayTarget::Tile(Tile::Food));
+
+    let hit = grid
+        .raycast(Vec2::ZERO, Vec2::NEG_X, 20., |_| false)
+        .unwrap();
+    assert_eq!(hit.target, RayTarget::Tile(Tile::Wall));
+    assert_eq!(hit.pos, IVec2::new(-10, 0));
+
+    assert_eq!(grid.raycast(Vec2::ZERO, Vec2::X, 3., |_| false), None);
+}
+
+#[test]
+fn test_raycast_looks_through_ignored_cells() {
+    let mut grid = WorldGrid::new(20, 20);
+    let own = Entity::from_raw_u32(1).unwrap();
+    let other = Entity::from_raw_u32(2).unwrap();
+    grid.insert_occupant(IVec2::new(1, 0), own);
+    grid.insert_occupant(IVec2::new(3, 0), other);
+
+    let hit = grid
+        .raycast(Vec2::ZERO, Vec2::X, 10., |cell| cell == own)
+        .unwrap();
+    assert_eq!(hit.target, RayTarget::Cell(other));
+}
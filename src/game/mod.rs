@@ -1,4 +1,5 @@
-mod grid;
+pub mod grid;
+pub mod projectile;
 mod ui;
 
 use bevy::prelude::*;
@@ -6,12 +7,47 @@ use rand::{SeedableRng, rngs::StdRng};
 
 use crate::{genome::Genome, organism::SpawnOrganism};
 
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct SimulationSettings {
+    /// The same seed and starting genome always play out the same way.
+    pub seed: u64,
+    pub ticks_per_second: f64,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            seed: 18912,
+            ticks_per_second: 60.,
+        }
+    }
+}
+
+/// The only source of randomness the simulation may use.
+#[derive(Resource, Deref, DerefMut)]
+pub struct WorldRng(StdRng);
+
+impl WorldRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((grid::plugin, ui::plugin));
+    // inserted before the plugin is added to pick another seed or tick rate
+    let settings = app
+        .world()
+        .get_resource::<SimulationSettings>()
+        .cloned()
+        .unwrap_or_default();
+    app.insert_resource(Time::<Fixed>::from_hz(settings.ticks_per_second))
+        .insert_resource(WorldRng::from_seed(settings.seed))
+        .insert_resource(settings);
+
+    app.add_plugins((grid::plugin, projectile::plugin, ui::plugin));
     app.add_systems(Startup, spawn_first_organism);
 }
 
-fn spawn_first_organism(mut msgs: MessageWriter<SpawnOrganism>) {
-    let mut rng = StdRng::seed_from_u64(18912);
-    msgs.write(SpawnOrganism::new(Genome::sandbox(&mut rng), Vec2::ZERO));
+fn spawn_first_organism(mut msgs: MessageWriter<SpawnOrganism>, mut rng: ResMut<WorldRng>) {
+    msgs.write(SpawnOrganism::new(Genome::sandbox(&mut **rng), Vec2::ZERO));
 }
@@ -0,0 +1,292 @@
+use bevy::{color::palettes::tailwind::ORANGE_400, prelude::*};
+
+use crate::{
+    cell::{CellHealth, CellOf},
+    game::grid::{Tile, WorldGrid},
+    organism::OrganismSet,
+};
+
+/// Projectiles fly above tiles and organisms.
+const PROJECTILE_LAYER: f32 = 0.5;
+const PROJECTILE_RADIUS: f32 = 0.2;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ProjectileSettings>()
+        .init_resource::<ProjectileAssets>();
+    app.add_message::<FireProjectile>();
+
+    app.add_systems(
+        FixedUpdate,
+        (spawn_projectiles, move_projectiles, kill_cells)
+            .chain()
+            .after(OrganismSet::ProcessOutput),
+    );
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct ProjectileSettings {
+    /// Tiles per second
+    pub speed: f32,
+    /// Tiles a projectile travels before it fizzles out
+    pub range: f32,
+    /// Health taken from a cell that gets hit. Cells start with `1` health.
+    pub damage: f32,
+}
+
+impl Default for ProjectileSettings {
+    fn default() -> Self {
+        Self {
+            speed: 12.,
+            range: 15.,
+            damage: 0.5,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct ProjectileAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for ProjectileAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Circle::new(PROJECTILE_RADIUS));
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::from(ORANGE_400));
+        Self { mesh, material }
+    }
+}
+
+#[derive(Message)]
+pub struct FireProjectile {
+    /// The organism that fired. Its own cells are never hit.
+    pub owner: Entity,
+    pub origin: Vec2,
+    pub direction: Vec2,
+}
+
+#[derive(Component, Reflect)]
+pub struct Projectile {
+    owner: Entity,
+    velocity: Vec2,
+    /// Tiles left to travel
+    remaining: f32,
+}
+
+/// Where a projectile is after a step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flight {
+    Flying,
+    /// Struck a cell of another organism
+    Hit(Entity),
+    /// Ran into a wall, or out of range
+    Spent,
+}
+
+impl Projectile {
+    /// A projectile fired by `owner`, or `None` if `direction` is zero.
+    pub fn new(owner: Entity, direction: Vec2, settings: &ProjectileSettings) -> Option<Self> {
+        let direction = direction.try_normalize()?;
+        Some(Self {
+            owner,
+            velocity: direction * settings.speed,
+            remaining: settings.range,
+        })
+    }
+
+    /// Moves `position` along for `delta` seconds, and reports what it ran into.
+    ///
+    /// `organism_of` gives the organism a cell belongs to, so the owner's cells are flown
+    /// through.
+    pub fn advance(
+        &mut self,
+        position: &mut Vec2,
+        delta: f32,
+        grid: &WorldGrid,
+        organism_of: impl Fn(Entity) -> Option<Entity>,
+    ) -> Flight {
+        let step = self.velocity * delta;
+        *position += step;
+        self.remaining -= step.length();
+
+        let pos = grid.world_to_tile(*position);
+        match (grid.tile(pos), grid.occupant(pos)) {
+            (Tile::Wall, _) => return Flight::Spent,
+            (_, Some(cell)) if organism_of(cell).is_some_and(|of| of != self.owner) => {
+                return Flight::Hit(cell);
+            }
+            _ => {}
+        }
+        if self.remaining <= 0. {
+            Flight::Spent
+        } else {
+            Flight::Flying
+        }
+    }
+}
+
+fn spawn_projectiles(
+    mut commands: Commands,
+    mut msgs: MessageReader<FireProjectile>,
+    settings: Res<ProjectileSettings>,
+    assets: Res<ProjectileAssets>,
+) {
+    for msg in msgs.read() {
+        let Some(projectile) = Projectile::new(msg.owner, msg.direction, &settings) else {
+            continue;
+        };
+        commands.spawn((
+            Name::new("Projectile"),
+            projectile,
+            Mesh2d(assets.mesh.clone()),
+            MeshMaterial2d(assets.material.clone()),
+            Transform::from_xyz(msg.origin.x, msg.origin.y, PROJECTILE_LAYER),
+        ));
+    }
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    projectiles: Query<(Entity, &mut Projectile, &mut Transform)>,
+    mut cells: Query<(&CellOf, &mut CellHealth)>,
+    grid: Res<WorldGrid>,
+    settings: Res<ProjectileSettings>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut projectile, mut transform) in projectiles {
+        let mut position = transform.translation.xy();
+        let flight = projectile.advance(&mut position, delta, &grid, |cell| {
+            cells.get(cell).ok().map(|(cell_of, _)| cell_of.0)
+        });
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        match flight {
+            Flight::Flying => continue,
+            Flight::Hit(cell) => {
+                if let Ok((_, mut health)) = cells.get_mut(cell) {
+                    health.hurt(settings.damage);
+                }
+            }
+            Flight::Spent => {}
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+fn kill_cells(mut commands: Commands, cells: Query<(Entity, &CellHealth), Changed<CellHealth>>) {
+    for (cell, health) in cells {
+        if health.is_dead() {
+            commands.entity(cell).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+use {bevy::ecs::system::RunSystemOnce, pretty_assertions::assert_eq};
+
+#[cfg(test)]
+fn entity(index: u32) -> Entity {
+    Entity::from_raw_u32(index).unwrap()
+}
+
+#[test]
+fn test_projectiles_fly_straight_and_fizzle_out() {
+    let grid = WorldGrid::new(100, 100);
+    let settings = ProjectileSettings {
+        speed: 2.,
+        range: 3.,
+        damage: 0.5,
+    };
+    let mut projectile = Projectile::new(entity(1), Vec2::new(0., 5.), &settings).unwrap();
+    let mut position = Vec2::ZERO;
+
+    assert_eq!(
+        projectile.advance(&mut position, 1., &grid, |_| None),
+        Flight::Flying
+    );
+    assert_eq!(position, Vec2::new(0., 2.));
+    assert_eq!(
+        projectile.advance(&mut position, 1., &grid, |_| None),
+        Flight::Spent
+    );
+
+    assert!(Projectile::new(entity(1), Vec2::ZERO, &settings).is_none());
+}
+
+#[test]
+fn test_projectiles_stop_at_walls() {
+    let grid = WorldGrid::walled(8, 8);
+    let settings = ProjectileSettings::default();
+    let mut projectile = Projectile::new(entity(1), Vec2::X, &settings).unwrap();
+    let mut position = Vec2::ZERO;
+
+    let mut flight = Flight::Flying;
+    let mut steps = 0;
+    while flight == Flight::Flying {
+        flight = projectile.advance(&mut position, 0.05, &grid, |_| None);
+        steps += 1;
+    }
+    assert_eq!(flight, Flight::Spent);
+    assert_eq!(grid.tile(grid.world_to_tile(position)), Tile::Wall);
+    assert!(
+        steps < 20,
+        "The wall comes well before the end of the range"
+    );
+}
+
+#[test]
+fn test_projectiles_hit_other_organisms_only() {
+    let mut grid = WorldGrid::new(20, 20);
+    let (owner, other) = (entity(1), entity(2));
+    let (own_cell, other_cell) = (entity(10), entity(20));
+    grid.insert_occupant(IVec2::new(1, 0), own_cell);
+    grid.insert_occupant(IVec2::new(2, 0), other_cell);
+    let organism_of = |cell: Entity| match cell {
+        cell if cell == own_cell => Some(owner),
+        cell if cell == other_cell => Some(other),
+        _ => None,
+    };
+    let settings = ProjectileSettings {
+        speed: 1.,
+        ..default()
+    };
+    let mut projectile = Projectile::new(owner, Vec2::X, &settings).unwrap();
+    let mut position = Vec2::ZERO;
+
+    // straight through its own cell
+    assert_eq!(
+        projectile.advance(&mut position, 1., &grid, organism_of),
+        Flight::Flying
+    );
+    assert_eq!(
+        projectile.advance(&mut position, 1., &grid, organism_of),
+        Flight::Hit(other_cell)
+    );
+}
+
+#[test]
+fn test_hits_hurt_until_the_cell_dies() {
+    let damage = ProjectileSettings::default().damage;
+    let mut health = CellHealth::default();
+    assert!(!health.hurt(damage));
+    assert!(health.hurt(damage));
+    assert!(health.is_dead());
+}
+
+#[test]
+fn test_dead_cells_are_despawned() {
+    let mut world = World::new();
+    let alive = world.spawn(CellHealth(0.5)).id();
+    let dead = world.spawn(CellHealth(0.)).id();
+
+    world.run_system_once(kill_cells).unwrap();
+
+    assert!(world.get_entity(alive).is_ok());
+    assert!(world.get_entity(dead).is_err());
+}
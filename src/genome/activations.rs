@@ -1,13 +1,142 @@
-use rand::Rng;
+use std::fmt;
+
+use rand::{Rng, seq::IteratorRandom};
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
 
 pub fn random_bias(rng: &mut impl Rng) -> f32 {
     rng.random_range(-1_f32..=1_f32)
 }
-pub fn random_activation(rng: &mut impl Rng) -> fn(f32) -> f32 {
-    match rng.random_range(0..3) {
-        0 => sigmoid,
-        1 => relu,
-        _ => linear_activation,
+
+pub fn random_weight(rng: &mut impl Rng) -> f32 {
+    rng.random_range(-1_f32..=1_f32)
+}
+
+/// Draws from every activation function.
+pub fn random_activation(rng: &mut impl Rng) -> Activation {
+    ActivationSet::all().random(rng)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, EnumCount, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    Sigmoid,
+    Relu,
+    Linear,
+    Tanh,
+    Gaussian,
+    Sine,
+    Step,
+    Abs,
+    /// Linear, but clamped to `-1..=1`
+    IdentityClamped,
+}
+
+impl Activation {
+    pub fn apply(self, n: f32) -> f32 {
+        match self {
+            Self::Sigmoid => sigmoid(n),
+            Self::Relu => relu(n),
+            Self::Linear => linear_activation(n),
+            Self::Tanh => n.tanh(),
+            Self::Gaussian => (-n * n).exp(),
+            Self::Sine => n.sin(),
+            Self::Step => {
+                if n > 0. {
+                    1.
+                } else {
+                    0.
+                }
+            }
+            Self::Abs => n.abs(),
+            Self::IdentityClamped => n.clamp(-1., 1.),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sigmoid => "sigmoid",
+            Self::Relu => "relu",
+            Self::Linear => "linear",
+            Self::Tanh => "tanh",
+            Self::Gaussian => "gaussian",
+            Self::Sine => "sine",
+            Self::Step => "step",
+            Self::Abs => "abs",
+            Self::IdentityClamped => "identity_clamped",
+        }
+    }
+
+    const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+impl fmt::Display for Activation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The activation functions a genome is allowed to draw from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<Activation>", into = "Vec<Activation>")]
+pub struct ActivationSet(u16);
+
+impl ActivationSet {
+    pub const fn none() -> Self {
+        Self(0)
+    }
+    pub fn all() -> Self {
+        Activation::iter().collect()
+    }
+    pub fn contains(&self, activation: Activation) -> bool {
+        self.0 & activation.bit() != 0
+    }
+    pub fn insert(&mut self, activation: Activation) {
+        self.0 |= activation.bit();
+    }
+    pub fn remove(&mut self, activation: Activation) {
+        self.0 &= !activation.bit();
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Activation> + '_ {
+        Activation::iter().filter(|activation| self.contains(*activation))
+    }
+
+    /// Falls back to [`Activation::Linear`] if the set is empty.
+    pub fn random(&self, rng: &mut impl Rng) -> Activation {
+        self.iter().choose(rng).unwrap_or(Activation::Linear)
+    }
+}
+
+impl Default for ActivationSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromIterator<Activation> for ActivationSet {
+    fn from_iter<T: IntoIterator<Item = Activation>>(iter: T) -> Self {
+        let mut set = Self::none();
+        for activation in iter {
+            set.insert(activation);
+        }
+        set
+    }
+}
+
+impl From<Vec<Activation>> for ActivationSet {
+    fn from(value: Vec<Activation>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl From<ActivationSet> for Vec<Activation> {
+    fn from(value: ActivationSet) -> Self {
+        value.iter().collect()
     }
 }
 
@@ -22,3 +151,46 @@ pub fn relu(n: f32) -> f32 {
 pub fn linear_activation(n: f32) -> f32 {
     n
 }
+
+#[cfg(test)]
+use rand::{SeedableRng, rngs::StdRng};
+
+#[test]
+fn test_activation_set_only_draws_members() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let set: ActivationSet = [Activation::Tanh, Activation::Step].into_iter().collect();
+
+    for _ in 0..100 {
+        let drawn = set.random(&mut rng);
+        assert!(matches!(drawn, Activation::Tanh | Activation::Step));
+    }
+}
+
+#[test]
+fn test_empty_activation_set_falls_back_to_linear() {
+    let mut rng = StdRng::seed_from_u64(42);
+    assert_eq!(ActivationSet::none().random(&mut rng), Activation::Linear);
+}
+
+#[test]
+fn test_activation_set_covers_every_activation() {
+    let all = ActivationSet::all();
+    assert_eq!(all.iter().count(), Activation::COUNT);
+
+    let mut set = all;
+    set.remove(Activation::Sine);
+    assert!(!set.contains(Activation::Sine));
+    assert!(set.contains(Activation::Sigmoid));
+}
+
+#[test]
+fn test_activation_values() {
+    assert_eq!(Activation::Relu.apply(-2.), 0.);
+    assert_eq!(Activation::Step.apply(0.5), 1.);
+    assert_eq!(Activation::Step.apply(-0.5), 0.);
+    assert_eq!(Activation::Abs.apply(-3.), 3.);
+    assert_eq!(Activation::IdentityClamped.apply(4.), 1.);
+    assert_eq!(Activation::IdentityClamped.apply(-4.), -1.);
+    assert_eq!(Activation::Gaussian.apply(0.), 1.);
+    assert!((Activation::Sigmoid.apply(0.) - 0.5).abs() < 0.001);
+}
@@ -1,9 +1,12 @@
-use bevy::{math::IVec2, platform::collections::HashMap};
+use bevy::{
+    math::IVec2,
+    platform::collections::{HashMap, HashSet},
+};
 use rand::{Rng, seq::SliceRandom};
 
 use crate::{
     cell::{CellGenome, CellKind, CellRequirements},
-    genome::{Direction, NeuronTopology},
+    genome::{Direction, Innovation, Neuron, NeuronArena, NeuronId, activations::ActivationSet},
 };
 
 #[derive(Default, Clone, Debug)]
@@ -24,11 +27,20 @@ impl CellMap {
     pub fn map_mut(&mut self) -> &mut HashMap<IVec2, CellGenome> {
         &mut self.0
     }
+    /// An empty spot touching the body, or the origin if there's no body yet.
     pub fn find_free_spot(&self, rng: &mut impl Rng) -> IVec2 {
         let mut cursor = IVec2::ZERO;
 
         if self.get(&cursor).is_none() {
-            return cursor;
+            if self.is_empty() || self.touches_body(cursor) {
+                return cursor;
+            }
+            // the origin's cell is gone, so walk out from the one closest to it instead
+            cursor = *self
+                .0
+                .keys()
+                .min_by_key(|location| (location.length_squared(), location.x, location.y))
+                .unwrap();
         }
 
         let mut directions = Direction::random_order(rng);
@@ -45,6 +57,57 @@ impl CellMap {
         }
     }
 
+    fn touches_body(&self, location: IVec2) -> bool {
+        Direction::all()
+            .iter()
+            .any(|direction| self.0.contains_key(&(location + direction.vec())))
+    }
+
+    /// Groups the cells into pieces that touch along a [`Direction`].
+    ///
+    /// Pieces are ordered by their first cell, and their cells by `x`, then `y`.
+    pub fn components(&self) -> Vec<Vec<IVec2>> {
+        components_of(self.0.keys().copied())
+    }
+
+    /// Whether the body is a single piece. An empty body counts as one.
+    pub fn is_contiguous(&self) -> bool {
+        self.components().len() <= 1
+    }
+
+    /// Whether removing the cell at `location` would break its piece of the body in two.
+    pub fn would_split(&self, location: IVec2) -> bool {
+        let neighbours: Vec<IVec2> = Direction::all()
+            .iter()
+            .map(|direction| location + direction.vec())
+            .filter(|next| self.0.contains_key(next))
+            .collect();
+        let Some(start) = neighbours.first() else {
+            return false;
+        };
+
+        let mut seen = HashSet::from([location, *start]);
+        let mut stack = vec![*start];
+        while let Some(current) = stack.pop() {
+            for direction in Direction::all() {
+                let next = current + direction.vec();
+                if self.0.contains_key(&next) && seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        !neighbours.iter().all(|neighbour| seen.contains(neighbour))
+    }
+
+    /// Any cell, picked uniformly.
+    pub fn random_location(&self, rng: &mut impl Rng) -> Option<IVec2> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let index = rng.random_range(0..self.0.len());
+        self.0.keys().nth(index).copied()
+    }
+
     pub fn num_inputs_outputs(&self) -> (usize, usize) {
         self.map().values().fold((0_usize, 0_usize), |acc, val| {
             (acc.0 + val.inputs.len(), acc.1 + val.outputs.len())
@@ -54,37 +117,155 @@ impl CellMap {
     pub fn get(&self, loc: &IVec2) -> Option<&CellGenome> {
         self.0.get(loc)
     }
-    // does not check if a cell is here.
+    /// Adds a cell with fresh neurons in `neurons`.
+    ///
+    /// A cell already at `location` is replaced, and its neurons are removed.
     pub fn add_cell(
         &mut self,
         location: IVec2,
         cell_kind: CellKind,
+        neurons: &mut NeuronArena,
+        rng: &mut impl Rng,
+    ) -> Option<CellGenome> {
+        self.add_cell_with(location, cell_kind, neurons, ActivationSet::all(), rng)
+    }
+    /// [`CellMap::add_cell`], with the output activations drawn from `activations`.
+    pub fn add_cell_with(
+        &mut self,
+        location: IVec2,
+        cell_kind: CellKind,
+        neurons: &mut NeuronArena,
+        activations: ActivationSet,
         rng: &mut impl Rng,
     ) -> Option<CellGenome> {
-        let mut cell_inputs = Vec::new();
-        let mut cell_outputs = Vec::new();
         let CellRequirements {
             num_inputs,
             num_outputs,
         } = cell_kind.requirements();
-        for _ in 0..num_inputs {
-            let new_input = NeuronTopology::input();
-            cell_inputs.push(new_input.clone());
-        }
-
-        for _ in 0..num_outputs {
-            let new_output = NeuronTopology::output(rng);
-            cell_outputs.push(new_output.clone());
-        }
+        let cell_inputs = (0..num_inputs)
+            .map(|slot| cell_input(location, cell_kind, slot, neurons))
+            .collect();
+        let cell_outputs = (0..num_outputs)
+            .map(|slot| cell_output(location, cell_kind, slot, neurons, activations, rng))
+            .collect();
 
         let cell = CellGenome {
             kind: cell_kind,
             inputs: cell_inputs,
             outputs: cell_outputs,
         };
-        self.0.insert(location, cell)
+        let replaced = self.0.insert(location, cell);
+        if let Some(replaced) = &replaced {
+            replaced.remove_neurons(neurons);
+        }
+        replaced
     }
-    pub fn remove(&mut self, loc: &IVec2) -> Option<CellGenome> {
-        self.0.remove(loc)
+    /// Turns the cell at `location` into a `cell_kind`, returning the kind it was.
+    ///
+    /// Input and output slots the new kind still has keep their neurons, and with them their
+    /// connections. Slots it doesn't have are removed, and slots it adds start unconnected.
+    /// Kept neurons and their connections are re-marked as if a `cell_kind` had been added
+    /// here, so crossover lines them up with one.
+    pub fn change_kind(
+        &mut self,
+        location: IVec2,
+        cell_kind: CellKind,
+        neurons: &mut NeuronArena,
+        activations: ActivationSet,
+        rng: &mut impl Rng,
+    ) -> Option<CellKind> {
+        let cell = self.0.get_mut(&location)?;
+        let CellRequirements {
+            num_inputs,
+            num_outputs,
+        } = cell_kind.requirements();
+
+        for id in cell.inputs.drain(num_inputs.min(cell.inputs.len())..) {
+            neurons.remove(id);
+        }
+        for (slot, id) in cell.inputs.iter().enumerate() {
+            neurons.remark(
+                *id,
+                Innovation::cell_neuron(location, cell_kind, false, slot),
+            );
+        }
+        for slot in cell.inputs.len()..num_inputs {
+            cell.inputs
+                .push(cell_input(location, cell_kind, slot, neurons));
+        }
+        for id in cell.outputs.drain(num_outputs.min(cell.outputs.len())..) {
+            neurons.remove(id);
+        }
+        for (slot, id) in cell.outputs.iter().enumerate() {
+            neurons.remark(
+                *id,
+                Innovation::cell_neuron(location, cell_kind, true, slot),
+            );
+        }
+        for slot in cell.outputs.len()..num_outputs {
+            cell.outputs.push(cell_output(
+                location,
+                cell_kind,
+                slot,
+                neurons,
+                activations,
+                rng,
+            ));
+        }
+
+        Some(std::mem::replace(&mut cell.kind, cell_kind))
+    }
+    /// Removes the cell at `loc` along with its neurons.
+    pub fn remove(&mut self, loc: &IVec2, neurons: &mut NeuronArena) -> Option<CellGenome> {
+        let removed = self.0.remove(loc)?;
+        removed.remove_neurons(neurons);
+        Some(removed)
+    }
+}
+
+/// Groups `locations` into pieces that touch along a [`Direction`], like
+/// [`CellMap::components`].
+pub fn components_of(locations: impl IntoIterator<Item = IVec2>) -> Vec<Vec<IVec2>> {
+    let mut locations: Vec<_> = locations.into_iter().collect();
+    locations.sort_by_key(|location| (location.x, location.y));
+    let filled: HashSet<IVec2> = locations.iter().copied().collect();
+
+    let mut seen = HashSet::new();
+    let mut pieces = Vec::new();
+    for start in locations {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut piece = vec![start];
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            for direction in Direction::all() {
+                let next = current + direction.vec();
+                if filled.contains(&next) && seen.insert(next) {
+                    piece.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        piece.sort_by_key(|location| (location.x, location.y));
+        pieces.push(piece);
     }
+    pieces
+}
+
+fn cell_input(location: IVec2, kind: CellKind, slot: usize, neurons: &mut NeuronArena) -> NeuronId {
+    let innovation = Innovation::cell_neuron(location, kind, false, slot);
+    neurons.insert(Neuron::input().with_innovation(innovation))
+}
+
+fn cell_output(
+    location: IVec2,
+    kind: CellKind,
+    slot: usize,
+    neurons: &mut NeuronArena,
+    activations: ActivationSet,
+    rng: &mut impl Rng,
+) -> NeuronId {
+    let innovation = Innovation::cell_neuron(location, kind, true, slot);
+    neurons.insert(Neuron::output(activations, rng).with_innovation(innovation))
 }
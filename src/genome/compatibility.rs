@@ -0,0 +1,200 @@
+use bevy::reflect::Reflect;
+
+use crate::genome::{
+    Genome,
+    crossover::{Gene, align},
+};
+
+/// How much each kind of difference counts towards [`Genome::distance`].
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct Compatibility {
+    /// Per share of positions only one genome has a cell at
+    pub layout: f32,
+    /// Per share of positions where both have a cell, but of different kinds
+    pub cell_kinds: f32,
+    /// Per share of excess connections
+    pub excess: f32,
+    /// Per share of disjoint connections
+    pub disjoint: f32,
+    /// Per unit of average weight difference between matching connections
+    pub weights: f32,
+}
+
+impl Default for Compatibility {
+    fn default() -> Self {
+        Self {
+            layout: 1.,
+            cell_kinds: 1.,
+            excess: 1.,
+            disjoint: 1.,
+            weights: 0.4,
+        }
+    }
+}
+
+impl Genome {
+    /// NEAT's compatibility distance, extended with the body plan.
+    ///
+    /// Cell differences are divided by the larger genome's cell count, and connection
+    /// differences by its connection count, so the distance doesn't grow with size alone.
+    /// Identical genomes are `0` apart, and the distance is symmetric.
+    pub fn distance(&self, other: &Genome, coefficients: &Compatibility) -> f32 {
+        let mut only_one = 0;
+        let mut other_kind = 0;
+        for (location, cell) in self.cells.map() {
+            match other.cells.get(location) {
+                Some(other) if other.kind != cell.kind => other_kind += 1,
+                Some(_) => {}
+                None => only_one += 1,
+            }
+        }
+        only_one += other
+            .cells
+            .map()
+            .keys()
+            .filter(|location| self.cells.get(location).is_none())
+            .count();
+        let cells = self.cells.len().max(other.cells.len()).max(1) as f32;
+
+        let (mut excess, mut disjoint, mut matching, mut weight_difference) = (0, 0, 0, 0.);
+        for gene in align(&self.neurons, &other.neurons) {
+            match gene {
+                Gene::Matching(a, b) => {
+                    let a = self.neurons.connections()[a].weight;
+                    let b = other.neurons.connections()[b].weight;
+                    weight_difference += (a - b).abs();
+                    matching += 1;
+                }
+                Gene::Disjoint(..) => disjoint += 1,
+                Gene::Excess(..) => excess += 1,
+            }
+        }
+        let connections = self
+            .neurons
+            .connections()
+            .len()
+            .max(other.neurons.connections().len())
+            .max(1) as f32;
+        let average_weight_difference = match matching {
+            0 => 0.,
+            matching => weight_difference / matching as f32,
+        };
+
+        coefficients.layout * only_one as f32 / cells
+            + coefficients.cell_kinds * other_kind as f32 / cells
+            + coefficients.excess * excess as f32 / connections
+            + coefficients.disjoint * disjoint as f32 / connections
+            + coefficients.weights * average_weight_difference
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::cell::CellKind,
+    bevy::math::IVec2,
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+/// Only the term under test counts
+#[cfg(test)]
+fn only(set: impl FnOnce(&mut Compatibility)) -> Compatibility {
+    let mut coefficients = Compatibility {
+        layout: 0.,
+        cell_kinds: 0.,
+        excess: 0.,
+        disjoint: 0.,
+        weights: 0.,
+    };
+    set(&mut coefficients);
+    coefficients
+}
+
+#[test]
+fn test_identical_genomes_are_compatible() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let genome = Genome::sandbox(&mut rng);
+
+    assert_eq!(
+        genome.distance(&genome.clone(), &Compatibility::default()),
+        0.
+    );
+}
+
+#[test]
+fn test_distance_is_symmetric() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let ancestor = Genome::sandbox(&mut rng);
+
+    for _ in 0..20 {
+        let (mut a, mut b) = (ancestor.clone(), ancestor.clone());
+        for _ in 0..5 {
+            a.scramble(&mut rng);
+            b.scramble(&mut rng);
+        }
+        let coefficients = Compatibility::default();
+        assert_eq!(a.distance(&b, &coefficients), b.distance(&a, &coefficients));
+    }
+}
+
+#[test]
+fn test_distance_counts_body_plan() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let a = Genome::sandbox(&mut rng);
+    let mut b = a.clone();
+    // one cell changes kind, and one more is added
+    b.add_cell(IVec2::new(0, 0), CellKind::Foot, &mut rng);
+    b.add_cell(IVec2::new(4, 4), CellKind::Foot, &mut rng);
+    let cells = b.cell_count() as f32;
+
+    let layout = only(|c| c.layout = 2.);
+    assert_eq!(a.distance(&b, &layout), 2. / cells);
+    let kinds = only(|c| c.cell_kinds = 3.);
+    assert_eq!(a.distance(&b, &kinds), 3. / cells);
+}
+
+#[test]
+fn test_distance_counts_unmatched_connections() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let a = Genome::sandbox(&mut rng);
+    let mut b = a.clone();
+
+    let mut orders = a
+        .neurons
+        .connections()
+        .iter()
+        .map(|c| c.order)
+        .collect::<Vec<_>>();
+    orders.sort();
+    // the newest connection is excess, the oldest disjoint
+    for order in [orders[0], *orders.last().unwrap()] {
+        let index = b
+            .neurons
+            .connections()
+            .iter()
+            .position(|c| c.order == order)
+            .unwrap();
+        b.neurons.remove_connection(index);
+    }
+    let connections = a.neurons.connections().len() as f32;
+
+    assert_eq!(a.distance(&b, &only(|c| c.excess = 1.)), 1. / connections);
+    assert_eq!(a.distance(&b, &only(|c| c.disjoint = 1.)), 1. / connections);
+    assert_eq!(a.distance(&b, &only(|c| c.weights = 1.)), 0.);
+}
+
+#[test]
+fn test_distance_averages_weight_differences() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let a = Genome::sandbox(&mut rng);
+    let mut b = a.clone();
+    let connections = b.neurons.connections().len();
+    // half the weights differ by 2
+    for connection in b.neurons.connections_mut().iter_mut().step_by(2) {
+        connection.weight += 2.;
+    }
+    let changed = connections.div_ceil(2) as f32;
+
+    let distance = a.distance(&b, &only(|c| c.weights = 0.5));
+    assert_eq!(distance, 0.5 * 2. * changed / connections as f32);
+}
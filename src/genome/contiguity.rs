@@ -0,0 +1,311 @@
+use bevy::{
+    math::IVec2,
+    platform::collections::{HashMap, HashSet},
+    reflect::Reflect,
+};
+
+use crate::{
+    cell::CellGenome,
+    genome::{CellMap, Connection, Genome, NeuronArena, NeuronId, NeuronKind, decycler::Cleaner},
+};
+
+/// What to do about a body that mutates into pieces that don't touch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum Contiguity {
+    /// The pieces stay one organism, and move together
+    #[default]
+    Ignore,
+    /// Cell deletions that would split the body are undone
+    Reject,
+    /// Every piece becomes an organism of its own
+    Split,
+}
+
+impl Genome {
+    /// One genome per piece of the body, or just a copy if the body is a single piece.
+    ///
+    /// Pieces keep their cells where they were, along with the hidden neurons that still lead
+    /// to one of their outputs. Connections from another piece's inputs are dropped.
+    pub fn fragments(&self) -> Vec<Genome> {
+        let pieces = self.cells.components();
+        if pieces.len() <= 1 {
+            return vec![self.clone()];
+        }
+        pieces.iter().map(|piece| self.fragment(piece)).collect()
+    }
+
+    /// Drops every cell `keep` returns `false` for, along with its neurons.
+    pub fn retain_cells(&mut self, mut keep: impl FnMut(IVec2) -> bool) {
+        let gone: Vec<IVec2> = self
+            .cells
+            .map()
+            .keys()
+            .copied()
+            .filter(|location| !keep(*location))
+            .collect();
+        if gone.is_empty() {
+            return;
+        }
+        for location in gone {
+            self.cells.remove(&location, &mut self.neurons);
+        }
+        Cleaner::new(self).clean();
+    }
+
+    fn fragment(&self, piece: &[IVec2]) -> Genome {
+        let mut neurons = NeuronArena::default();
+        let mut ids = HashMap::new();
+        let mut copy = |id: &NeuronId, neurons: &mut NeuronArena| {
+            let copied = neurons.insert(self.neurons[*id].clone());
+            ids.insert(*id, copied);
+            copied
+        };
+
+        let mut cells = CellMap::with_capacity(piece.len());
+        for location in piece {
+            let cell = self.cells.get(location).unwrap();
+            let inputs = cell
+                .inputs
+                .iter()
+                .map(|id| copy(id, &mut neurons))
+                .collect();
+            let outputs = cell
+                .outputs
+                .iter()
+                .map(|id| copy(id, &mut neurons))
+                .collect();
+            cells.map_mut().insert(
+                *location,
+                CellGenome {
+                    kind: cell.kind,
+                    inputs,
+                    outputs,
+                },
+            );
+        }
+
+        // walk back from the piece's outputs to find the hidden neurons feeding them
+        let incoming = self.neurons.incoming();
+        let mut stack: Vec<NeuronId> = piece
+            .iter()
+            .flat_map(|location| &self.cells.get(location).unwrap().outputs)
+            .copied()
+            .collect();
+        let mut reached = HashSet::new();
+        while let Some(current) = stack.pop() {
+            for edge in incoming.of(current) {
+                let from = self.neurons.connections()[*edge].from;
+                if self.neurons[from].kind == NeuronKind::Hidden && reached.insert(from) {
+                    stack.push(from);
+                }
+            }
+        }
+        for hidden in self.neurons.hidden() {
+            if reached.contains(hidden) {
+                copy(hidden, &mut neurons);
+            }
+        }
+
+        for connection in self.neurons.connections() {
+            if let (Some(from), Some(to)) = (ids.get(&connection.from), ids.get(&connection.to)) {
+                neurons.add_connection(Connection {
+                    from: *from,
+                    to: *to,
+                    ..*connection
+                });
+            }
+        }
+
+        Genome {
+            cells,
+            neurons,
+            mutation: self.mutation.clone(),
+            activations: self.activations,
+            cycles: self.cycles,
+        }
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::{
+        cell::CellKind,
+        genome::{MutationAction, MutationChances},
+    },
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_components_follow_adjacency() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::from_cells(
+        vec![
+            (CellKind::Eye, IVec2::new(0, 0)),
+            (CellKind::Foot, IVec2::new(1, 0)),
+            // diagonal neighbours don't count as touching
+            (CellKind::Mouth, IVec2::new(2, 1)),
+            (CellKind::Data, IVec2::new(2, 2)),
+        ],
+        &mut rng,
+    );
+
+    assert_eq!(
+        genome.cells().components(),
+        vec![
+            vec![IVec2::new(0, 0), IVec2::new(1, 0)],
+            vec![IVec2::new(2, 1), IVec2::new(2, 2)],
+        ]
+    );
+    assert!(!genome.cells().is_contiguous());
+    assert!(Genome::empty().cells().is_contiguous());
+}
+
+#[test]
+fn test_free_spots_touch_the_body() {
+    let mut rng = StdRng::seed_from_u64(42);
+    // nothing is left at the origin, or next to it
+    let mut genome = Genome::from_cells(
+        vec![
+            (CellKind::Eye, IVec2::new(3, 0)),
+            (CellKind::Foot, IVec2::new(4, 0)),
+        ],
+        &mut rng,
+    );
+
+    assert_ne!(genome.cells().find_free_spot(&mut rng), IVec2::ZERO);
+    for _ in 0..10 {
+        let spot = genome.cells().find_free_spot(&mut rng);
+        genome.add_cell(spot, CellKind::Mouth, &mut rng);
+        assert!(genome.cells().is_contiguous());
+    }
+}
+
+#[test]
+fn test_fragments_keep_the_network_reaching_them() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    // a second eye and launcher pair, away from the first
+    let (eye, launcher) = (IVec2::new(5, 0), IVec2::new(6, 0));
+    genome.add_cell(eye, CellKind::Eye, &mut rng);
+    genome.add_cell(launcher, CellKind::Launcher, &mut rng);
+    let hidden = genome.add_hidden(&mut rng);
+    let first_eye = genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs.clone();
+    for input in genome.cells.get(&eye).unwrap().inputs.clone() {
+        genome.neurons.connect(input, hidden, 0.5);
+    }
+    // the first eye also feeds the second half, which is cut when they split
+    genome.neurons.connect(first_eye[0], hidden, 2.);
+    for output in genome.cells.get(&launcher).unwrap().outputs.clone() {
+        genome.neurons.connect(hidden, output, 0.5);
+    }
+
+    let fragments = genome.fragments();
+    assert_eq!(fragments.len(), 2);
+
+    let (first, second) = (&fragments[0], &fragments[1]);
+    assert_eq!(first.cells().len(), 2);
+    assert_eq!(first.neurons().hidden().len(), 1);
+    assert_eq!(first.neurons().connections().len(), 2 + 3);
+    assert!(first.neurons().connections().iter().all(|c| c.weight == 1.));
+
+    assert!(second.cells().get(&eye).is_some());
+    assert!(second.cells().get(&launcher).is_some());
+    assert_eq!(second.neurons().hidden().len(), 1);
+    assert_eq!(second.neurons().connections().len(), 2 + 3);
+    assert!(
+        second
+            .neurons()
+            .connections()
+            .iter()
+            .all(|c| c.weight == 0.5)
+    );
+
+    // markings carry over, so the pieces still line up with their ancestor
+    let second_hidden = second.neurons().hidden()[0];
+    assert_eq!(
+        second.neurons()[second_hidden].innovation,
+        genome.neurons[hidden].innovation
+    );
+}
+
+#[test]
+fn test_contiguous_genomes_stay_whole() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::simple_linear(&mut rng);
+
+    let fragments = genome.fragments();
+
+    assert_eq!(fragments.len(), 1);
+    assert_eq!(
+        fragments[0].snapshot().with_stable_ids(),
+        genome.snapshot().with_stable_ids()
+    );
+}
+
+#[test]
+fn test_rejected_deletions_keep_the_body_whole() {
+    let mut rng = StdRng::seed_from_u64(42);
+    // a line, so deleting anything but the ends splits it
+    let mut genome = Genome::from_cells(
+        (0..6).map(|x| (CellKind::Foot, IVec2::new(x, 0))).collect(),
+        &mut rng,
+    );
+    genome.mutation = MutationChances::only(MutationAction::DeleteCell, 100);
+
+    for _ in 0..10 {
+        genome.scramble_with(Contiguity::Reject, &mut rng);
+        assert!(genome.cells().is_contiguous());
+    }
+    assert!(genome.cells().len() < 6, "The ends can still go");
+}
+
+#[test]
+fn test_only_cells_holding_the_body_together_would_split_it() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let line = Genome::from_cells(
+        (0..3).map(|x| (CellKind::Foot, IVec2::new(x, 0))).collect(),
+        &mut rng,
+    );
+    assert!(line.cells().would_split(IVec2::new(1, 0)));
+    assert!(!line.cells().would_split(IVec2::new(0, 0)));
+    assert!(!line.cells().would_split(IVec2::new(2, 0)));
+
+    // around a square, there's always another way
+    let square = Genome::from_cells(
+        vec![
+            (CellKind::Foot, IVec2::new(0, 0)),
+            (CellKind::Foot, IVec2::new(1, 0)),
+            (CellKind::Foot, IVec2::new(0, 1)),
+            (CellKind::Foot, IVec2::new(1, 1)),
+        ],
+        &mut rng,
+    );
+    for location in square.cells().map().keys() {
+        assert!(!square.cells().would_split(*location));
+    }
+}
+
+#[test]
+fn test_sandbox_is_one_piece() {
+    let mut rng = StdRng::seed_from_u64(42);
+    assert!(Genome::sandbox(&mut rng).cells().is_contiguous());
+}
+
+#[test]
+fn test_retained_cells_keep_their_wiring() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let launcher = IVec2::new(1, 0);
+    genome.add_cell(IVec2::new(2, 0), CellKind::Foot, &mut rng);
+
+    genome.retain_cells(|location| location.x < 2);
+
+    assert_eq!(genome.cells().len(), 2);
+    let outputs = &genome.cells().get(&launcher).unwrap().outputs;
+    assert!(
+        outputs
+            .iter()
+            .all(|output| genome.neurons().inputs(*output).count() == 1)
+    );
+}
@@ -0,0 +1,473 @@
+use bevy::platform::collections::HashMap;
+use rand::Rng;
+
+use crate::{
+    cell::CellGenome,
+    genome::{
+        CellMap, Connection, Genome, Innovation, Neuron, NeuronArena, NeuronId, decycler::Cleaner,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parent {
+    A,
+    B,
+}
+
+/// One connection gene of two genomes lined up by [`Innovation`].
+///
+/// Indices are into the parent's [`NeuronArena::connections`]. Markings are hashes, so
+/// they say nothing about when a gene appeared; genes only one parent has are ranked by
+/// [`Connection::order`] instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gene {
+    /// Both parents have it
+    Matching(usize, usize),
+    /// Only one parent has it, and it's no newer than the other's newest
+    Disjoint(Parent, usize),
+    /// Only one parent has it, and it's newer than anything the other has
+    Excess(Parent, usize),
+}
+
+/// Lines up the connections of `a` and `b` by marking.
+///
+/// Parallel connections share a marking, so they're paired up in the order they were made.
+pub fn align(a: &NeuronArena, b: &NeuronArena) -> Vec<Gene> {
+    let keys_a = gene_keys(a);
+    let keys_b = gene_keys(b);
+    let lookup_b = keys_b
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (*key, index))
+        .collect::<HashMap<_, _>>();
+    let lookup_a = keys_a
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (*key, index))
+        .collect::<HashMap<_, _>>();
+    let newest = |neurons: &NeuronArena| neurons.connections().iter().map(|c| c.order).max();
+    let (newest_a, newest_b) = (newest(a), newest(b));
+
+    let unmatched = |parent, index, order, newest_other: Option<u64>| {
+        if newest_other.is_some_and(|newest| order <= newest) {
+            Gene::Disjoint(parent, index)
+        } else {
+            Gene::Excess(parent, index)
+        }
+    };
+
+    let mut genes = Vec::with_capacity(keys_a.len().max(keys_b.len()));
+    for (index, key) in keys_a.iter().enumerate() {
+        genes.push(match lookup_b.get(key) {
+            Some(other) => Gene::Matching(index, *other),
+            None => unmatched(Parent::A, index, a.connections()[index].order, newest_b),
+        });
+    }
+    for (index, key) in keys_b.iter().enumerate() {
+        if !lookup_a.contains_key(key) {
+            genes.push(unmatched(
+                Parent::B,
+                index,
+                b.connections()[index].order,
+                newest_a,
+            ));
+        }
+    }
+    genes
+}
+
+/// Each connection's marking, and how many older connections share it.
+///
+/// Parallels are counted by [`Connection::order`] rather than by position, since removing
+/// a connection can shuffle the rest.
+fn gene_keys(neurons: &NeuronArena) -> Vec<(Innovation, usize)> {
+    let connections = neurons.connections();
+    let mut oldest_first: Vec<usize> = (0..connections.len()).collect();
+    oldest_first.sort_by_key(|index| connections[*index].order);
+
+    let mut seen = HashMap::<Innovation, usize>::new();
+    let mut keys = vec![(Innovation::default(), 0); connections.len()];
+    for index in oldest_first {
+        let innovation = connections[index].innovation;
+        let count = seen.entry(innovation).or_default();
+        keys[index] = (innovation, *count);
+        *count += 1;
+    }
+    keys
+}
+
+impl Genome {
+    /// Recombines two genomes, NEAT style, with `a` as the fitter parent.
+    ///
+    /// Cells at a position both parents fill come from either one at random, and cells
+    /// only `a` has are kept. Neurons and connections are lined up by [`Innovation`]:
+    /// matching genes take their bias, activation or weight from either parent, while
+    /// disjoint and excess genes are only inherited from `a`. Everything else, like the
+    /// mutation chances, comes from `a` too.
+    pub fn crossover(a: &Genome, b: &Genome, rng: &mut impl Rng) -> Genome {
+        let mut neurons = NeuronArena::default();
+        let mut cells = CellMap::with_capacity(a.cells.len());
+
+        for (location, cell_a) in a.cells.map() {
+            let (parent, other, cell) = match b.cells.get(location) {
+                Some(cell_b) if rng.random_bool(0.5) => (b, a, cell_b),
+                _ => (a, b, cell_a),
+            };
+            let mut inherit = |ids: &[NeuronId]| {
+                ids.iter()
+                    .map(|id| {
+                        let neuron = inherit_neuron(&parent.neurons[*id], &other.neurons, rng);
+                        neurons.insert(neuron)
+                    })
+                    .collect()
+            };
+            let inputs = inherit(&cell.inputs);
+            let outputs = inherit(&cell.outputs);
+            cells.map_mut().insert(
+                *location,
+                CellGenome {
+                    kind: cell.kind,
+                    inputs,
+                    outputs,
+                },
+            );
+        }
+        for hidden in a.neurons.hidden() {
+            neurons.insert(inherit_neuron(&a.neurons[*hidden], &b.neurons, rng));
+        }
+
+        for gene in align(&a.neurons, &b.neurons) {
+            let (parent, index) = match gene {
+                Gene::Matching(index, other) => match rng.random_bool(0.5) {
+                    true => (&b.neurons, other),
+                    false => (&a.neurons, index),
+                },
+                Gene::Disjoint(Parent::A, index) | Gene::Excess(Parent::A, index) => {
+                    (&a.neurons, index)
+                }
+                Gene::Disjoint(Parent::B, _) | Gene::Excess(Parent::B, _) => continue,
+            };
+            let connection = &parent.connections()[index];
+            // either end may belong to a cell that came from `b` as another kind
+            let (Some(from), Some(to)) = (
+                neurons.find(parent[connection.from].innovation),
+                neurons.find(parent[connection.to].innovation),
+            ) else {
+                continue;
+            };
+            neurons.add_connection(Connection {
+                from,
+                to,
+                ..*connection
+            });
+        }
+
+        let mut child = Genome {
+            cells,
+            neurons,
+            mutation: a.mutation.clone(),
+            activations: a.activations,
+            cycles: a.cycles,
+        };
+        Cleaner::new(&mut child).clean();
+        child
+    }
+}
+
+/// `neuron`, with its bias and activation possibly swapped for those of its match in `other`.
+fn inherit_neuron(neuron: &Neuron, other: &NeuronArena, rng: &mut impl Rng) -> Neuron {
+    let mut neuron = neuron.clone();
+    if let Some(matching) = other.find(neuron.innovation).map(|id| &other[id])
+        && rng.random_bool(0.5)
+    {
+        neuron.bias = matching.bias;
+        neuron.activation = matching.activation;
+    }
+    neuron
+}
+
+#[cfg(test)]
+use {
+    crate::{cell::CellKind, cpu_net::CpuNetwork},
+    bevy::math::IVec2,
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+/// Removes the connection with this marking, returning it
+#[cfg(test)]
+fn remove_gene(genome: &mut Genome, innovation: Innovation) -> Connection {
+    let index = genome
+        .neurons
+        .connections()
+        .iter()
+        .position(|c| c.innovation == innovation)
+        .unwrap();
+    genome.neurons.remove_connection(index)
+}
+
+/// Markings of the connections from the oldest to the newest
+#[cfg(test)]
+fn innovations_by_order(genome: &Genome) -> Vec<Innovation> {
+    let mut connections = genome.neurons.connections().to_vec();
+    connections.sort_by_key(|c| c.order);
+    connections.iter().map(|c| c.innovation).collect()
+}
+
+#[cfg(test)]
+fn sorted_innovations(genome: &Genome) -> Vec<Innovation> {
+    let mut innovations = genome
+        .neurons
+        .connections()
+        .iter()
+        .map(|c| c.innovation)
+        .collect::<Vec<_>>();
+    innovations.sort();
+    innovations
+}
+
+#[test]
+fn test_markings_survive_cloning() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let genome = Genome::sandbox(&mut rng);
+    let clone = genome.clone();
+
+    assert!(
+        align(&genome.neurons, &clone.neurons)
+            .iter()
+            .all(|gene| matches!(gene, Gene::Matching(a, b) if a == b))
+    );
+}
+
+#[test]
+fn test_same_change_gets_same_marking() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let genome = Genome::simple_linear(&mut rng);
+    let (mut a, mut b) = (genome.clone(), genome.clone());
+
+    // both lines independently wire the eye straight into the launcher
+    for genome in [&mut a, &mut b] {
+        let eye = genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs[0];
+        let launcher = genome.cells.get(&IVec2::new(1, 0)).unwrap().outputs[0];
+        genome.neurons.connect(eye, launcher, 0.5);
+    }
+
+    assert_eq!(sorted_innovations(&a), sorted_innovations(&b));
+    assert!(
+        align(&a.neurons, &b.neurons)
+            .iter()
+            .all(|gene| matches!(gene, Gene::Matching(_, _)))
+    );
+}
+
+#[test]
+fn test_align_matching_disjoint_and_excess() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let a = Genome::sandbox(&mut rng);
+    let mut b = a.clone();
+
+    let innovations = innovations_by_order(&a);
+    let newest = *innovations.last().unwrap();
+    let older = innovations[innovations.len() / 2];
+    remove_gene(&mut b, newest);
+    remove_gene(&mut b, older);
+
+    let genes = align(&a.neurons, &b.neurons);
+    let innovation_of = |index: usize| a.neurons.connections()[index].innovation;
+
+    let matching = genes
+        .iter()
+        .filter(|gene| matches!(gene, Gene::Matching(_, _)))
+        .count();
+    assert_eq!(matching, innovations.len() - 2);
+
+    let disjoint = genes
+        .iter()
+        .filter_map(|gene| match gene {
+            Gene::Disjoint(parent, index) => Some((*parent, innovation_of(*index))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(disjoint, vec![(Parent::A, older)]);
+
+    let excess = genes
+        .iter()
+        .filter_map(|gene| match gene {
+            Gene::Excess(parent, index) => Some((*parent, innovation_of(*index))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(excess, vec![(Parent::A, newest)]);
+
+    // seen from the other side, those genes are missing from `a` instead
+    let genes = align(&b.neurons, &a.neurons);
+    let index_of = |innovation: Innovation| {
+        a.neurons
+            .connections()
+            .iter()
+            .position(|c| c.innovation == innovation)
+            .unwrap()
+    };
+    assert!(genes.contains(&Gene::Disjoint(Parent::B, index_of(older))));
+    assert!(genes.contains(&Gene::Excess(Parent::B, index_of(newest))));
+}
+
+#[test]
+fn test_parallel_connections_pair_up_by_order() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let mut a = Genome::simple_linear(&mut rng);
+    let eye = a.cells.get(&IVec2::new(0, 0)).unwrap().inputs[0];
+    let launcher = a.cells.get(&IVec2::new(1, 0)).unwrap().outputs[0];
+    for weight in [0.1, 0.2, 0.3] {
+        assert!(a.neurons.connect(eye, launcher, weight));
+    }
+
+    // removing the first connection moves the newest parallel into its place
+    let mut b = a.clone();
+    b.neurons.remove_connection(0);
+
+    for gene in align(&a.neurons, &b.neurons) {
+        if let Gene::Matching(i, j) = gene {
+            assert_eq!(
+                a.neurons.connections()[i].order,
+                b.neurons.connections()[j].order
+            );
+        }
+    }
+}
+
+#[test]
+fn test_new_genes_are_excess() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let a = Genome::sandbox(&mut rng);
+    let mut b = a.clone();
+
+    // newer than anything `a` has, however old the neurons it joins
+    let eye = b.cells.get(&IVec2::new(0, 0)).unwrap().inputs[0];
+    let launcher = b.cells.get(&IVec2::new(1, 1)).unwrap().outputs[0];
+    assert!(b.neurons.connect(eye, launcher, 0.5));
+    let added = b.neurons.connections().len() - 1;
+
+    let genes = align(&a.neurons, &b.neurons);
+    assert!(genes.contains(&Gene::Excess(Parent::B, added)));
+    assert!(
+        genes
+            .iter()
+            .all(|gene| matches!(gene, Gene::Matching(_, _) | Gene::Excess(Parent::B, _)))
+    );
+
+    // the order survives copying the gene over
+    let child = Genome::crossover(&b, &a, &mut rng);
+    assert_eq!(innovations_by_order(&child), innovations_by_order(&b));
+}
+
+#[test]
+fn test_crossover_mixes_matching_genes() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let a = Genome::sandbox(&mut rng);
+    let mut b = a.clone();
+    for connection in b.neurons.connections_mut() {
+        connection.weight = 10.;
+    }
+
+    let child = Genome::crossover(&a, &b, &mut rng);
+
+    assert_eq!(sorted_innovations(&child), sorted_innovations(&a));
+    let weights = child
+        .neurons
+        .connections()
+        .iter()
+        .map(|c| c.weight)
+        .collect::<Vec<_>>();
+    // every weight comes from one parent or the other, and both parents contribute
+    assert!(weights.iter().all(|w| *w == 1. || *w == 10.));
+    assert!(weights.contains(&1.));
+    assert!(weights.contains(&10.));
+}
+
+#[test]
+fn test_crossover_inherits_disjoint_and_excess_genes_from_a() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let ancestor = Genome::sandbox(&mut rng);
+    let innovations = innovations_by_order(&ancestor);
+    let (mut a, mut b) = (ancestor.clone(), ancestor.clone());
+
+    // `a` is missing an excess gene, `b` is missing a disjoint one
+    let only_in_b = remove_gene(&mut a, *innovations.last().unwrap());
+    let only_in_a = remove_gene(&mut b, innovations[0]);
+
+    for _ in 0..10 {
+        let child = Genome::crossover(&a, &b, &mut rng);
+        let child_innovations = sorted_innovations(&child);
+        assert!(child_innovations.contains(&only_in_a.innovation));
+        assert!(!child_innovations.contains(&only_in_b.innovation));
+        assert_eq!(child_innovations, sorted_innovations(&a));
+    }
+}
+
+#[test]
+fn test_crossover_recombines_cells_by_position() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let ancestor = Genome::sandbox(&mut rng);
+    let (mut a, mut b) = (ancestor.clone(), ancestor);
+    a.add_cell(IVec2::new(5, 5), CellKind::Foot, &mut rng);
+    b.add_cell(IVec2::new(6, 6), CellKind::Foot, &mut rng);
+    // the eye became a different cell in `b`
+    b.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+
+    let mut kinds = Vec::new();
+    for _ in 0..20 {
+        let child = Genome::crossover(&a, &b, &mut rng);
+        assert!(child.cells.get(&IVec2::new(5, 5)).is_some());
+        assert!(child.cells.get(&IVec2::new(6, 6)).is_none());
+        assert_eq!(child.cell_count(), a.cell_count());
+        kinds.push(child.cells.get(&IVec2::new(0, 0)).unwrap().kind);
+
+        // connections only ever join neurons the child has
+        for connection in child.neurons.connections() {
+            assert!(child.neurons.contains(connection.from));
+            assert!(child.neurons.contains(connection.to));
+        }
+        CpuNetwork::new(&child);
+    }
+    assert!(kinds.contains(&CellKind::Eye));
+    assert!(kinds.contains(&CellKind::Data));
+}
+
+#[test]
+fn test_crossover_of_evolved_relatives() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let ancestor = Genome::sandbox(&mut rng);
+
+    for _ in 0..20 {
+        let (mut a, mut b) = (ancestor.clone(), ancestor.clone());
+        for _ in 0..5 {
+            a.scramble(&mut rng);
+            b.scramble(&mut rng);
+        }
+        let child = Genome::crossover(&a, &b, &mut rng);
+
+        // relatives still share most of their history
+        let matching = align(&a.neurons, &b.neurons)
+            .iter()
+            .filter(|gene| matches!(gene, Gene::Matching(_, _)))
+            .count();
+        assert!(matching > 0);
+        let (network, _) = CpuNetwork::new(&child);
+        let mut values = vec![0.; network.len()];
+        network.process(&mut values);
+    }
+}
+
+#[test]
+fn test_markings_survive_snapshots() {
+    let mut rng = StdRng::seed_from_u64(16);
+    let mut genome = Genome::sandbox(&mut rng);
+    for _ in 0..10 {
+        genome.scramble(&mut rng);
+    }
+
+    let loaded = Genome::from_ron(&genome.to_ron().unwrap()).unwrap();
+
+    assert_eq!(sorted_innovations(&loaded), sorted_innovations(&genome));
+}
@@ -1,222 +1,199 @@
 use std::collections::HashSet;
 
-use bevy::platform::collections::HashMap;
-use uuid::Uuid;
-
-use crate::genome::{Genome, NeuronInputType, NeuronTopology, TakesInput};
+use bevy::{log::debug, platform::collections::HashMap, reflect::Reflect};
+use serde::{Deserialize, Serialize};
+
+use crate::genome::{CellMap, Genome, NeuronArena, NeuronId, NeuronKind};
+
+/// What [`Cleaner::clean`] does with the cycles mutations create.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleMode {
+    /// Cycles are cut, so the network is a plain feed-forward graph.
+    #[default]
+    Decycle,
+    /// Edges that close a cycle become recurrent, reading the previous tick's value.
+    Recurrent,
+}
 
 pub struct Cleaner<'a> {
-    // stack: HashSet<Uuid>,
-    // visited: HashSet<Uuid>,
     genome: &'a mut Genome,
 }
 impl<'a> Cleaner<'a> {
     pub fn new(genome: &'a mut Genome) -> Self {
-        Self {
-            // stack: HashSet::new(),
-            // visited: HashSet::new(),
-            genome,
-        }
-    }
-    pub fn remove_dead_connections(&mut self) {
-        for cell in self.genome.cells.map_mut().values_mut() {
-            for output in cell.outputs.iter_mut() {
-                output.with_mut(|val| val.inputs_mut().retain(|input| input.is_alive()))
-            }
-        }
-
-        for hidden_node in self.genome.hidden.iter_mut() {
-            hidden_node.with_mut(|h| h.inputs_mut().retain(|input| input.is_alive()))
-        }
+        Self { genome }
     }
 
     pub fn clean(&mut self) {
-        self.remove_dead_connections();
-        self.decycle();
+        match self.genome.cycles {
+            CycleMode::Decycle => self.decycle(),
+            CycleMode::Recurrent => self.mark_recurrent(),
+        }
     }
 
+    /// Removes connections until no cycle is left, recurrent ones included.
     pub fn decycle(&mut self) {
-        let mut stack = HashSet::new();
-        let mut visited = HashSet::new();
-
-        loop {
-            let mut remove_queue = RemoveFrom::default();
-
-            'cells: for cell in self.genome.cells.map_mut().values_mut() {
-                for output in &cell.outputs {
-                    let to_remove = dfs(output, &mut stack, &mut visited);
+        for connection in self.genome.neurons.connections_mut() {
+            connection.recurrent = false;
+        }
+        self.fix_cycles(|neurons, back_edges| {
+            neurons.retain_connections(|connection| {
+                !back_edges.contains(connection.from, connection.to)
+            });
+        });
+    }
 
-                    if !to_remove.0.is_empty() {
-                        remove_queue = to_remove;
-                        break 'cells;
-                    }
+    /// Marks the connection closing each cycle as recurrent.
+    ///
+    /// Connections that are already recurrent stay that way, so an evolved memory loop
+    /// keeps its shape as the rest of the network mutates.
+    pub fn mark_recurrent(&mut self) {
+        self.fix_cycles(|neurons, back_edges| {
+            for connection in neurons.connections_mut() {
+                if back_edges.contains(connection.from, connection.to) {
+                    connection.recurrent = true;
                 }
             }
+        });
+    }
 
-            if remove_queue.0.is_empty() {
-                break;
-            }
-            for (_, cell) in self.genome.cells.map_mut() {
-                for output_neuron in cell.outputs.iter_mut() {
-                    if let Some(inputs) = remove_queue.0.get(&output_neuron.id()) {
-                        let self_referencing = inputs.contains(&output_neuron.id());
-                        remove_tool(output_neuron, inputs, self_referencing);
-                    }
-                }
-            }
-            for hidden_neuron in self.genome.hidden.iter_mut() {
-                if let Some(inputs) = remove_queue.0.get(&hidden_neuron.id()) {
-                    let self_referencing = inputs.contains(&hidden_neuron.id());
-                    remove_tool(hidden_neuron, inputs, self_referencing);
-                }
-            }
+    /// Finds the connections closing a cycle among the non-recurrent ones, and hands them
+    /// all to `fix` at once.
+    fn fix_cycles(&mut self, fix: impl FnOnce(&mut NeuronArena, &BackEdges)) {
+        let back_edges = back_edges(&self.genome.cells, &self.genome.neurons);
+        if !back_edges.0.is_empty() {
+            fix(&mut self.genome.neurons, &back_edges);
         }
     }
 }
 
-fn remove_tool<T: TakesInput>(
-    node: &mut NeuronTopology<T>,
-    values: &HashSet<Uuid>,
-    self_reference: bool,
-) {
-    if self_reference {
-        let bad_indices = node.with_ref(|neuron| {
-            let mut bad_indices = Vec::new();
-            for (i, input) in neuron.inputs().iter().enumerate() {
-                if input.id().is_some_and(|id| values.contains(&id)) {
-                    bad_indices.push(i);
-                }
-            }
-            bad_indices
-        });
+/// Connections closing a cycle, keyed by the neuron reading them
+#[derive(Debug, Default)]
+struct BackEdges(HashMap<NeuronId, HashSet<NeuronId>>);
 
-        node.with_mut(|neuron| {
-            let inputs = neuron.inputs_mut();
-            for indice in bad_indices.into_iter().rev() {
-                inputs.remove(indice);
-            }
-        })
-    } else {
-        node.with_mut(|neuron| {
-            neuron
-                .inputs_mut()
-                .retain(|input| input.id().is_some_and(|id| !values.contains(&id)));
-        })
+impl BackEdges {
+    fn insert(&mut self, from: NeuronId, to: NeuronId) {
+        self.0.entry(to).or_default().insert(from);
+    }
+
+    fn contains(&self, from: NeuronId, to: NeuronId) -> bool {
+        self.0.get(&to).is_some_and(|inputs| inputs.contains(&from))
     }
 }
 
-#[derive(Debug, Default)]
-struct RemoveFrom(HashMap<Uuid, HashSet<Uuid>>);
-
-fn dfs<T: TakesInput>(
-    node: &NeuronTopology<T>,
-    stack: &mut HashSet<Uuid>,
-    visited: &mut HashSet<Uuid>,
-) -> RemoveFrom {
-    let node_id = node.id();
-    println!("Searching depth for {node_id}");
-    visited.insert(node_id);
-
-    let mut total_remove = RemoveFrom::default();
-    let mut self_remove = HashSet::new();
-
-    stack.insert(node_id);
-
-    node.with_ref(|node| {
-        for input in node.inputs() {
-            match &input.input_type {
-                NeuronInputType::Hidden(hidden) => {
-                    if let Some(neuron_input) = hidden.upgrade() {
-                        let input_id = neuron_input.id();
-                        println!("Checking input {input_id} for node {node_id}");
-                        if !visited.contains(&input_id) {
-                            println!("Not visited");
-                            let child_result = dfs(&neuron_input, stack, visited);
-                            total_remove.0.extend(child_result.0);
-                        } else if stack.contains(&input_id) {
-                            println!("Stack contains");
-                            self_remove.insert(input_id);
-                        }
-                    }
-                }
-                NeuronInputType::Input(_) => {}
+/// A depth-first search backwards from every output, following hidden senders.
+///
+/// A connection from a neuron still on the search stack closes a cycle, and the graph left
+/// without those connections is acyclic. Recurrent connections are never back edges, but
+/// their senders are searched too, so cycles behind them are found as well.
+///
+/// Uses an explicit stack and visits every neuron and connection once.
+fn back_edges(cells: &CellMap, neurons: &NeuronArena) -> BackEdges {
+    let incoming = neurons.incoming();
+    let mut back_edges = BackEdges::default();
+    let mut visited = HashSet::new();
+    let mut on_stack = HashSet::new();
+    // each entry is a neuron and how many of its connections have been looked at
+    let mut stack: Vec<(NeuronId, usize)> = Vec::new();
+
+    let mut roots = cells
+        .map()
+        .values()
+        .flat_map(|cell| &cell.outputs)
+        .copied()
+        .collect::<Vec<_>>();
+    roots.reverse();
+
+    while let Some(root) = roots.pop() {
+        if !visited.insert(root) {
+            continue;
+        }
+        debug!("Searching for cycles from {root}");
+        on_stack.insert(root);
+        stack.push((root, 0));
+
+        while let Some((node_id, next)) = stack.last_mut() {
+            let node_id = *node_id;
+            let Some(edge) = incoming.of(node_id).get(*next) else {
+                on_stack.remove(&node_id);
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            let connection = &neurons.connections()[*edge];
+            let input_id = connection.from;
+            if !neurons
+                .get(input_id)
+                .is_some_and(|input| input.kind == NeuronKind::Hidden)
+            {
+                continue;
+            }
+            if connection.recurrent {
+                // searched once the current tree is done, so its cycles can't pass
+                // through this connection
+                roots.push(input_id);
+            } else if visited.insert(input_id) {
+                on_stack.insert(input_id);
+                stack.push((input_id, 0));
+            } else if on_stack.contains(&input_id) {
+                debug!("{input_id} -> {node_id} closes a cycle");
+                back_edges.insert(input_id, node_id);
             }
         }
-    });
-    println!("out of node ref");
-
-    if !self_remove.is_empty() {
-        total_remove.0.insert(node_id, self_remove);
     }
 
-    stack.remove(&node_id);
-    total_remove
+    back_edges
 }
 
 #[cfg(test)]
 use {
-    crate::cell::CellKind,
+    crate::{cell::CellKind, genome::Incoming},
     bevy::prelude::*,
     pretty_assertions::assert_eq,
     rand::{SeedableRng, rngs::StdRng},
 };
 
-#[test]
-fn test_cleaner_removes_dead_connections() {
-    let mut rng = StdRng::seed_from_u64(238102);
-    let mut genome = Genome::empty();
-
-    // Create cells with inputs and outputs
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
-
-    let mut rng = StdRng::seed_from_u64(271);
-    // Create hidden neurons
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = NeuronTopology::hidden(&mut rng);
-
-    // Create connections
-    if let Some(eye) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for input in &eye.inputs {
-            hidden1.add_input(input);
-        }
+/// Connects `from` into every output of the cell at `location`
+#[cfg(test)]
+fn feed_outputs(genome: &mut Genome, location: IVec2, from: NeuronId) {
+    for output in genome.cells.get(&location).unwrap().outputs.clone() {
+        genome.neurons.connect(from, output, 1.);
     }
+}
 
-    if let Some(launcher) = genome.cells.map_mut().get_mut(&IVec2::new(1, 0)) {
-        for output in &launcher.outputs {
-            output.add_input(&hidden1);
-            output.add_input(&hidden2);
+/// Whether any neuron reachable from an output feeds back into itself without a recurrent
+/// connection in the way
+#[cfg(test)]
+fn has_cycle(genome: &Genome) -> bool {
+    fn visit(
+        id: NeuronId,
+        genome: &Genome,
+        incoming: &Incoming,
+        stack: &mut Vec<NeuronId>,
+        done: &mut HashSet<NeuronId>,
+    ) -> bool {
+        if stack.contains(&id) {
+            return true;
         }
-    }
-
-    // Add hidden neurons to genome
-    genome.hidden.push(hidden1);
-    genome.hidden.push(hidden2);
-
-    // Now remove hidden neurons to create dead connections
-    genome.hidden.clear();
-
-    // Clean should remove dead connections
-    let mut cleaner = Cleaner::new(&mut genome);
-    cleaner.remove_dead_connections();
-
-    // Check that dead connections are removed
-    for cell in genome.cells.map().values() {
-        for output in &cell.outputs {
-            output.with_ref(|neuron| {
-                for input in neuron.inputs() {
-                    assert!(
-                        input.is_alive(),
-                        "All connections should be alive after cleaning"
-                    );
-                }
-            });
+        if !done.insert(id) {
+            return false;
         }
+        stack.push(id);
+        let cyclic = incoming.of(id).iter().any(|edge| {
+            let connection = &genome.neurons.connections()[*edge];
+            !connection.recurrent && visit(connection.from, genome, incoming, stack, done)
+        });
+        stack.pop();
+        cyclic
     }
+    let incoming = genome.neurons.incoming();
+    let mut done = HashSet::new();
+    genome.cells.map().values().any(|cell| {
+        cell.outputs
+            .iter()
+            .any(|output| visit(*output, genome, &incoming, &mut Vec::new(), &mut done))
+    })
 }
 
 #[test]
@@ -225,36 +202,28 @@ fn test_decycler_simple_cycle() {
     let mut rng = StdRng::seed_from_u64(271);
 
     // Create a Data cell (has both inputs and outputs)
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
 
     // Create two hidden neurons
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = NeuronTopology::hidden(&mut rng);
+    let hidden1 = genome.add_hidden(&mut rng);
+    let hidden2 = genome.add_hidden(&mut rng);
 
     // Create a cycle: hidden1 -> hidden2 -> hidden1
-    hidden2.add_input(&hidden1);
-    hidden1.add_input(&hidden2);
+    genome.neurons.connect(hidden1, hidden2, 1.);
+    genome.neurons.connect(hidden2, hidden1, 1.);
 
     // Connect cell outputs to hidden neurons
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&hidden1);
-        }
-    }
-
-    genome.hidden.push(hidden1.clone());
-    genome.hidden.push(hidden2.clone());
+    feed_outputs(&mut genome, IVec2::new(0, 0), hidden1);
+    assert!(has_cycle(&genome));
 
     // Run decycler
     let mut cleaner = Cleaner::new(&mut genome);
     cleaner.decycle();
 
-    // The cycle should be broken
-    // We can't easily verify the exact structure, but it should not panic
-    // and the genome should still be valid
+    // The cycle should be broken, and only one of its connections dropped
+    assert!(!has_cycle(&genome));
     assert_eq!(genome.hidden_count(), 2, "Hidden neurons should remain");
+    assert_eq!(genome.neurons.connections().len(), 8 + 1);
 }
 
 #[test]
@@ -262,40 +231,28 @@ fn test_decycler_self_loop() {
     let mut rng = StdRng::seed_from_u64(238102);
     let mut genome = Genome::empty();
 
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
 
     let mut rng = StdRng::seed_from_u64(271);
     // Create a hidden neuron with a self-loop
-    let hidden = NeuronTopology::hidden(&mut rng);
-    hidden.add_input(&hidden); // Self-loop
+    let hidden = genome.add_hidden(&mut rng);
+    genome.neurons.connect(hidden, hidden, 1.);
 
     // Connect to output
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&hidden);
-        }
-    }
-
-    genome.hidden.push(hidden);
+    feed_outputs(&mut genome, IVec2::new(0, 0), hidden);
 
     // Run decycler
     let mut cleaner = Cleaner::new(&mut genome);
     cleaner.decycle();
 
     // Self-loop should be removed
-    // Verify by checking that no neuron has itself as input
-    for hidden_neuron in &genome.hidden {
-        let hidden_id = hidden_neuron.id();
-        hidden_neuron.with_ref(|neuron| {
-            for input in neuron.inputs() {
-                if let Some(input_id) = input.id() {
-                    assert_ne!(input_id, hidden_id, "Self-loops should be removed");
-                }
-            }
-        });
-    }
+    assert!(
+        genome
+            .neurons
+            .inputs(hidden)
+            .all(|input| input.from != hidden),
+        "Self-loops should be removed"
+    );
 }
 
 #[test]
@@ -303,34 +260,23 @@ fn test_decycler_complex_cycle() {
     let mut genome = Genome::empty();
     let mut rng = StdRng::seed_from_u64(238102);
 
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
 
     let mut rng = StdRng::seed_from_u64(271);
     // Create a complex cycle: h1 -> h2 -> h3 -> h4 -> h2
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    let h3 = NeuronTopology::hidden(&mut rng);
-    let h4 = NeuronTopology::hidden(&mut rng);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    let h3 = genome.add_hidden(&mut rng);
+    let h4 = genome.add_hidden(&mut rng);
 
-    h2.add_input(&h1);
-    h3.add_input(&h2);
-    h4.add_input(&h3);
-    h2.add_input(&h4); // Creates the cycle
+    genome.neurons.connect(h1, h2, 1.);
+    genome.neurons.connect(h2, h3, 1.);
+    genome.neurons.connect(h3, h4, 1.);
+    genome.neurons.connect(h4, h2, 1.); // Creates the cycle
 
     // Connect to outputs
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&h1);
-            output.add_input(&h3);
-        }
-    }
-
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
-    genome.hidden.push(h3);
-    genome.hidden.push(h4);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h3);
 
     // Run decycler
     let mut cleaner = Cleaner::new(&mut genome);
@@ -338,6 +284,7 @@ fn test_decycler_complex_cycle() {
 
     // Should still have all neurons
     assert_eq!(genome.hidden_count(), 4, "All neurons should remain");
+    assert!(!has_cycle(&genome));
 }
 
 #[test]
@@ -345,44 +292,26 @@ fn test_decycler_multiple_independent_cycles() {
     let mut genome = Genome::empty();
     let mut rng = StdRng::seed_from_u64(271);
 
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
 
     // Create first cycle: h1 <-> h2
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    h1.add_input(&h2);
-    h2.add_input(&h1);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h2, h1, 1.);
+    genome.neurons.connect(h1, h2, 1.);
 
     // Create second cycle: h3 <-> h4
-    let h3 = NeuronTopology::hidden(&mut rng);
-    let h4 = NeuronTopology::hidden(&mut rng);
-    h3.add_input(&h4);
-    h4.add_input(&h3);
+    let h3 = genome.add_hidden(&mut rng);
+    let h4 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h4, h3, 1.);
+    genome.neurons.connect(h3, h4, 1.);
 
     // Connect cycles to different outputs
-    if let Some(data_cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &data_cell.outputs {
-            output.add_input(&h1);
-            output.add_input(&h2);
-        }
-    }
-
-    if let Some(launcher_cell) = genome.cells.map_mut().get_mut(&IVec2::new(1, 0)) {
-        for output in &launcher_cell.outputs {
-            output.add_input(&h3);
-            output.add_input(&h4);
-        }
-    }
-
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
-    genome.hidden.push(h3);
-    genome.hidden.push(h4);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h2);
+    feed_outputs(&mut genome, IVec2::new(1, 0), h3);
+    feed_outputs(&mut genome, IVec2::new(1, 0), h4);
 
     // Run decycler
     let mut cleaner = Cleaner::new(&mut genome);
@@ -390,57 +319,41 @@ fn test_decycler_multiple_independent_cycles() {
 
     // All neurons should remain
     assert_eq!(genome.hidden_count(), 4);
+    assert!(!has_cycle(&genome));
 }
 
 #[test]
-fn test_clean_combines_dead_removal_and_decycling() {
+fn test_clean_after_removing_a_neuron() {
     let mut genome = Genome::empty();
     let mut rng = StdRng::seed_from_u64(271);
 
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
 
     // Create neurons
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    let h3 = NeuronTopology::hidden(&mut rng);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    let h3 = genome.add_hidden(&mut rng);
 
     // Create a cycle
-    h1.add_input(&h2);
-    h2.add_input(&h1);
-
-    // h3 will become dead
+    genome.neurons.connect(h2, h1, 1.);
+    genome.neurons.connect(h1, h2, 1.);
 
     // Connect to outputs
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&h1);
-            output.add_input(&h2);
-            output.add_input(&h3);
-        }
-    }
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h2);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h3);
 
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
-    // Don't add h3 to genome.hidden, making it a dead connection
+    // removing h3 takes its connections with it
+    genome.neurons.remove(h3);
 
-    // Run clean (combines remove_dead_connections and decycle)
     let mut cleaner = Cleaner::new(&mut genome);
     cleaner.clean();
 
-    // Should have broken the cycle and removed dead connections
     assert_eq!(genome.hidden_count(), 2);
-
-    // Verify no dead connections remain
-    for cell in genome.cells.map().values() {
-        for output in &cell.outputs {
-            output.with_ref(|neuron| {
-                for input in neuron.inputs() {
-                    assert!(input.is_alive());
-                }
-            });
-        }
+    assert!(!has_cycle(&genome));
+    for connection in genome.neurons.connections() {
+        assert!(genome.neurons.contains(connection.from));
+        assert!(genome.neurons.contains(connection.to));
     }
 }
 
@@ -450,46 +363,29 @@ fn test_decycler_preserves_valid_paths() {
 
     let mut rng = StdRng::seed_from_u64(271);
     // Create a more complex network
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Data, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(2, 0), CellKind::Launcher, &mut rng);
-
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    let h3 = NeuronTopology::hidden(&mut rng);
-    let h4 = NeuronTopology::hidden(&mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(2, 0), CellKind::Launcher, &mut rng);
+
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    let h3 = genome.add_hidden(&mut rng);
+    let h4 = genome.add_hidden(&mut rng);
 
     // Create valid forward connections
-    if let Some(eye) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for input in &eye.inputs {
-            h1.add_input(input);
-        }
+    for input in genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs.clone() {
+        genome.neurons.connect(input, h1, 1.);
     }
 
-    h2.add_input(&h1);
-    h3.add_input(&h2);
-    h4.add_input(&h3);
+    genome.neurons.connect(h1, h2, 1.);
+    genome.neurons.connect(h2, h3, 1.);
+    genome.neurons.connect(h3, h4, 1.);
 
     // Add a backward edge creating a cycle
-    h2.add_input(&h4);
+    genome.neurons.connect(h4, h2, 1.);
 
     // Connect to outputs
-    if let Some(launcher) = genome.cells.map_mut().get_mut(&IVec2::new(2, 0)) {
-        for output in &launcher.outputs {
-            output.add_input(&h4);
-        }
-    }
-
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
-    genome.hidden.push(h3);
-    genome.hidden.push(h4);
+    feed_outputs(&mut genome, IVec2::new(2, 0), h4);
 
     let initial_hidden_count = genome.hidden_count();
 
@@ -500,7 +396,11 @@ fn test_decycler_preserves_valid_paths() {
     // All neurons should still exist
     assert_eq!(genome.hidden_count(), initial_hidden_count);
 
-    // The main forward path should still exist (though we can't easily verify the exact structure)
+    // Only the backward edge goes
+    assert!(!has_cycle(&genome));
+    assert!(genome.neurons.inputs(h2).all(|input| input.from == h1));
+    assert_eq!(genome.neurons.inputs(h3).count(), 1);
+    assert_eq!(genome.neurons.inputs(h4).count(), 1);
 }
 
 #[test]
@@ -509,44 +409,25 @@ fn test_decycler_handles_disconnected_components() {
 
     let mut rng = StdRng::seed_from_u64(271);
 
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
 
     // Create two separate subgraphs
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    let h3 = NeuronTopology::hidden(&mut rng);
-    let h4 = NeuronTopology::hidden(&mut rng);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    let h3 = genome.add_hidden(&mut rng);
+    let h4 = genome.add_hidden(&mut rng);
 
     // First subgraph with cycle
-    h1.add_input(&h2);
-    h2.add_input(&h1);
+    genome.neurons.connect(h2, h1, 1.);
+    genome.neurons.connect(h1, h2, 1.);
 
     // Second subgraph without cycle
-    h4.add_input(&h3);
+    genome.neurons.connect(h3, h4, 1.);
 
     // Connect to different outputs
-    let cells: Vec<_> = genome.cells.map().keys().cloned().collect();
-    if let Some(cell1) = genome.cells.map_mut().get_mut(&cells[0]) {
-        for output in &cell1.outputs {
-            output.add_input(&h1);
-        }
-    }
-
-    if let Some(cell2) = genome.cells.map_mut().get_mut(&cells[1]) {
-        for output in &cell2.outputs {
-            output.add_input(&h4);
-        }
-    }
-
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
-    genome.hidden.push(h3);
-    genome.hidden.push(h4);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
+    feed_outputs(&mut genome, IVec2::new(1, 0), h4);
 
     // Run decycler
     let mut cleaner = Cleaner::new(&mut genome);
@@ -554,6 +435,7 @@ fn test_decycler_handles_disconnected_components() {
 
     // All neurons should remain
     assert_eq!(genome.hidden_count(), 4);
+    assert_eq!(genome.neurons.inputs(h4).count(), 1);
 }
 
 #[test]
@@ -575,6 +457,7 @@ fn test_clean_on_genome_with_no_cycles() {
 
     let initial_hidden = genome.hidden_count();
     let initial_cells = genome.cell_count();
+    let initial_connections = genome.neurons.connections().to_vec();
 
     // Clean should not change a valid acyclic genome
     let mut cleaner = Cleaner::new(&mut genome);
@@ -582,6 +465,7 @@ fn test_clean_on_genome_with_no_cycles() {
 
     assert_eq!(genome.hidden_count(), initial_hidden);
     assert_eq!(genome.cell_count(), initial_cells);
+    assert_eq!(genome.neurons.connections(), initial_connections.as_slice());
 }
 
 #[test]
@@ -589,34 +473,24 @@ fn test_decycler_with_deeply_nested_cycles() {
     let mut genome = Genome::empty();
 
     let mut rng = StdRng::seed_from_u64(271);
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
 
     // Create a deep nested structure with multiple cycles
-    let neurons: Vec<_> = (0..10).map(|_| NeuronTopology::hidden(&mut rng)).collect();
+    let neurons: Vec<_> = (0..10).map(|_| genome.add_hidden(&mut rng)).collect();
 
     // Create forward connections
     for i in 0..9 {
-        neurons[i + 1].add_input(&neurons[i]);
+        genome.neurons.connect(neurons[i], neurons[i + 1], 1.);
     }
 
     // Add multiple backward edges creating nested cycles
-    neurons[2].add_input(&neurons[5]);
-    neurons[1].add_input(&neurons[8]);
-    neurons[0].add_input(&neurons[9]);
+    genome.neurons.connect(neurons[5], neurons[2], 1.);
+    genome.neurons.connect(neurons[8], neurons[1], 1.);
+    genome.neurons.connect(neurons[9], neurons[0], 1.);
 
     // Connect to outputs
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&neurons[0]);
-            output.add_input(&neurons[9]);
-        }
-    }
-
-    for neuron in neurons {
-        genome.hidden.push(neuron);
-    }
+    feed_outputs(&mut genome, IVec2::new(0, 0), neurons[0]);
+    feed_outputs(&mut genome, IVec2::new(0, 0), neurons[9]);
 
     // Run decycler
     let mut cleaner = Cleaner::new(&mut genome);
@@ -624,6 +498,7 @@ fn test_decycler_with_deeply_nested_cycles() {
 
     // Should maintain all neurons
     assert_eq!(genome.hidden_count(), 10);
+    assert!(!has_cycle(&genome));
 }
 
 #[test]
@@ -631,42 +506,27 @@ fn test_multiple_clean_calls_are_idempotent() {
     let mut genome = Genome::empty();
     let mut rng = StdRng::seed_from_u64(271);
 
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
 
     // Create a cycle
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    h1.add_input(&h2);
-    h2.add_input(&h1);
-
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&h1);
-        }
-    }
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h2, h1, 1.);
+    genome.neurons.connect(h1, h2, 1.);
 
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
 
     // First clean
-    let mut cleaner = Cleaner::new(&mut genome);
-    cleaner.clean();
-
-    let state_after_first = genome.hidden_count();
+    Cleaner::new(&mut genome).clean();
+    let state_after_first = genome.neurons.connections().to_vec();
 
     // Second clean
-    let mut cleaner = Cleaner::new(&mut genome);
-    cleaner.clean();
-
-    let state_after_second = genome.hidden_count();
+    Cleaner::new(&mut genome).clean();
+    let state_after_second = genome.neurons.connections().to_vec();
 
     // Third clean
-    let mut cleaner = Cleaner::new(&mut genome);
-    cleaner.clean();
-
-    let state_after_third = genome.hidden_count();
+    Cleaner::new(&mut genome).clean();
+    let state_after_third = genome.neurons.connections().to_vec();
 
     // State should stabilize after first clean
     assert_eq!(state_after_first, state_after_second);
@@ -679,36 +539,21 @@ fn test_clean_preserves_input_to_output_connectivity() {
 
     let mut rng = StdRng::seed_from_u64(271);
     // Create a network with inputs and outputs
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
 
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
 
     // Connect input -> h1 -> h2 -> output
-    if let Some(eye) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for input in &eye.inputs {
-            h1.add_input(input);
-        }
-    }
-
-    h2.add_input(&h1);
-
-    if let Some(launcher) = genome.cells.map_mut().get_mut(&IVec2::new(1, 0)) {
-        for output in &launcher.outputs {
-            output.add_input(&h2);
-        }
+    for input in genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs.clone() {
+        genome.neurons.connect(input, h1, 1.);
     }
+    genome.neurons.connect(h1, h2, 1.);
+    feed_outputs(&mut genome, IVec2::new(1, 0), h2);
 
     // Add a cycle
-    h1.add_input(&h2);
-
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
+    genome.neurons.connect(h2, h1, 1.);
 
     // Clean
     let mut cleaner = Cleaner::new(&mut genome);
@@ -717,85 +562,106 @@ fn test_clean_preserves_input_to_output_connectivity() {
     // Should still have both hidden neurons
     assert_eq!(genome.hidden_count(), 2);
 
-    // There should still be a path from input to output
-    // (though the cycle should be broken)
+    // There should still be a path from input to output, with the cycle broken
+    assert!(!has_cycle(&genome));
+    assert!(genome.neurons.inputs(h2).any(|input| input.from == h1));
+    assert_eq!(genome.neurons.inputs(h1).count(), 2);
 }
 
 #[test]
-fn test_cleaner_with_partially_dead_connections() {
+fn test_genome_scramble_includes_cleaning() {
     let mut genome = Genome::empty();
+    let mut rng = StdRng::seed_from_u64(42);
+
+    // Create a genome with a cycle
+    genome.add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
+
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h2, h1, 1.);
+    genome.neurons.connect(h1, h2, 1.);
+
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
+
+    // Scramble should include cleaning (which removes cycles)
+    genome.scramble(&mut rng);
+    assert!(!has_cycle(&genome));
+}
 
+#[test]
+fn test_recurrent_mode_keeps_cycles() {
+    let mut genome = Genome::empty();
     let mut rng = StdRng::seed_from_u64(271);
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
-
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    let h3 = NeuronTopology::hidden(&mut rng);
-
-    // Connect h1 and h2 to outputs
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&h1);
-            output.add_input(&h2);
-            output.add_input(&h3); // h3 will be dead
-        }
+    genome.set_cycle_mode(CycleMode::Recurrent);
+
+    genome.add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
+
+    // h1 <-> h2, and h2 feeding itself
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h1, h2, 1.);
+    genome.neurons.connect(h2, h1, 1.);
+    genome.neurons.connect(h2, h2, 1.);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
+    let connections = genome.neurons.connections().len();
+
+    Cleaner::new(&mut genome).clean();
+
+    assert_eq!(genome.neurons.connections().len(), connections);
+    assert!(!has_cycle(&genome));
+    let recurrent = genome
+        .neurons
+        .connections()
+        .iter()
+        .filter(|c| c.recurrent)
+        .map(|c| (c.from, c.to))
+        .collect::<Vec<_>>();
+    // one connection per cycle is enough to break it
+    assert_eq!(recurrent.len(), 2);
+    assert!(recurrent.contains(&(h2, h2)));
+}
 
-        // Also create some connections from inputs
-        for input in &cell.inputs {
-            h1.add_input(input);
-            h2.add_input(input);
-        }
-    }
+#[test]
+fn test_recurrent_marks_are_kept() {
+    let mut genome = Genome::empty();
+    let mut rng = StdRng::seed_from_u64(271);
+    genome.set_cycle_mode(CycleMode::Recurrent);
 
-    // Only add h1 and h2 to genome, making h3 dead
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h1, h2, 1.);
+    genome.neurons.connect(h2, h1, 1.);
+    feed_outputs(&mut genome, IVec2::new(0, 0), h1);
 
-    // Clean
-    let mut cleaner = Cleaner::new(&mut genome);
-    cleaner.clean();
+    Cleaner::new(&mut genome).clean();
+    let before = genome.neurons.connections().to_vec();
 
-    // Should still have h1 and h2
-    assert_eq!(genome.hidden_count(), 2);
+    // another path around the same loop doesn't move the existing mark
+    let h3 = genome.add_hidden(&mut rng);
+    genome.neurons.connect(h3, h1, 1.);
+    Cleaner::new(&mut genome).clean();
 
-    // Verify only live connections remain
-    for cell in genome.cells.map().values() {
-        for output in &cell.outputs {
-            output.with_ref(|neuron| {
-                for input in neuron.inputs() {
-                    assert!(input.is_alive(), "Only live connections should remain");
-                }
-            });
-        }
-    }
+    assert_eq!(&genome.neurons.connections()[..before.len()], &before[..]);
 }
 
 #[test]
-fn test_genome_scramble_includes_cleaning() {
+fn test_decycle_drops_recurrent_connections() {
     let mut genome = Genome::empty();
-    let mut rng = StdRng::seed_from_u64(42);
+    let mut rng = StdRng::seed_from_u64(271);
+    genome.set_cycle_mode(CycleMode::Recurrent);
 
-    // Create a genome with a cycle
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Data, &mut rng);
-
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    h1.add_input(&h2);
-    h2.add_input(&h1);
-
-    if let Some(cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for output in &cell.outputs {
-            output.add_input(&h1);
-        }
-    }
+    genome.add_cell(IVec2::new(0, 0), CellKind::Launcher, &mut rng);
+    let hidden = genome.add_hidden(&mut rng);
+    genome.neurons.connect(hidden, hidden, 1.);
+    feed_outputs(&mut genome, IVec2::new(0, 0), hidden);
 
-    genome.hidden.push(h1);
-    genome.hidden.push(h2);
+    Cleaner::new(&mut genome).clean();
+    assert!(genome.neurons.inputs(hidden).any(|c| c.recurrent));
 
-    // Scramble should include cleaning (which removes cycles)
-    genome.scramble(&mut rng);
+    genome.set_cycle_mode(CycleMode::Decycle);
+    Cleaner::new(&mut genome).clean();
+
+    assert_eq!(genome.neurons.inputs(hidden).count(), 0);
+    assert!(genome.neurons.connections().iter().all(|c| !c.recurrent));
 }
@@ -0,0 +1,588 @@
+use std::fmt::Write;
+
+use bevy::{
+    color::palettes::tailwind::{BLUE_400, RED_400},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    cell::CellKind,
+    genome::{Genome, NeuronId, NeuronKind, activations::Activation, layers},
+};
+
+/// The color of large positive values, here and in the brain viewer
+pub const POSITIVE_SIGNAL: Srgba = RED_400;
+/// The color of large negative values, here and in the brain viewer
+pub const NEGATIVE_SIGNAL: Srgba = BLUE_400;
+
+/// Horizontal distance between two columns of an SVG diagram
+const SVG_COLUMN: f32 = 220.;
+/// Vertical distance between two neurons in a column
+const SVG_ROW: f32 = 70.;
+/// Leaves room for cell boxes and neuron labels around the edge
+const SVG_MARGIN: f32 = 100.;
+const SVG_RADIUS: f32 = 16.;
+/// The widest an edge gets, however heavy its weight
+const MAX_PEN: f32 = 6.;
+
+const INPUT_FILL: &str = "#4ade80";
+const HIDDEN_FILL: &str = "#e5e7eb";
+const OUTPUT_FILL: &str = "#facc15";
+
+/// A network drawn for people rather than the simulation, as Graphviz DOT or SVG.
+///
+/// Built with [`Genome::diagram`] or [`CpuNetwork::diagram`](crate::cpu_net::CpuNetwork::diagram).
+/// Cells become clusters holding their input and output neurons, and hidden neurons sit
+/// between them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrainDiagram {
+    pub(crate) cells: Vec<DiagramCell>,
+    pub(crate) neurons: Vec<DiagramNeuron>,
+    pub(crate) connections: Vec<DiagramConnection>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DiagramCell {
+    pub kind: CellKind,
+    pub location: IVec2,
+    pub inputs: Vec<NeuronId>,
+    pub outputs: Vec<NeuronId>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DiagramNeuron {
+    pub id: NeuronId,
+    pub kind: NeuronKind,
+    /// Inputs have neither a bias nor an activation
+    pub bias: Option<f32>,
+    pub activation: Option<Activation>,
+    /// The value of the last tick, when drawn from a running network
+    pub value: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DiagramConnection {
+    pub from: NeuronId,
+    pub to: NeuronId,
+    pub weight: f32,
+    pub recurrent: bool,
+}
+
+impl Genome {
+    /// Diagrams every neuron and enabled connection, whether or not a cell reads from it.
+    pub fn diagram(&self) -> BrainDiagram {
+        let mut cells: Vec<DiagramCell> = self
+            .cells
+            .map()
+            .iter()
+            .map(|(location, cell)| DiagramCell {
+                kind: cell.kind,
+                location: *location,
+                inputs: cell.inputs.clone(),
+                outputs: cell.outputs.clone(),
+            })
+            .collect();
+        cells.sort_by_key(|cell| (cell.location.x, cell.location.y));
+
+        let order = cells
+            .iter()
+            .flat_map(|cell| cell.inputs.iter().chain(&cell.outputs))
+            .chain(self.neurons.hidden());
+        let neurons = order
+            .filter_map(|id| {
+                let neuron = self.neurons.get(*id)?;
+                let computed = neuron.kind != NeuronKind::Input;
+                Some(DiagramNeuron {
+                    id: *id,
+                    kind: neuron.kind,
+                    bias: computed.then_some(neuron.bias),
+                    activation: computed.then_some(neuron.activation),
+                    value: None,
+                })
+            })
+            .collect();
+
+        let connections = self
+            .neurons
+            .connections()
+            .iter()
+            .filter(|connection| connection.enabled)
+            .map(|connection| DiagramConnection {
+                from: connection.from,
+                to: connection.to,
+                weight: connection.weight,
+                recurrent: connection.recurrent,
+            })
+            .collect();
+
+        BrainDiagram {
+            cells,
+            neurons,
+            connections,
+        }
+    }
+}
+
+impl BrainDiagram {
+    /// A Graphviz digraph, running left to right from inputs to outputs.
+    ///
+    /// Render it with `dot -Tsvg brain.dot -o brain.svg`, or use [`BrainDiagram::to_svg`]
+    /// when Graphviz isn't around.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph brain {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str(
+            "    node [shape=circle, style=filled, fontname=\"Helvetica\", fontsize=10];\n",
+        );
+        dot.push_str("    edge [fontname=\"Helvetica\", fontsize=9];\n");
+
+        let neurons: HashMap<NeuronId, &DiagramNeuron> = self
+            .neurons
+            .iter()
+            .map(|neuron| (neuron.id, neuron))
+            .collect();
+        let mut clustered = Vec::new();
+        for (i, cell) in self.cells.iter().enumerate() {
+            let _ = writeln!(dot, "    subgraph cluster_{i} {{");
+            let _ = writeln!(dot, "        label=\"{}\";", escape_dot(&cell.title()));
+            dot.push_str("        style=rounded;\n");
+            for id in cell.inputs.iter().chain(&cell.outputs) {
+                if let Some(neuron) = neurons.get(id) {
+                    let _ = writeln!(dot, "        {};", dot_node(neuron, self.name(neuron)));
+                    clustered.push(*id);
+                }
+            }
+            dot.push_str("    }\n");
+        }
+        for neuron in &self.neurons {
+            if !clustered.contains(&neuron.id) {
+                let _ = writeln!(dot, "    {};", dot_node(neuron, self.name(neuron)));
+            }
+        }
+
+        for connection in &self.connections {
+            let _ = writeln!(
+                dot,
+                "    \"n{}\" -> \"n{}\" [label=\"{:.2}\", color=\"{}\", penwidth={:.2}{}];",
+                connection.from,
+                connection.to,
+                connection.weight,
+                edge_color(connection.weight),
+                pen_width(connection.weight),
+                if connection.recurrent {
+                    ", style=dashed, constraint=false"
+                } else {
+                    ""
+                },
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// A standalone SVG image, laid out without Graphviz.
+    ///
+    /// Columns are laid out like the brain viewer's, see [`layers`]. A cell's inputs and
+    /// outputs are each boxed and labelled with the cell. Recurrent connections are dashed.
+    pub fn to_svg(&self) -> String {
+        let positions = self.svg_positions();
+        let width = positions
+            .values()
+            .map(|position| position.x)
+            .fold(0., f32::max)
+            + SVG_MARGIN;
+        let height = positions
+            .values()
+            .map(|position| position.y)
+            .fold(0., f32::max)
+            + SVG_MARGIN;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.0} {height:.0}" font-family="Helvetica, Arial, sans-serif" font-size="10">"#
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+        for cell in &self.cells {
+            for side in [&cell.inputs, &cell.outputs] {
+                let ys: Vec<f32> = side
+                    .iter()
+                    .filter_map(|id| positions.get(id))
+                    .map(|position| position.y)
+                    .collect();
+                let Some(x) = side.iter().find_map(|id| positions.get(id)).map(|p| p.x) else {
+                    continue;
+                };
+                let top = ys.iter().copied().fold(f32::MAX, f32::min) - SVG_ROW * 0.5;
+                let bottom = ys.iter().copied().fold(f32::MIN, f32::max) + SVG_ROW * 0.5;
+                let left = x - SVG_COLUMN * 0.4;
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{left:.1}" y="{top:.1}" width="{:.1}" height="{:.1}" rx="8" fill="none" stroke="#9ca3af"/>"##,
+                    SVG_COLUMN * 0.8,
+                    bottom - top,
+                );
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{:.1}" y="{:.1}" fill="#4b5563">{}</text>"##,
+                    left + 6.,
+                    top + 12.,
+                    escape_xml(&cell.title()),
+                );
+            }
+        }
+
+        for connection in &self.connections {
+            let (Some(from), Some(to)) = (
+                positions.get(&connection.from),
+                positions.get(&connection.to),
+            ) else {
+                continue;
+            };
+            let dash = if connection.recurrent {
+                r#" stroke-dasharray="6 4""#
+            } else {
+                ""
+            };
+            let middle = from.lerp(*to, 0.5);
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{:.2}"{dash}/>"#,
+                from.x,
+                from.y,
+                to.x,
+                to.y,
+                edge_color(connection.weight),
+                pen_width(connection.weight),
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{:.2}</text>"#,
+                middle.x,
+                middle.y - 4.,
+                connection.weight,
+            );
+        }
+
+        for neuron in &self.neurons {
+            let Some(position) = positions.get(&neuron.id) else {
+                continue;
+            };
+            let _ = writeln!(
+                svg,
+                r##"<circle cx="{:.1}" cy="{:.1}" r="{SVG_RADIUS}" fill="{}" stroke="#374151"/>"##,
+                position.x,
+                position.y,
+                fill(neuron.kind),
+            );
+            for (line, text) in self.name(neuron).lines().enumerate() {
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                    position.x,
+                    position.y + SVG_RADIUS + 12. * (line + 1) as f32,
+                    escape_xml(text),
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// What a neuron is shown as, one fact per line.
+    fn name(&self, neuron: &DiagramNeuron) -> String {
+        let place = self.cells.iter().find_map(|cell| {
+            if let Some(i) = cell.inputs.iter().position(|id| *id == neuron.id) {
+                Some(format!("{:?} in {i}", cell.kind))
+            } else {
+                let i = cell.outputs.iter().position(|id| *id == neuron.id)?;
+                Some(format!("{:?} out {i}", cell.kind))
+            }
+        });
+        let mut name = place.unwrap_or_else(|| format!("Hidden {}", neuron.id));
+        if let Some(activation) = neuron.activation {
+            let _ = write!(name, "\n{activation}");
+        }
+        if let Some(bias) = neuron.bias {
+            let _ = write!(name, "\nbias {bias:.2}");
+        }
+        if let Some(value) = neuron.value {
+            let _ = write!(name, "\n= {value:.2}");
+        }
+        name
+    }
+
+    /// Where each neuron goes in [`BrainDiagram::to_svg`], from the top left corner.
+    fn svg_positions(&self) -> HashMap<NeuronId, Vec2> {
+        let index: HashMap<NeuronId, usize> = self
+            .neurons
+            .iter()
+            .enumerate()
+            .map(|(i, neuron)| (neuron.id, i))
+            .collect();
+        let kinds: Vec<NeuronKind> = self.neurons.iter().map(|neuron| neuron.kind).collect();
+        let groups: Vec<usize> = self
+            .neurons
+            .iter()
+            .map(|neuron| {
+                self.cells
+                    .iter()
+                    .position(|cell| {
+                        cell.inputs.contains(&neuron.id) || cell.outputs.contains(&neuron.id)
+                    })
+                    .unwrap_or(self.cells.len())
+            })
+            .collect();
+        let edges: Vec<(usize, usize)> = self
+            .connections
+            .iter()
+            .filter(|connection| !connection.recurrent)
+            .filter_map(|connection| {
+                Some((*index.get(&connection.from)?, *index.get(&connection.to)?))
+            })
+            .collect();
+
+        let grid = layers(&kinds, &groups, &edges);
+        let top = grid.iter().map(|at| at.y).fold(0., f32::max);
+        self.neurons
+            .iter()
+            .zip(grid)
+            .map(|(neuron, at)| {
+                (
+                    neuron.id,
+                    Vec2::new(
+                        SVG_MARGIN + at.x * SVG_COLUMN,
+                        SVG_MARGIN + (top - at.y) * SVG_ROW,
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+impl DiagramCell {
+    fn title(&self) -> String {
+        format!("{:?} ({}, {})", self.kind, self.location.x, self.location.y)
+    }
+}
+
+fn dot_node(neuron: &DiagramNeuron, name: String) -> String {
+    let shape = match neuron.kind {
+        NeuronKind::Input => ", shape=box",
+        NeuronKind::Hidden => "",
+        NeuronKind::Output => ", shape=doublecircle",
+    };
+    format!(
+        "\"n{}\" [label=\"{}\", fillcolor=\"{}\"{shape}]",
+        neuron.id,
+        escape_dot(&name),
+        fill(neuron.kind),
+    )
+}
+
+fn fill(kind: NeuronKind) -> &'static str {
+    match kind {
+        NeuronKind::Input => INPUT_FILL,
+        NeuronKind::Hidden => HIDDEN_FILL,
+        NeuronKind::Output => OUTPUT_FILL,
+    }
+}
+
+/// The brain viewer's signal colors
+fn edge_color(weight: f32) -> String {
+    if weight < 0. {
+        NEGATIVE_SIGNAL.to_hex()
+    } else {
+        POSITIVE_SIGNAL.to_hex()
+    }
+}
+
+fn pen_width(weight: f32) -> f32 {
+    (0.5 + weight.abs()).min(MAX_PEN)
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+use {
+    crate::{
+        cpu_net::CpuNetwork,
+        genome::{Neuron, NeuronArena},
+        node_visual::{COLUMN_SPACING, layered},
+    },
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_dot_has_a_cluster_per_cell_and_an_edge_per_connection() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::simple_linear(&mut rng);
+    let diagram = genome.diagram();
+
+    let dot = diagram.to_dot();
+
+    assert!(dot.starts_with("digraph brain {"));
+    assert_eq!(
+        dot.matches("subgraph cluster_").count(),
+        genome.cells().len()
+    );
+    assert_eq!(
+        dot.matches(" -> ").count(),
+        genome.neurons().connections().len()
+    );
+    let hidden = genome.hidden_neurons()[0];
+    let bias = genome.neurons()[hidden].bias;
+    assert!(dot.contains(&format!("\"n{hidden}\" [label=\"Hidden {hidden}\\n")));
+    assert!(dot.contains(&format!("bias {bias:.2}")));
+}
+
+#[test]
+fn test_network_diagram_matches_genome() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::simple_linear(&mut rng);
+    let (network, cells) = CpuNetwork::new(&genome);
+
+    let sorted = |mut diagram: BrainDiagram| {
+        diagram.neurons.sort_by_key(|neuron| neuron.id);
+        diagram
+            .connections
+            .sort_by_key(|connection| (connection.from, connection.to));
+        diagram
+    };
+
+    let from_genome = sorted(genome.diagram());
+    let from_network =
+        sorted(network.diagram(cells.iter().map(|(location, cell)| (*location, cell)), None));
+
+    assert_eq!(from_network, from_genome);
+}
+
+#[test]
+fn test_svg_draws_every_neuron_and_connection() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::sandbox(&mut rng);
+    let diagram = genome.diagram();
+
+    let svg = diagram.to_svg();
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(svg.matches("<circle ").count(), diagram.neurons.len());
+    assert_eq!(svg.matches("<line ").count(), diagram.connections.len());
+    // one box per side of a cell that has neurons on it
+    let boxes = diagram
+        .cells
+        .iter()
+        .map(|cell| !cell.inputs.is_empty() as usize + !cell.outputs.is_empty() as usize)
+        .sum::<usize>();
+    assert_eq!(svg.matches(r#"rx="8""#).count(), boxes);
+}
+
+#[test]
+fn test_svg_is_laid_out_and_colored_like_the_viewer() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::sandbox(&mut rng);
+    let diagram = genome.diagram();
+    let (network, cells) = CpuNetwork::new(&genome);
+    let mut cells: Vec<_> = cells.into_iter().collect();
+    cells.sort_by_key(|(location, _)| (location.x, location.y));
+
+    // the same column for every neuron, counted from the left
+    let columns = |positions: HashMap<NeuronId, Vec2>, spacing: f32| {
+        let left = positions.values().map(|at| at.x).fold(f32::MAX, f32::min);
+        let mut columns: Vec<(NeuronId, i32)> = positions
+            .into_iter()
+            .map(|(id, at)| (id, ((at.x - left) / spacing).round() as i32))
+            .collect();
+        columns.sort();
+        columns
+    };
+    let viewer = layered(&network, cells.iter().map(|(_, cell)| cell));
+    assert_eq!(
+        columns(diagram.svg_positions(), SVG_COLUMN),
+        columns(viewer, COLUMN_SPACING)
+    );
+
+    assert_eq!(edge_color(1.), POSITIVE_SIGNAL.to_hex());
+    assert_eq!(edge_color(-1.), NEGATIVE_SIGNAL.to_hex());
+}
+
+#[test]
+fn test_svg_keeps_each_cells_outputs_together() {
+    // each eye input feeds one output of both feet, so sorting outputs one by one would
+    // interleave the feet
+    let mut arena = NeuronArena::default();
+    let mut id = || arena.insert(Neuron::input());
+    let eye = DiagramCell {
+        kind: CellKind::Eye,
+        location: IVec2::new(0, 0),
+        inputs: vec![id(), id()],
+        outputs: Vec::new(),
+    };
+    let feet: Vec<DiagramCell> = (1..3)
+        .map(|x| DiagramCell {
+            kind: CellKind::Foot,
+            location: IVec2::new(x, 0),
+            inputs: Vec::new(),
+            outputs: vec![id(), id()],
+        })
+        .collect();
+    let neuron = |id, kind| DiagramNeuron {
+        id,
+        kind,
+        bias: None,
+        activation: None,
+        value: None,
+    };
+    let connection = |from, to| DiagramConnection {
+        from,
+        to,
+        weight: 1.,
+        recurrent: false,
+    };
+    let mut neurons: Vec<DiagramNeuron> = eye
+        .inputs
+        .iter()
+        .map(|id| neuron(*id, NeuronKind::Input))
+        .collect();
+    let mut connections = Vec::new();
+    for foot in &feet {
+        for (input, output) in eye.inputs.iter().zip(&foot.outputs) {
+            neurons.push(neuron(*output, NeuronKind::Output));
+            connections.push(connection(*input, *output));
+        }
+    }
+    let diagram = BrainDiagram {
+        cells: [eye].into_iter().chain(feet).collect(),
+        neurons,
+        connections,
+    };
+
+    let positions = diagram.svg_positions();
+    let span = |cell: &DiagramCell| {
+        let heights = cell.outputs.iter().map(|id| positions[id].y);
+        let top = heights.clone().fold(f32::MAX, f32::min);
+        let bottom = heights.fold(f32::MIN, f32::max);
+        (top, bottom)
+    };
+    let (first, second) = (span(&diagram.cells[1]), span(&diagram.cells[2]));
+    assert!(
+        first.1 < second.0 || second.1 < first.0,
+        "{first:?} and {second:?} overlap"
+    );
+}
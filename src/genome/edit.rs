@@ -0,0 +1,250 @@
+use crate::genome::{
+    Connection, Genome, NeuronId, NeuronKind, activations::Activation, decycler::Cleaner,
+};
+
+/// A change made by hand to a genome's network.
+///
+/// Connections are picked by the neurons at either end, so an edit to one applies to every
+/// connection between the same two neurons.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BrainEdit {
+    SetWeight {
+        from: NeuronId,
+        to: NeuronId,
+        weight: f32,
+    },
+    SetBias {
+        neuron: NeuronId,
+        bias: f32,
+    },
+    SetActivation {
+        neuron: NeuronId,
+        activation: Activation,
+    },
+    /// Makes `to` read from `from`, or re-enables the connection with this weight if it
+    /// already does
+    Connect {
+        from: NeuronId,
+        to: NeuronId,
+        weight: f32,
+    },
+    Disconnect {
+        from: NeuronId,
+        to: NeuronId,
+    },
+    /// Only hidden neurons can go, since inputs and outputs belong to their cell
+    RemoveHidden(NeuronId),
+}
+
+impl Genome {
+    /// Applies `edit`, then cleans up any cycle it closed.
+    ///
+    /// Unless cycles are kept as recurrent connections, that means a new connection closing
+    /// a cycle is cut right away. Returns `false`, leaving the genome as it was, if the edit
+    /// doesn't fit: a neuron is missing, the connection doesn't exist, or an input would be
+    /// given a bias or activation.
+    pub fn edit(&mut self, edit: BrainEdit) -> bool {
+        let applied = match edit {
+            BrainEdit::SetWeight { from, to, weight } => {
+                self.for_connections(from, to, |connection| connection.weight = weight)
+            }
+            BrainEdit::SetBias { neuron, bias } => match self.neurons.get_mut(neuron) {
+                Some(neuron) if neuron.takes_input() => {
+                    neuron.bias = bias;
+                    true
+                }
+                _ => false,
+            },
+            BrainEdit::SetActivation { neuron, activation } => match self.neurons.get_mut(neuron) {
+                Some(neuron) if neuron.takes_input() => {
+                    neuron.activation = activation;
+                    true
+                }
+                _ => false,
+            },
+            BrainEdit::Connect { from, to, weight } => {
+                self.for_connections(from, to, |connection| {
+                    connection.weight = weight;
+                    connection.enabled = true;
+                }) || self.neurons.connect(from, to, weight)
+            }
+            BrainEdit::Disconnect { from, to } => {
+                let before = self.neurons.connections().len();
+                self.neurons.retain_connections(|connection| {
+                    connection.from != from || connection.to != to
+                });
+                self.neurons.connections().len() < before
+            }
+            BrainEdit::RemoveHidden(neuron) => {
+                self.neurons
+                    .get(neuron)
+                    .is_some_and(|neuron| neuron.kind == NeuronKind::Hidden)
+                    && self.neurons.remove(neuron).is_some()
+            }
+        };
+        if applied {
+            Cleaner::new(self).clean();
+        }
+        applied
+    }
+
+    /// Runs `change` on every connection from `from` to `to`, returning whether there were any.
+    fn for_connections(
+        &mut self,
+        from: NeuronId,
+        to: NeuronId,
+        mut change: impl FnMut(&mut Connection),
+    ) -> bool {
+        let mut found = false;
+        for connection in self.neurons.connections_mut() {
+            if connection.from == from && connection.to == to {
+                change(connection);
+                found = true;
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::genome::decycler::CycleMode,
+    bevy::math::IVec2,
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_weights_bias_and_activation_are_set() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+    let output = genome.cells().get(&IVec2::new(1, 0)).unwrap().outputs[0];
+
+    assert!(genome.edit(BrainEdit::SetWeight {
+        from: hidden,
+        to: output,
+        weight: -2.,
+    }));
+    assert!(genome.edit(BrainEdit::SetBias {
+        neuron: hidden,
+        bias: 0.5,
+    }));
+    assert!(genome.edit(BrainEdit::SetActivation {
+        neuron: hidden,
+        activation: Activation::Tanh,
+    }));
+
+    assert_eq!(genome.neurons().inputs(output).next().unwrap().weight, -2.);
+    assert_eq!(genome.neurons()[hidden].bias, 0.5);
+    assert_eq!(genome.neurons()[hidden].activation, Activation::Tanh);
+}
+
+#[test]
+fn test_edits_that_dont_fit_change_nothing() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let before = genome.snapshot();
+    let input = genome.cells().get(&IVec2::new(0, 0)).unwrap().inputs[0];
+    let output = genome.cells().get(&IVec2::new(1, 0)).unwrap().outputs[0];
+
+    // there's no direct connection between them
+    assert!(!genome.edit(BrainEdit::SetWeight {
+        from: input,
+        to: output,
+        weight: 3.,
+    }));
+    assert!(!genome.edit(BrainEdit::Disconnect {
+        from: input,
+        to: output,
+    }));
+    // inputs only hold what their cell writes
+    assert!(!genome.edit(BrainEdit::SetBias {
+        neuron: input,
+        bias: 1.,
+    }));
+    // outputs belong to their cell
+    assert!(!genome.edit(BrainEdit::RemoveHidden(output)));
+    // and nothing reads from an output
+    assert!(!genome.edit(BrainEdit::Connect {
+        from: output,
+        to: input,
+        weight: 1.,
+    }));
+
+    assert_eq!(genome.snapshot(), before);
+}
+
+#[test]
+fn test_connecting_twice_keeps_one_connection() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let input = genome.cells().get(&IVec2::new(0, 0)).unwrap().inputs[0];
+    let output = genome.cells().get(&IVec2::new(1, 0)).unwrap().outputs[0];
+
+    for weight in [1., 0.25] {
+        assert!(genome.edit(BrainEdit::Connect {
+            from: input,
+            to: output,
+            weight,
+        }));
+    }
+
+    let direct: Vec<_> = genome
+        .neurons()
+        .inputs(output)
+        .filter(|connection| connection.from == input)
+        .collect();
+    assert_eq!(direct.len(), 1);
+    assert_eq!(direct[0].weight, 0.25);
+
+    assert!(genome.edit(BrainEdit::Disconnect {
+        from: input,
+        to: output,
+    }));
+    assert!(genome.neurons().inputs(output).all(|c| c.from != input));
+}
+
+#[test]
+fn test_removing_a_hidden_neuron_drops_its_connections() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+
+    assert!(genome.edit(BrainEdit::RemoveHidden(hidden)));
+
+    assert!(!genome.neurons().contains(hidden));
+    assert!(genome.neurons().connections().is_empty());
+}
+
+#[test]
+fn test_loops_are_cleaned_up() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+    let loop_back = BrainEdit::Connect {
+        from: hidden,
+        to: hidden,
+        weight: 1.,
+    };
+
+    // cut right away when cycles aren't allowed
+    assert!(genome.edit(loop_back));
+    assert_eq!(
+        genome
+            .neurons()
+            .inputs(hidden)
+            .filter(|c| c.from == hidden)
+            .count(),
+        0
+    );
+
+    genome.set_cycle_mode(CycleMode::Recurrent);
+    assert!(genome.edit(loop_back));
+    let looped = genome
+        .neurons()
+        .inputs(hidden)
+        .find(|c| c.from == hidden)
+        .unwrap();
+    assert!(looped.recurrent);
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use bevy::{math::IVec2, reflect::Reflect};
+use serde::{Deserialize, Serialize};
+
+use crate::cell::CellKind;
+
+/// A historical marking, shared by every copy of a gene however far it was inherited.
+///
+/// Markings are hashes of how a gene came to be: which cell slot a neuron belongs to, which
+/// neurons a connection joins, which connection a hidden neuron split. Two genomes that make
+/// the same change independently agree on its marking, without a shared counter, so
+/// evolution stays reproducible from a seed.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Reflect,
+)]
+#[serde(transparent)]
+pub struct Innovation(pub u64);
+
+const CELL_INPUT: u64 = 1;
+const CELL_OUTPUT: u64 = 2;
+const CONNECTION: u64 = 3;
+const SPLIT: u64 = 4;
+const SALT: u64 = 5;
+const DUPLICATE: u64 = 6;
+
+impl Innovation {
+    /// The `slot`th input or output neuron of a `kind` cell at `location`.
+    pub fn cell_neuron(location: IVec2, kind: CellKind, output: bool, slot: usize) -> Self {
+        let tag = if output { CELL_OUTPUT } else { CELL_INPUT };
+        Self::of(&[
+            tag,
+            location.x as u64,
+            location.y as u64,
+            kind as u64,
+            slot as u64,
+        ])
+    }
+
+    /// A connection from the neuron marked `from` into the one marked `to`.
+    pub fn connection(from: Self, to: Self) -> Self {
+        Self::of(&[CONNECTION, from.0, to.0])
+    }
+
+    /// A hidden neuron added in the middle of `connection`.
+    pub fn split(connection: Self) -> Self {
+        Self::of(&[SPLIT, connection.0])
+    }
+
+    /// A hidden neuron copied from the one marked `original`.
+    pub fn duplicate(original: Self) -> Self {
+        Self::of(&[DUPLICATE, original.0])
+    }
+
+    /// The `salt`th alternative to this marking, for when it's already taken.
+    pub fn salted(self, salt: u32) -> Self {
+        Self::of(&[SALT, self.0, salt as u64])
+    }
+
+    fn of(parts: &[u64]) -> Self {
+        Self(
+            parts
+                .iter()
+                .fold(0x2545_f491_4f6c_dd1d, |hash, part| splitmix(hash ^ part)),
+        )
+    }
+}
+
+/// A fixed mixing function, so markings mean the same thing in every build.
+fn splitmix(mut n: u64) -> u64 {
+    n = n.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    n = (n ^ (n >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    n = (n ^ (n >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    n ^ (n >> 31)
+}
+
+impl fmt::Display for Innovation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:016x}", self.0)
+    }
+}
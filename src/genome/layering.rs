@@ -0,0 +1,147 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::genome::NeuronKind;
+
+/// Times the columns are reordered, each way, to untangle edges
+const SWEEPS: usize = 4;
+
+/// Columns and rows for a graph of neurons, shared by the brain viewer and
+/// [`BrainDiagram::to_svg`](crate::genome::BrainDiagram::to_svg).
+///
+/// `edges` index into `kinds`, and should leave out recurrent connections. `groups` gives
+/// the cell each neuron belongs to, and only matters for outputs. Inputs take column `0`
+/// in the order they come in, and outputs the last column. Hidden neurons sit one column
+/// past the deepest neuron they read from. Hidden columns are then reordered a few times
+/// by the average height of each neuron's neighbours, which undoes most edge crossings.
+/// Outputs are reordered too, but a group at a time, so each cell's outputs stay together.
+///
+/// Each position is a column along `x` and a height along `y`, with every column centered
+/// on zero and higher rows first.
+pub fn layers(kinds: &[NeuronKind], groups: &[usize], edges: &[(usize, usize)]) -> Vec<Vec2> {
+    let count = kinds.len();
+    let mut senders = vec![Vec::new(); count];
+    let mut receivers = vec![Vec::new(); count];
+    for (from, to) in edges {
+        senders[*to].push(*from);
+        receivers[*from].push(*to);
+    }
+
+    // longest path from the inputs, in topological order. Anything on a cycle that slipped
+    // through keeps the depth it had when the cycle was reached
+    let mut depth: Vec<usize> = kinds
+        .iter()
+        .map(|kind| (*kind != NeuronKind::Input) as usize)
+        .collect();
+    let mut waiting: Vec<usize> = senders.iter().map(Vec::len).collect();
+    let mut ready: Vec<usize> = (0..count).filter(|neuron| waiting[*neuron] == 0).collect();
+    while let Some(neuron) = ready.pop() {
+        for receiver in &receivers[neuron] {
+            depth[*receiver] = depth[*receiver].max(depth[neuron] + 1);
+            waiting[*receiver] -= 1;
+            if waiting[*receiver] == 0 {
+                ready.push(*receiver);
+            }
+        }
+    }
+    let last = (0..count)
+        .filter(|neuron| kinds[*neuron] != NeuronKind::Output)
+        .map(|neuron| depth[neuron] + 1)
+        .max()
+        .unwrap_or(1);
+
+    let mut columns = vec![Vec::new(); last + 1];
+    let mut column_of = vec![0; count];
+    for (neuron, kind) in kinds.iter().enumerate() {
+        column_of[neuron] = match kind {
+            NeuronKind::Input => 0,
+            NeuronKind::Hidden => depth[neuron],
+            NeuronKind::Output => last,
+        };
+        columns[column_of[neuron]].push(neuron);
+    }
+
+    let mut heights = vec![0.; count];
+    for column in &columns {
+        place(column, &mut heights);
+    }
+    for _ in 0..SWEEPS {
+        for column in &mut columns[1..last] {
+            reorder(column, &senders, &mut heights);
+        }
+        reorder_groups(&mut columns[last], groups, &senders, &mut heights);
+        for column in columns.iter_mut().skip(1).rev().skip(1) {
+            reorder(column, &receivers, &mut heights);
+        }
+    }
+
+    (0..count)
+        .map(|neuron| Vec2::new(column_of[neuron] as f32, heights[neuron]))
+        .collect()
+}
+
+/// Spreads `column` top to bottom, centered on zero.
+fn place(column: &[usize], heights: &mut [f32]) {
+    let middle = (column.len() as f32 - 1.) * 0.5;
+    for (row, neuron) in column.iter().enumerate() {
+        heights[*neuron] = middle - row as f32;
+    }
+}
+
+/// Sorts `column` by the average height of each neuron's `neighbours`.
+///
+/// Neurons without any keep their height, and ties keep their order.
+fn reorder(column: &mut [usize], neighbours: &[Vec<usize>], heights: &mut [f32]) {
+    let mut keyed = barycenters(column, neighbours, heights);
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (slot, (_, neuron)) in column.iter_mut().zip(keyed) {
+        *slot = neuron;
+    }
+    place(column, heights);
+}
+
+/// Like [`reorder`], but keeps neurons of the same group next to each other.
+///
+/// Groups are sorted by the average of their neurons, then each group on its own.
+fn reorder_groups(
+    column: &mut [usize],
+    groups: &[usize],
+    neighbours: &[Vec<usize>],
+    heights: &mut [f32],
+) {
+    let mut blocks: Vec<(f32, Vec<(f32, usize)>)> = Vec::new();
+    let mut block_of = HashMap::new();
+    for (height, neuron) in barycenters(column, neighbours, heights) {
+        let block = *block_of.entry(groups[neuron]).or_insert_with(|| {
+            blocks.push((0., Vec::new()));
+            blocks.len() - 1
+        });
+        blocks[block].1.push((height, neuron));
+    }
+    for (average, members) in &mut blocks {
+        *average = members.iter().map(|(height, _)| height).sum::<f32>() / members.len() as f32;
+        members.sort_by(|a, b| b.0.total_cmp(&a.0));
+    }
+    blocks.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let sorted = blocks.into_iter().flat_map(|(_, members)| members);
+    for (slot, (_, neuron)) in column.iter_mut().zip(sorted) {
+        *slot = neuron;
+    }
+    place(column, heights);
+}
+
+/// Average height of each neuron's `neighbours`, or its own height if it has none.
+fn barycenters(column: &[usize], neighbours: &[Vec<usize>], heights: &[f32]) -> Vec<(f32, usize)> {
+    column
+        .iter()
+        .map(|neuron| {
+            let around = &neighbours[*neuron];
+            let height = if around.is_empty() {
+                heights[*neuron]
+            } else {
+                around.iter().map(|n| heights[*n]).sum::<f32>() / around.len() as f32
+            };
+            (height, *neuron)
+        })
+        .collect()
+}
@@ -1,14 +1,19 @@
 pub mod activations;
 
-mod replicator;
-use replicator::*;
-
 mod neuron;
 pub use neuron::*;
 
 mod cell_map;
 pub use cell_map::*;
 
+mod innovation;
+pub use innovation::*;
+
+mod crossover;
+
+mod compatibility;
+pub use compatibility::*;
+
 mod mutation;
 pub use mutation::*;
 
@@ -19,70 +24,128 @@ pub mod decycler;
 mod direction;
 pub use direction::*;
 
+mod snapshot;
+pub use snapshot::*;
+
+mod contiguity;
+pub use contiguity::*;
+
+mod edit;
+pub use edit::*;
+
+mod diagram;
+pub use diagram::*;
+
+mod layering;
+pub use layering::*;
+
 use bevy::prelude::*;
 use rand::Rng;
 
+/// Cloning a genome copies its whole network, so the clone can be mutated on its own.
 #[derive(Debug, Clone)]
 pub struct Genome {
     pub(crate) cells: CellMap,
-    pub(crate) hidden: Vec<NeuronTopology<Hidden>>,
+    /// Every neuron of every cell, plus the hidden ones, and all their connections.
+    pub(crate) neurons: NeuronArena,
     pub(crate) mutation: MutationChances,
+    /// The activation functions mutations are allowed to pick from.
+    pub(crate) activations: ActivationSet,
+    /// Whether cycles are cut or kept as recurrent connections.
+    pub(crate) cycles: CycleMode,
 }
 impl Genome {
     pub fn sandbox(rng: &mut impl Rng) -> Self {
         let template = [
             (CellKind::Eye, IVec2::new(0, 0)),
             (CellKind::Launcher, IVec2::new(1, 1)),
-            (CellKind::Data, IVec2::new(-1, -1)),
+            (CellKind::Data, IVec2::new(-1, 0)),
+            (CellKind::Mouth, IVec2::new(0, 1)),
         ];
 
         let mut this = Self {
             cells: CellMap::default(),
-            hidden: Vec::new(),
+            neurons: NeuronArena::default(),
             mutation: MutationChances::new(20),
+            activations: ActivationSet::all(),
+            cycles: CycleMode::default(),
         };
 
         //outputs first
         for (kind, location) in template {
-            this.cells.add_cell(location, kind, rng);
+            this.cells
+                .add_cell_with(location, kind, &mut this.neurons, this.activations, rng);
         }
         let mut hidden_nodes = Vec::new();
 
-        for cell in this.cells.map_mut().values_mut() {
-            for output in cell.outputs.iter_mut() {
+        for cell in this.cells.map().values() {
+            for output in &cell.outputs {
                 //go 1:1 between hidden and output nodes
-                let hidden = NeuronTopology::hidden(rng);
-                output.add_input(&hidden);
+                let innovation = Innovation::split(this.neurons[*output].innovation);
+                let hidden = this
+                    .neurons
+                    .insert(Neuron::hidden(this.activations, rng).with_innovation(innovation));
+                this.neurons.connect(hidden, *output, 1.);
                 hidden_nodes.push(hidden);
             }
         }
 
-        for cell in this.cells.map_mut().values_mut() {
-            for hidden_node in hidden_nodes.iter_mut() {
-                for input in cell.inputs.iter() {
-                    hidden_node.add_input(input);
+        for hidden_node in &hidden_nodes {
+            for cell in this.cells.map().values() {
+                for input in &cell.inputs {
+                    this.neurons.connect(*input, *hidden_node, 1.);
                 }
             }
         }
-        this.hidden = hidden_nodes;
 
         this
     }
     pub fn cells(&self) -> &CellMap {
         &self.cells
     }
-
-    pub fn deep_clone(&self) -> Genome {
-        let replicator = Replicator::new(self);
-        replicator.replicate()
+    pub fn neurons(&self) -> &NeuronArena {
+        &self.neurons
+    }
+    pub fn activations(&self) -> ActivationSet {
+        self.activations
+    }
+    /// Restricts the activations that future mutations can pick.
+    ///
+    /// Neurons that already exist keep their activation.
+    pub fn set_activations(&mut self, activations: ActivationSet) {
+        self.activations = activations;
+    }
+    pub fn cycle_mode(&self) -> CycleMode {
+        self.cycles
+    }
+    /// Switches between cutting cycles and keeping them as recurrent connections.
+    ///
+    /// Takes effect the next time the genome is cleaned, usually on its next mutation.
+    pub fn set_cycle_mode(&mut self, cycles: CycleMode) {
+        self.cycles = cycles;
     }
 
     pub fn scramble(&mut self, rng: &mut impl Rng) {
+        self.scramble_with(Contiguity::Ignore, rng);
+    }
+
+    /// [`Genome::scramble`], skipping cell deletions that would split the body if
+    /// `contiguity` is [`Contiguity::Reject`].
+    pub fn scramble_with(&mut self, contiguity: Contiguity, rng: &mut impl Rng) {
         self.mutation.adjust_mutation_chances(rng);
         let mut mutation_iter = self.mutation.yield_mutations(rng);
 
         while let Some(action) = mutation_iter.next(rng) {
-            action.perform(&mut self.cells, &mut self.hidden, rng);
+            if contiguity == Contiguity::Reject && action == MutationAction::DeleteCell {
+                // picks the cell the same way the mutation would
+                if let Some(location) = self.cells.random_location(rng)
+                    && !self.cells.would_split(location)
+                {
+                    self.cells.remove(&location, &mut self.neurons);
+                }
+                continue;
+            }
+            action.perform(&mut self.cells, &mut self.neurons, self.activations, rng);
         }
 
         Cleaner::new(self).clean();
@@ -93,8 +156,10 @@ impl Genome {
     pub fn empty() -> Self {
         Self {
             cells: CellMap::default(),
-            hidden: Vec::new(),
+            neurons: NeuronArena::default(),
             mutation: MutationChances::new(50),
+            activations: ActivationSet::all(),
+            cycles: CycleMode::default(),
         }
     }
 
@@ -104,31 +169,24 @@ impl Genome {
         let mut genome = Self::empty();
 
         // Add an Eye cell (input)
-        genome.cells.add_cell(IVec2::new(0, 0), CellKind::Eye, rng);
+        genome.add_cell(IVec2::new(0, 0), CellKind::Eye, rng);
 
         // Add a Launcher cell (output)
-        genome
-            .cells
-            .add_cell(IVec2::new(1, 0), CellKind::Launcher, rng);
+        genome.add_cell(IVec2::new(1, 0), CellKind::Launcher, rng);
 
         // Connect them through a hidden neuron
-        let hidden = NeuronTopology::hidden(rng);
+        let hidden = genome.add_hidden(rng);
 
         // Connect input to hidden
-        if let Some(eye_cell) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-            for input in &eye_cell.inputs {
-                hidden.add_input(input);
-            }
+        for input in genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs.clone() {
+            genome.neurons.connect(input, hidden, 1.);
         }
 
         // Connect hidden to output
-        if let Some(launcher_cell) = genome.cells.map_mut().get_mut(&IVec2::new(1, 0)) {
-            for output in &launcher_cell.outputs {
-                output.add_input(&hidden);
-            }
+        for output in genome.cells.get(&IVec2::new(1, 0)).unwrap().outputs.clone() {
+            genome.neurons.connect(hidden, output, 1.);
         }
 
-        genome.hidden.push(hidden);
         genome
     }
 
@@ -138,12 +196,30 @@ impl Genome {
         let mut genome = Self::empty();
 
         for (kind, location) in cells {
-            genome.cells.add_cell(location, kind, rng);
+            genome.add_cell(location, kind, rng);
         }
 
         genome
     }
 
+    /// Add a cell with fresh neurons, replacing any cell already there
+    #[cfg(test)]
+    pub fn add_cell(
+        &mut self,
+        location: IVec2,
+        kind: CellKind,
+        rng: &mut impl Rng,
+    ) -> Option<crate::cell::CellGenome> {
+        self.cells.add_cell(location, kind, &mut self.neurons, rng)
+    }
+
+    /// Add an unconnected hidden neuron
+    #[cfg(test)]
+    pub fn add_hidden(&mut self, rng: &mut impl Rng) -> NeuronId {
+        self.neurons
+            .insert(Neuron::hidden(ActivationSet::all(), rng))
+    }
+
     /// Get the number of cells
     #[cfg(test)]
     pub fn cell_count(&self) -> usize {
@@ -153,23 +229,23 @@ impl Genome {
     /// Get the number of hidden neurons
     #[cfg(test)]
     pub fn hidden_count(&self) -> usize {
-        self.hidden.len()
+        self.neurons.hidden().len()
     }
 
     /// Get hidden neurons for testing
     #[cfg(test)]
-    pub fn hidden_neurons(&self) -> &[NeuronTopology<Hidden>] {
-        &self.hidden
-    }
-
-    /// Get mutable hidden neurons for testing
-    #[cfg(test)]
-    pub fn hidden_neurons_mut(&mut self) -> &mut Vec<NeuronTopology<Hidden>> {
-        &mut self.hidden
+    pub fn hidden_neurons(&self) -> &[NeuronId] {
+        self.neurons.hidden()
     }
 }
 
-use crate::{cell::CellKind, genome::decycler::Cleaner};
+use crate::{
+    cell::CellKind,
+    genome::{
+        activations::ActivationSet,
+        decycler::{Cleaner, CycleMode},
+    },
+};
 
 #[cfg(test)]
 use {
@@ -195,7 +271,7 @@ fn test_genome_sandbox_construction() {
     let genome = Genome::sandbox(&mut rng);
 
     // Sandbox should have specific cells
-    assert_eq!(genome.cell_count(), 3, "Sandbox should have 3 cells");
+    assert_eq!(genome.cell_count(), 4, "Sandbox should have 4 cells");
 
     // Check specific cell locations
     assert!(
@@ -207,8 +283,8 @@ fn test_genome_sandbox_construction() {
         "Should have cell at (1,1)"
     );
     assert!(
-        genome.cells().get(&IVec2::new(-1, -1)).is_some(),
-        "Should have cell at (-1,-1)"
+        genome.cells().get(&IVec2::new(-1, 0)).is_some(),
+        "Should have cell at (-1,0)"
     );
 
     // Check cell types
@@ -221,9 +297,13 @@ fn test_genome_sandbox_construction() {
         CellKind::Launcher
     );
     assert_eq!(
-        genome.cells().get(&IVec2::new(-1, -1)).unwrap().kind,
+        genome.cells().get(&IVec2::new(-1, 0)).unwrap().kind,
         CellKind::Data
     );
+    assert_eq!(
+        genome.cells().get(&IVec2::new(0, 1)).unwrap().kind,
+        CellKind::Mouth
+    );
 
     // Should have hidden neurons (one per output)
     assert!(
@@ -309,7 +389,7 @@ fn test_cell_map_find_free_spot() {
     let mut rng = StdRng::seed_from_u64(42);
 
     // Add a cell at origin
-    genome.cells.add_cell(IVec2::ZERO, CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::ZERO, CellKind::Eye, &mut rng);
 
     // Find free spot should not return origin
     let free_spot = genome.cells.find_free_spot(&mut rng);
@@ -336,9 +416,7 @@ fn test_cell_map_find_free_spot_multiple() {
     for x in -1..=1 {
         for y in -1..=1 {
             if x != 0 || y != 0 {
-                genome
-                    .cells
-                    .add_cell(IVec2::new(x, y), CellKind::Foot, &mut rng);
+                genome.add_cell(IVec2::new(x, y), CellKind::Foot, &mut rng);
             }
         }
     }
@@ -359,7 +437,7 @@ fn test_cell_map_num_inputs_outputs() {
         vec![
             (CellKind::Eye, IVec2::new(0, 0)),      // 2 inputs, 0 outputs
             (CellKind::Launcher, IVec2::new(1, 0)), // 0 inputs, 3 outputs
-            (CellKind::Data, IVec2::new(2, 0)),     // 4 inputs, 4 outputs
+            (CellKind::Data, IVec2::new(2, 0)),     // 4 inputs, 8 outputs
             (CellKind::Foot, IVec2::new(3, 0)),     // 0 inputs, 0 outputs
         ],
         &mut rng,
@@ -369,8 +447,102 @@ fn test_cell_map_num_inputs_outputs() {
 
     // 2 + 0 + 4 + 0
     assert_eq!(num_inputs, 6, "Should count all inputs");
-    // 3 + 0 + 4 + 0
-    assert_eq!(num_outputs, 7, "Should count all outputs");
+    // 3 + 0 + 8 + 0
+    assert_eq!(num_outputs, 11, "Should count all outputs");
+}
+
+#[test]
+fn test_cell_map_change_kind_keeps_slots() {
+    let mut rng = StdRng::seed_from_u64(238102);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+    let (eye, launcher) = (IVec2::new(0, 0), IVec2::new(1, 0));
+    let eye_inputs = genome.cells.get(&eye).unwrap().inputs.clone();
+    let launcher_outputs = genome.cells.get(&launcher).unwrap().outputs.clone();
+
+    // a launcher has three outputs and a foot two, so the last one goes
+    let previous = genome.cells.change_kind(
+        launcher,
+        CellKind::Foot,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+    assert_eq!(previous, Some(CellKind::Launcher));
+    let foot = genome.cells.get(&launcher).unwrap();
+    assert_eq!(foot.kind, CellKind::Foot);
+    assert_eq!(foot.outputs, launcher_outputs[..2]);
+    assert!(!genome.neurons.contains(launcher_outputs[2]));
+    for output in &foot.outputs {
+        let senders: Vec<_> = genome.neurons.inputs(*output).map(|c| c.from).collect();
+        assert_eq!(senders, vec![hidden]);
+    }
+
+    // an eye has two inputs and a data cell four, so two fresh ones are added, along with
+    // all eight outputs
+    genome.cells.change_kind(
+        eye,
+        CellKind::Data,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+    let data = genome.cells.get(&eye).unwrap();
+    assert_eq!(data.inputs[..2], eye_inputs[..]);
+    assert_eq!(data.inputs.len(), 4);
+    assert_eq!(data.outputs.len(), 8);
+    let senders: Vec<_> = genome.neurons.inputs(hidden).map(|c| c.from).collect();
+    assert_eq!(senders, eye_inputs);
+
+    // nothing to change where there's no cell
+    let missing = genome.cells.change_kind(
+        IVec2::new(9, 9),
+        CellKind::Eye,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+    assert_eq!(missing, None);
+}
+
+#[test]
+fn test_changed_cells_are_marked_like_added_ones() {
+    let mut rng = StdRng::seed_from_u64(238102);
+    let mut changed = Genome::simple_linear(&mut rng);
+    let launcher = IVec2::new(1, 0);
+    changed.cells.change_kind(
+        launcher,
+        CellKind::Foot,
+        &mut changed.neurons,
+        changed.activations,
+        &mut rng,
+    );
+
+    let mut added = Genome::empty();
+    added.add_cell(launcher, CellKind::Foot, &mut rng);
+
+    let markings = |genome: &Genome| {
+        let cell = genome.cells.get(&launcher).unwrap();
+        cell.inputs
+            .iter()
+            .chain(&cell.outputs)
+            .map(|id| genome.neurons[*id].innovation)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(markings(&changed), markings(&added));
+
+    // connections into the kept outputs follow their new markings
+    let hidden = changed.hidden_neurons()[0];
+    for output in &changed.cells.get(&launcher).unwrap().outputs {
+        let connection = changed.neurons.inputs(*output).next().unwrap();
+        assert_eq!(
+            connection.innovation,
+            Innovation::connection(
+                changed.neurons[hidden].innovation,
+                changed.neurons[*output].innovation
+            )
+        );
+    }
 }
 
 #[test]
@@ -379,9 +551,7 @@ fn test_cell_map_add_and_remove() {
 
     let mut rng = StdRng::seed_from_u64(238102);
     // Add cell
-    let replaced = genome
-        .cells
-        .add_cell(IVec2::new(5, 5), CellKind::Eye, &mut rng);
+    let replaced = genome.add_cell(IVec2::new(5, 5), CellKind::Eye, &mut rng);
     assert!(
         replaced.is_none(),
         "Should return None when adding to empty location"
@@ -389,9 +559,7 @@ fn test_cell_map_add_and_remove() {
     assert_eq!(genome.cell_count(), 1);
 
     // Replace cell at same location
-    let replaced = genome
-        .cells
-        .add_cell(IVec2::new(5, 5), CellKind::Launcher, &mut rng);
+    let replaced = genome.add_cell(IVec2::new(5, 5), CellKind::Launcher, &mut rng);
     assert!(
         replaced.is_some(),
         "Should return previous cell when replacing"
@@ -404,13 +572,14 @@ fn test_cell_map_add_and_remove() {
     assert_eq!(genome.cell_count(), 1, "Count should still be 1");
 
     // Remove cell
-    let removed = genome.cells.remove(&IVec2::new(5, 5));
+    let removed = genome.cells.remove(&IVec2::new(5, 5), &mut genome.neurons);
     assert!(removed.is_some(), "Should return removed cell");
     assert_eq!(removed.unwrap().kind, CellKind::Launcher);
     assert_eq!(genome.cell_count(), 0, "Should be empty after removal");
+    assert!(genome.neurons.is_empty(), "Cell neurons go with the cell");
 
     // Remove from empty location
-    let removed = genome.cells.remove(&IVec2::new(5, 5));
+    let removed = genome.cells.remove(&IVec2::new(5, 5), &mut genome.neurons);
     assert!(
         removed.is_none(),
         "Should return None when removing from empty location"
@@ -434,7 +603,7 @@ fn test_cell_requirements() {
 
     let data_req = CellKind::Data.requirements();
     assert_eq!(data_req.num_inputs, 4);
-    assert_eq!(data_req.num_outputs, 4);
+    assert_eq!(data_req.num_outputs, 8);
 }
 
 #[test]
@@ -443,18 +612,10 @@ fn test_cell_creation_respects_requirements() {
     let mut rng = StdRng::seed_from_u64(238102);
 
     // Add each type of cell
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(2, 0), CellKind::Data, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(3, 0), CellKind::Foot, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(2, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(3, 0), CellKind::Foot, &mut rng);
 
     // Check Eye
     let eye = genome.cells.get(&IVec2::new(0, 0)).unwrap();
@@ -469,7 +630,7 @@ fn test_cell_creation_respects_requirements() {
     // Check Data
     let data = genome.cells.get(&IVec2::new(2, 0)).unwrap();
     assert_eq!(data.inputs.len(), 4, "Data should have 4 inputs");
-    assert_eq!(data.outputs.len(), 4, "Data should have 4 outputs");
+    assert_eq!(data.outputs.len(), 8, "Data should have 8 outputs");
 
     // Check Collagen
     let collagen = genome.cells.get(&IVec2::new(3, 0)).unwrap();
@@ -483,18 +644,14 @@ fn test_genome_hidden_neuron_management() {
     let mut rng = StdRng::seed_from_u64(238102);
 
     // Add hidden neurons
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
-    let h3 = NeuronTopology::hidden(&mut rng);
-
-    genome.hidden_neurons_mut().push(h1);
-    genome.hidden_neurons_mut().push(h2);
-    genome.hidden_neurons_mut().push(h3);
+    genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
+    genome.add_hidden(&mut rng);
 
     assert_eq!(genome.hidden_count(), 3, "Should have 3 hidden neurons");
 
     // Remove one
-    genome.hidden_neurons_mut().swap_remove(1);
+    genome.neurons.remove(h2);
     assert_eq!(
         genome.hidden_count(),
         2,
@@ -549,54 +706,35 @@ fn test_genome_with_interconnected_cells() {
     let mut rng = StdRng::seed_from_u64(238102);
 
     // Create a small network
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Data, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(2, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(2, 0), CellKind::Launcher, &mut rng);
 
     // Add hidden neurons and create connections
-    let h1 = NeuronTopology::hidden(&mut rng);
-    let h2 = NeuronTopology::hidden(&mut rng);
+    let h1 = genome.add_hidden(&mut rng);
+    let h2 = genome.add_hidden(&mut rng);
 
-    // Connect Eye inputs to h1
-    if let Some(eye) = genome.cells.map_mut().get_mut(&IVec2::new(0, 0)) {
-        for input in &eye.inputs {
-            h1.add_input(input);
-        }
+    let eye = genome.cells.get(&IVec2::new(0, 0)).unwrap().clone();
+    let data = genome.cells.get(&IVec2::new(1, 0)).unwrap().clone();
+    let launcher = genome.cells.get(&IVec2::new(2, 0)).unwrap().clone();
+
+    // Connect Eye and Data inputs to h1
+    for input in eye.inputs.iter().chain(&data.inputs) {
+        genome.neurons.connect(*input, h1, 1.);
     }
 
     // Connect h1 to h2
-    h2.add_input(&h1);
-
-    // Connect Data cell
-    if let Some(data) = genome.cells.map_mut().get_mut(&IVec2::new(1, 0)) {
-        // Data inputs to h1
-        for input in &data.inputs {
-            h1.add_input(input);
-        }
-        // h2 to Data outputs
-        for output in &data.outputs {
-            output.add_input(&h2);
-        }
-    }
+    genome.neurons.connect(h1, h2, 1.);
 
-    // Connect h2 to Launcher outputs
-    if let Some(launcher) = genome.cells.map_mut().get_mut(&IVec2::new(2, 0)) {
-        for output in &launcher.outputs {
-            output.add_input(&h2);
-        }
+    // Connect h2 to Data and Launcher outputs
+    for output in data.outputs.iter().chain(&launcher.outputs) {
+        genome.neurons.connect(h2, *output, 1.);
     }
 
-    genome.hidden_neurons_mut().push(h1);
-    genome.hidden_neurons_mut().push(h2);
-
     assert_eq!(genome.cell_count(), 3, "Should have 3 cells");
     assert_eq!(genome.hidden_count(), 2, "Should have 2 hidden neurons");
+    assert_eq!(genome.neurons.inputs(h1).count(), 6);
+    assert_eq!(genome.neurons.connections().len(), 6 + 1 + 11);
 }
 
 #[test]
@@ -672,23 +810,19 @@ fn test_genome_sandbox_has_connections() {
     // Check that outputs have inputs (hidden neurons)
     for cell in genome.cells().map().values() {
         for output in &cell.outputs {
-            output.with_ref(|neuron| {
-                assert!(
-                    !neuron.inputs().is_empty(),
-                    "Sandbox outputs should have connections"
-                );
-            });
+            assert!(
+                genome.neurons().inputs(*output).next().is_some(),
+                "Sandbox outputs should have connections"
+            );
         }
     }
 
     // Check that hidden neurons have inputs
     for hidden in genome.hidden_neurons() {
-        hidden.with_ref(|neuron| {
-            assert!(
-                !neuron.inputs().is_empty(),
-                "Sandbox hidden neurons should have inputs"
-            );
-        });
+        assert!(
+            genome.neurons().inputs(*hidden).next().is_some(),
+            "Sandbox hidden neurons should have inputs"
+        );
     }
 }
 
@@ -698,15 +832,9 @@ fn test_cell_at_negative_coordinates() {
 
     let mut rng = StdRng::seed_from_u64(238102);
     // Test cells at negative coordinates work properly
-    genome
-        .cells
-        .add_cell(IVec2::new(-100, -100), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(-50, 50), CellKind::Launcher, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(75, -75), CellKind::Data, &mut rng);
+    genome.add_cell(IVec2::new(-100, -100), CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::new(-50, 50), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(75, -75), CellKind::Data, &mut rng);
 
     assert_eq!(genome.cell_count(), 3);
     assert!(genome.cells.get(&IVec2::new(-100, -100)).is_some());
@@ -726,3 +854,122 @@ fn test_genome_accessors() {
     let hidden = genome.hidden_neurons();
     assert!(!hidden.is_empty(), "Should have hidden neurons");
 }
+
+#[test]
+fn test_clone_preserves_structure() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::sandbox(&mut rng);
+    for _ in 0..5 {
+        genome.scramble(&mut rng);
+    }
+
+    let cloned = genome.clone();
+
+    // ids are stable, so the clone lines up with the original neuron by neuron
+    assert_eq!(genome.hidden_neurons(), cloned.hidden_neurons());
+    assert_eq!(genome.neurons.connections(), cloned.neurons.connections());
+    assert_eq!(genome.snapshot(), cloned.snapshot());
+}
+
+#[test]
+fn test_cloned_genome_independence() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let original = Genome::sandbox(&mut rng);
+    let mut cloned = original.clone();
+
+    let original_cell_count = original.cell_count();
+    let original_hidden_count = original.hidden_count();
+    let original_connections = original.neurons.connections().len();
+
+    // Mutate the clone
+    for _ in 0..5 {
+        MutationAction::AddCell.perform(
+            &mut cloned.cells,
+            &mut cloned.neurons,
+            cloned.activations,
+            &mut rng,
+        );
+    }
+    let hidden = cloned.hidden_neurons()[0];
+    cloned.neurons.remove(hidden);
+    cloned
+        .neurons
+        .get_mut(cloned.hidden_neurons()[0])
+        .unwrap()
+        .bias = 10.;
+
+    // Original should be unchanged
+    assert_eq!(original.cell_count(), original_cell_count);
+    assert_eq!(original.hidden_count(), original_hidden_count);
+    assert_eq!(original.neurons.connections().len(), original_connections);
+    assert!(original.neurons.contains(hidden));
+    assert_ne!(
+        original
+            .neurons
+            .get(cloned.hidden_neurons()[0])
+            .unwrap()
+            .bias,
+        10.
+    );
+
+    // Clone should have changed
+    assert_ne!(cloned.cell_count(), original_cell_count);
+}
+
+#[test]
+fn test_offspring_are_reproducible() {
+    let mut rng = StdRng::seed_from_u64(18912);
+    let parent = Genome::sandbox(&mut rng);
+
+    let offspring = |seed: u64| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut child = parent.clone();
+        for _ in 0..10 {
+            child.scramble(&mut rng);
+            child = child.clone();
+        }
+        child.snapshot()
+    };
+
+    assert_eq!(offspring(7), offspring(7));
+}
+
+#[test]
+fn test_large_genome_clone() {
+    let mut genome = Genome::empty();
+    let mut rng = StdRng::seed_from_u64(42);
+
+    // Create a large genome
+    for i in 0..20 {
+        for j in 0..20 {
+            let cell_kind = match (i + j) % 4 {
+                0 => CellKind::Eye,
+                1 => CellKind::Launcher,
+                2 => CellKind::Data,
+                _ => CellKind::Foot,
+            };
+            genome.add_cell(IVec2::new(i, j), cell_kind, &mut rng);
+        }
+    }
+
+    // Add many hidden neurons
+    for _ in 0..100 {
+        genome.add_hidden(&mut rng);
+    }
+
+    // Add connections
+    for _ in 0..50 {
+        MutationAction::AddConnection.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+
+    let cloned = genome.clone();
+
+    assert_eq!(genome.cell_count(), cloned.cell_count());
+    assert_eq!(genome.hidden_count(), cloned.hidden_count());
+    assert_eq!(genome.neurons.len(), cloned.neurons.len());
+}
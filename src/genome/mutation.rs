@@ -2,14 +2,16 @@ use rand::{
     Rng,
     seq::{IndexedMutRandom, IteratorRandom},
 };
+use serde::{Deserialize, Serialize};
 use strum::{EnumCount, EnumIter, IntoEnumIterator};
 
 use crate::genome::{
-    CellKind, CellMap, Hidden, NeuronTopology,
+    CellKind, CellMap, NeuronArena,
+    activations::ActivationSet,
     mutator::{ConnectionTask, Mutator, OutputTask},
 };
 
-#[derive(Copy, Clone, Debug, EnumIter, EnumCount, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, EnumIter, EnumCount, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MutationAction {
     AddCell,
     DeleteCell,
@@ -19,63 +21,98 @@ pub enum MutationAction {
     RemoveNeuron,
     MutateWeight,
     MutateActivation,
+    MutateBias,
+    RemoveConnection,
+    ToggleConnection,
+    ReplaceWeight,
+    GaussianPerturbWeight,
+    DuplicateHidden,
 }
 
 impl MutationAction {
     pub fn perform(
         &self,
         cells: &mut CellMap,
-        hidden: &mut Vec<NeuronTopology<Hidden>>,
+        neurons: &mut NeuronArena,
+        activations: ActivationSet,
         rng: &mut impl Rng,
     ) {
         match self {
             MutationAction::AddCell => {
                 let new_cell_kind = CellKind::iter().choose(rng).unwrap();
                 let new_spot = cells.find_free_spot(rng);
-                cells.add_cell(new_spot, new_cell_kind, rng);
+                cells.add_cell_with(new_spot, new_cell_kind, neurons, activations, rng);
             }
             MutationAction::DeleteCell => {
-                if cells.is_empty() {
-                    return;
+                if let Some(location) = cells.random_location(rng) {
+                    cells.remove(&location, neurons);
                 }
-                let rand_index = rng.random_range(0..cells.len());
-                let random_cell_loc = cells.map().keys().nth(rand_index).unwrap();
-                let loc = *random_cell_loc;
-                cells.remove(&loc);
             }
             MutationAction::MutateCell => {
                 if cells.is_empty() {
                     return;
                 }
                 let new_cell_kind = CellKind::iter().choose(rng).unwrap();
-                let rand_index = rng.random_range(0..cells.len());
-                let random_cell_loc = cells.map().keys().nth(rand_index).unwrap();
-                cells.add_cell(*random_cell_loc, new_cell_kind, rng);
+                let location = cells.random_location(rng).unwrap();
+                cells.change_kind(location, new_cell_kind, neurons, activations, rng);
             }
             MutationAction::AddConnection => {
-                Mutator::new(cells, hidden).with_random_input_and_output(rng, ConnectionTask::Add);
+                Mutator::new(cells, neurons, activations)
+                    .with_random_input_and_output(rng, ConnectionTask::Add);
             }
             MutationAction::SplitConnection => {
-                Mutator::new(cells, hidden).with_random_output(rng, OutputTask::Split);
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::Split);
             }
             MutationAction::RemoveNeuron => {
+                let hidden = neurons.hidden();
                 if hidden.is_empty() {
                     return;
                 }
-                let random_index = rng.random_range(0..hidden.len());
-                hidden.swap_remove(random_index);
+                let random_neuron = hidden[rng.random_range(0..hidden.len())];
+                neurons.remove(random_neuron);
             }
             MutationAction::MutateWeight => {
-                Mutator::new(cells, hidden).with_random_output(rng, OutputTask::MutateWeight);
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::MutateWeight);
             }
             MutationAction::MutateActivation => {
-                Mutator::new(cells, hidden).with_random_output(rng, OutputTask::MutateActivation);
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::MutateActivation);
+            }
+            MutationAction::MutateBias => {
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::MutateBias);
+            }
+            MutationAction::RemoveConnection => {
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::RemoveConnection);
+            }
+            MutationAction::ToggleConnection => {
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::ToggleConnection);
+            }
+            MutationAction::ReplaceWeight => {
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::ReplaceWeight);
+            }
+            MutationAction::GaussianPerturbWeight => {
+                Mutator::new(cells, neurons, activations)
+                    .with_random_output(rng, OutputTask::GaussianPerturbWeight);
+            }
+            MutationAction::DuplicateHidden => {
+                let hidden = neurons.hidden();
+                if hidden.is_empty() {
+                    return;
+                }
+                let random_neuron = hidden[rng.random_range(0..hidden.len())];
+                neurons.duplicate(random_neuron);
             }
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MutationChance {
     pub(crate) action: MutationAction,
     pub(crate) chance: f32,
@@ -86,13 +123,13 @@ impl MutationChance {
         let value = rng.random_range(0.0..=5.0);
 
         let add_to = if rng.random_bool(0.5) { -value } else { value };
-        self.chance += add_to;
+        self.chance = (self.chance + add_to).max(0.);
     }
 }
 
 pub const MAX_MUTATIONS: usize = 200;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MutationChances {
     self_mutation: u8,
     chances: Vec<MutationChance>,
@@ -112,6 +149,15 @@ impl MutationChances {
         }
     }
 
+    /// Chances that only ever pick `action`
+    #[cfg(test)]
+    pub fn only(action: MutationAction, self_mutation_rate: u8) -> Self {
+        Self {
+            self_mutation: self_mutation_rate,
+            chances: vec![MutationChance { action, chance: 1. }],
+        }
+    }
+
     pub fn adjust_mutation_chances(&mut self, rng: &mut impl Rng) {
         const MAX_LOOP: u8 = 10;
         let mut loop_count = 0;
@@ -176,7 +222,7 @@ impl<'a> MutationIter<'a> {
     }
 
     pub fn next(&mut self, rng: &mut impl Rng) -> Option<MutationAction> {
-        if !self.keep_yielding || self.count >= MAX_MUTATIONS {
+        if !self.keep_yielding || self.count >= MAX_MUTATIONS || self.total <= 0. {
             return None;
         }
         let mut chance = rng.random_range(0_f32..self.total);
@@ -209,7 +255,12 @@ fn test_mutation_action_add_cell() {
 
     // Add a few cells through mutation
     for _ in 0..5 {
-        MutationAction::AddCell.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::AddCell.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     assert_eq!(genome.cell_count(), 5, "Should have added 5 cells");
@@ -238,10 +289,20 @@ fn test_mutation_action_delete_cell() {
 
     assert_eq!(genome.cell_count(), 3);
 
-    MutationAction::DeleteCell.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+    MutationAction::DeleteCell.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
     assert_eq!(genome.cell_count(), 2, "Should have deleted one cell");
 
-    MutationAction::DeleteCell.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+    MutationAction::DeleteCell.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
     assert_eq!(genome.cell_count(), 1, "Should have deleted another cell");
 }
 
@@ -251,7 +312,12 @@ fn test_mutation_action_delete_cell_empty_genome() {
     let mut genome = Genome::empty();
 
     // Should not panic when deleting from empty genome
-    MutationAction::DeleteCell.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+    MutationAction::DeleteCell.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
     assert_eq!(genome.cell_count(), 0, "Should still be empty");
 }
 
@@ -262,7 +328,12 @@ fn test_mutation_action_mutate_cell() {
 
     // Mutate the cell multiple times
     for _ in 0..10 {
-        MutationAction::MutateCell.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::MutateCell.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     // Cell count should remain the same
@@ -273,10 +344,33 @@ fn test_mutation_action_mutate_cell() {
     // Just verify we still have a valid cell
     assert!(matches!(
         cell.kind,
-        CellKind::Eye | CellKind::Launcher | CellKind::Data | CellKind::Foot
+        CellKind::Eye | CellKind::Launcher | CellKind::Data | CellKind::Foot | CellKind::Mouth
     ));
 }
 
+#[test]
+fn test_mutation_action_mutate_cell_keeps_wiring() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+
+    for _ in 0..20 {
+        let before = genome.neurons.connections().to_vec();
+        MutationAction::MutateCell.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+
+        // only connections to slots the new kind doesn't have are dropped
+        for connection in before {
+            if genome.neurons.contains(connection.from) && genome.neurons.contains(connection.to) {
+                assert!(genome.neurons.connections().contains(&connection));
+            }
+        }
+    }
+}
+
 #[test]
 fn test_mutation_action_add_connection() {
     let mut rng = StdRng::seed_from_u64(42);
@@ -286,7 +380,12 @@ fn test_mutation_action_add_connection() {
 
     // Add connections multiple times
     for _ in 0..5 {
-        MutationAction::AddConnection.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::AddConnection.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     // Hidden count should remain the same (AddConnection doesn't create new neurons)
@@ -302,7 +401,12 @@ fn test_mutation_action_split_connection() {
 
     // Split connections should create new hidden neurons
     for _ in 0..3 {
-        MutationAction::SplitConnection.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::SplitConnection.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     // Some splits might have succeeded and added hidden neurons
@@ -317,12 +421,17 @@ fn test_mutation_action_remove_neuron() {
 
     // Add some hidden neurons first
     for _ in 0..5 {
-        genome.hidden.push(NeuronTopology::hidden(&mut rng));
+        genome.add_hidden(&mut rng);
     }
 
     let initial_count = genome.hidden_count();
 
-    MutationAction::RemoveNeuron.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+    MutationAction::RemoveNeuron.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
 
     assert_eq!(
         genome.hidden_count(),
@@ -337,7 +446,12 @@ fn test_mutation_action_remove_neuron_empty() {
     let mut genome = Genome::empty();
 
     // Should not panic when removing from empty hidden list
-    MutationAction::RemoveNeuron.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+    MutationAction::RemoveNeuron.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
     assert_eq!(genome.hidden_count(), 0);
 }
 
@@ -348,7 +462,12 @@ fn test_mutation_action_mutate_weight() {
 
     // This should modify weights on existing connections
     for _ in 0..10 {
-        MutationAction::MutateWeight.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::MutateWeight.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     // Just verify it doesn't panic or change structure
@@ -362,13 +481,274 @@ fn test_mutation_action_mutate_activation() {
 
     // This should modify activation functions on neurons
     for _ in 0..10 {
-        MutationAction::MutateActivation.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::MutateActivation.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     // Just verify it doesn't panic or change structure
     assert!(genome.cell_count() > 0);
 }
 
+#[test]
+fn test_mutation_action_mutate_activation_respects_palette() {
+    use crate::genome::activations::{Activation, ActivationSet};
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    genome.set_activations(ActivationSet::from(vec![Activation::Tanh]));
+
+    for _ in 0..50 {
+        MutationAction::MutateActivation.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+
+    // the only hidden neuron is picked often enough to have been rewritten
+    let activation = genome
+        .neurons
+        .get(genome.hidden_neurons()[0])
+        .unwrap()
+        .activation;
+    assert_eq!(activation, Activation::Tanh);
+}
+
+#[test]
+fn test_mutation_action_mutate_bias() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let before = genome.clone();
+
+    for _ in 0..10 {
+        MutationAction::MutateBias.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+
+    // only biases change, and never on inputs, which don't use them
+    assert_eq!(genome.neurons.connections(), before.neurons.connections());
+    let mut changed = false;
+    for (id, neuron) in genome.neurons.iter() {
+        let original = &before.neurons[id];
+        if neuron.kind == NeuronKind::Input {
+            assert_eq!(neuron.bias, original.bias);
+        }
+        changed |= neuron.bias != original.bias;
+    }
+    assert!(changed, "Some bias should have moved");
+}
+
+#[test]
+fn test_mutation_action_remove_connection() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let initial_connections = genome.neurons.connections().len();
+    let initial_hidden_count = genome.hidden_count();
+
+    MutationAction::RemoveConnection.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+
+    assert_eq!(
+        genome.neurons.connections().len(),
+        initial_connections - 1,
+        "Should have removed exactly one connection"
+    );
+    assert_eq!(genome.hidden_count(), initial_hidden_count);
+}
+
+#[test]
+fn test_mutation_action_remove_connection_empty() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::empty();
+
+    // Should not panic without any connections
+    MutationAction::RemoveConnection.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+    assert!(genome.neurons.connections().is_empty());
+}
+
+#[test]
+fn test_mutation_action_toggle_connection() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let initial_connections = genome.neurons.connections().len();
+    let disabled = |genome: &Genome| {
+        genome
+            .neurons
+            .connections()
+            .iter()
+            .filter(|c| !c.enabled)
+            .count()
+    };
+    assert_eq!(disabled(&genome), 0);
+
+    MutationAction::ToggleConnection.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+    assert_eq!(disabled(&genome), 1, "Should have disabled one connection");
+
+    // toggled connections are kept, whichever way they end up
+    for _ in 0..20 {
+        MutationAction::ToggleConnection.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+    assert_eq!(genome.neurons.connections().len(), initial_connections);
+}
+
+#[test]
+fn test_mutation_action_replace_weight() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+
+    for _ in 0..10 {
+        MutationAction::ReplaceWeight.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+
+    // every weight starts at 1, and replacements are drawn from -1..=1
+    let weights: Vec<_> = genome
+        .neurons
+        .connections()
+        .iter()
+        .map(|c| c.weight)
+        .collect();
+    assert!(weights.iter().all(|weight| (-1.0..=1.0).contains(weight)));
+    assert!(weights.iter().any(|weight| *weight != 1.));
+}
+
+#[test]
+fn test_mutation_action_gaussian_perturb_weight() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let initial_connections = genome.neurons.connections().len();
+
+    for _ in 0..10 {
+        MutationAction::GaussianPerturbWeight.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+
+    // nudges are mostly small, so weights stay near where they started
+    let weights: Vec<_> = genome
+        .neurons
+        .connections()
+        .iter()
+        .map(|c| c.weight)
+        .collect();
+    assert_eq!(weights.len(), initial_connections);
+    assert!(weights.iter().any(|weight| *weight != 1.));
+    assert!(weights.iter().all(|weight| (weight - 1.).abs() < 5.));
+}
+
+#[test]
+fn test_mutation_action_duplicate_hidden() {
+    use crate::cpu_net::CpuNetwork;
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let original = genome.hidden_neurons()[0];
+    let evaluate = |genome: &Genome| {
+        let (network, cells) = CpuNetwork::new(genome);
+        let mut values = vec![0.; network.len()];
+        let eye = &cells[&IVec2::new(0, 0)];
+        for i in 0..eye.input_neurons().len() {
+            eye.set(&mut values, i, 0.5);
+        }
+        network.process(&mut values);
+        let launcher = &cells[&IVec2::new(1, 0)];
+        (0..launcher.output_neurons().len())
+            .map(|i| launcher.get(&values, i))
+            .collect::<Vec<_>>()
+    };
+    let before = evaluate(&genome);
+
+    MutationAction::DuplicateHidden.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+
+    assert_eq!(
+        genome.hidden_count(),
+        2,
+        "Should have copied the hidden neuron"
+    );
+    let copy = genome.hidden_neurons()[1];
+    assert_eq!(genome.neurons[copy].bias, genome.neurons[original].bias);
+    assert_eq!(
+        genome.neurons[copy].activation,
+        genome.neurons[original].activation
+    );
+    let senders = |id| {
+        genome
+            .neurons
+            .inputs(id)
+            .map(|c| (c.from, c.weight))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(senders(copy), senders(original));
+
+    // both halves send half as strongly, so nothing downstream notices
+    for output in &genome.cells.get(&IVec2::new(1, 0)).unwrap().outputs {
+        let weights = genome
+            .neurons
+            .inputs(*output)
+            .map(|c| c.weight)
+            .collect::<Vec<_>>();
+        assert_eq!(weights, vec![0.5, 0.5]);
+    }
+    for (before, after) in before.iter().zip(evaluate(&genome)) {
+        assert!((before - after).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_mutation_action_duplicate_hidden_empty() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::empty();
+
+    // Should not panic without hidden neurons
+    MutationAction::DuplicateHidden.perform(
+        &mut genome.cells,
+        &mut genome.neurons,
+        genome.activations,
+        &mut rng,
+    );
+    assert_eq!(genome.hidden_count(), 0);
+}
+
 #[test]
 fn test_mutation_chances_initialization() {
     let chances = MutationChances::new(75);
@@ -458,11 +838,22 @@ fn test_mutation_on_complex_genome() {
         MutationAction::RemoveNeuron,
         MutationAction::MutateWeight,
         MutationAction::MutateActivation,
+        MutationAction::MutateBias,
+        MutationAction::RemoveConnection,
+        MutationAction::ToggleConnection,
+        MutationAction::ReplaceWeight,
+        MutationAction::GaussianPerturbWeight,
+        MutationAction::DuplicateHidden,
     ];
 
     for _ in 0..100 {
         let action_idx = rng.random_range(0..actions.len());
-        actions[action_idx].perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        actions[action_idx].perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 }
 
@@ -472,20 +863,15 @@ fn test_split_connection_creates_hidden() {
 
     // Create a genome with a direct connection we can split
     let mut genome = Genome::empty();
-    genome
-        .cells
-        .add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
-    genome
-        .cells
-        .add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
+    genome.add_cell(IVec2::new(0, 0), CellKind::Eye, &mut rng);
+    genome.add_cell(IVec2::new(1, 0), CellKind::Launcher, &mut rng);
 
     // Connect input to output directly
     let eye_inputs = genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs.clone();
-    if let Some(launcher) = genome.cells.map_mut().get_mut(&IVec2::new(1, 0)) {
-        for output in &launcher.outputs {
-            for input in &eye_inputs {
-                output.add_input(input);
-            }
+    let launcher_outputs = genome.cells.get(&IVec2::new(1, 0)).unwrap().outputs.clone();
+    for output in &launcher_outputs {
+        for input in &eye_inputs {
+            genome.neurons.connect(*input, *output, 1.);
         }
     }
 
@@ -493,7 +879,12 @@ fn test_split_connection_creates_hidden() {
 
     // Try to split connections multiple times
     for _ in 0..10 {
-        MutationAction::SplitConnection.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+        MutationAction::SplitConnection.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
     }
 
     // Should have created at least some hidden neurons
@@ -515,12 +906,22 @@ fn test_mutations_preserve_cell_requirements() {
 
     for cell_kind in cell_types {
         let mut genome = Genome::empty();
-        genome.cells.add_cell(IVec2::new(0, 0), cell_kind, &mut rng);
+        genome.add_cell(IVec2::new(0, 0), cell_kind, &mut rng);
 
         // Apply mutations
         for _ in 0..5 {
-            MutationAction::AddConnection.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
-            MutationAction::MutateWeight.perform(&mut genome.cells, &mut genome.hidden, &mut rng);
+            MutationAction::AddConnection.perform(
+                &mut genome.cells,
+                &mut genome.neurons,
+                genome.activations,
+                &mut rng,
+            );
+            MutationAction::MutateWeight.perform(
+                &mut genome.cells,
+                &mut genome.neurons,
+                genome.activations,
+                &mut rng,
+            );
         }
 
         // Check cell still has correct number of inputs/outputs
@@ -1,156 +1,104 @@
 use rand::Rng;
 
 use crate::genome::{
-    CanBeInput, CellMap, Hidden, NeuronInputType, NeuronTopology, TakesInput, activations,
+    CellMap, Connection, Innovation, Neuron, NeuronArena, NeuronId,
+    activations::{self, ActivationSet},
 };
 
+/// Standard deviation of [`OutputTask::GaussianPerturbWeight`]'s nudges
+const WEIGHT_PERTURBATION: f32 = 0.2;
+
 pub struct Mutator<'a> {
     cells: &'a CellMap,
-    hidden: &'a mut Vec<NeuronTopology<Hidden>>,
+    neurons: &'a mut NeuronArena,
+    activations: ActivationSet,
 }
 
 impl<'a> Mutator<'a> {
-    pub fn new(cells: &'a CellMap, hidden: &'a mut Vec<NeuronTopology<Hidden>>) -> Self {
-        Self { cells, hidden }
+    pub fn new(
+        cells: &'a CellMap,
+        neurons: &'a mut NeuronArena,
+        activations: ActivationSet,
+    ) -> Self {
+        Self {
+            cells,
+            neurons,
+            activations,
+        }
     }
 
     pub fn with_random_output(&mut self, rng: &mut impl Rng, task: OutputTask) {
         let (_, num_outputs) = self.cells.num_inputs_outputs();
-        let neurons_capable_of_taking_input = num_outputs + self.hidden.len();
+        let neurons_capable_of_taking_input = num_outputs + self.neurons.hidden().len();
         if neurons_capable_of_taking_input == 0 {
             return;
         }
         let output_neuron = rng.random_range(0..neurons_capable_of_taking_input);
-
-        let output_is_hidden = output_neuron >= num_outputs;
-        let returned = if output_is_hidden {
-            let output_neuron_i = output_neuron - num_outputs;
-            let output_neuron = &self.hidden[output_neuron_i];
-            task.do_thing(rng, output_neuron)
-        } else {
-            let output_neuron_i = output_neuron;
-            let mut i = 0;
-            let mut returned = OutputTaskReturn::None;
-            'outer: for cell in self.cells.map().values() {
-                for output_neuron in cell.outputs.iter() {
-                    if i == output_neuron_i {
-                        returned = task.do_thing(rng, output_neuron);
-                        break 'outer;
-                    }
-                    i += 1;
-                }
-            }
-            returned
+        let Some(output_neuron) = self.receiver(output_neuron, num_outputs) else {
+            return;
         };
 
-        match returned {
-            OutputTaskReturn::NewHiddenNode(hidden_node) => {
-                self.hidden.push(hidden_node);
-            }
-            OutputTaskReturn::None => {}
-        }
+        task.do_thing(rng, self.activations, self.neurons, output_neuron);
     }
 
-    pub fn with_random_input_and_output(&self, rng: &mut impl Rng, thing_to_do: ConnectionTask) {
+    pub fn with_random_input_and_output(
+        &mut self,
+        rng: &mut impl Rng,
+        thing_to_do: ConnectionTask,
+    ) {
         let (num_inputs, num_outputs) = self.cells.num_inputs_outputs();
-        let neurons_capable_of_taking_input = num_outputs + self.hidden.len();
-        let neurons_capable_of_being_input = num_inputs + self.hidden.len();
+        let num_hidden = self.neurons.hidden().len();
+        let neurons_capable_of_taking_input = num_outputs + num_hidden;
+        let neurons_capable_of_being_input = num_inputs + num_hidden;
         if neurons_capable_of_being_input == 0 || neurons_capable_of_taking_input == 0 {
             return;
         }
 
         let input_neuron = rng.random_range(0..neurons_capable_of_being_input);
-
         let output_neuron = rng.random_range(0..neurons_capable_of_taking_input);
-        //num_outputs = 2, num_hidden = 3;
-        // 5
-        // rand_index picks 1
-        // 1 = num_outputs[1] (last one)
-        //
-        // if 2 >= 2
-        // if 1 >= 2
-        let input_is_hidden = input_neuron >= num_inputs;
-        let output_is_hidden = output_neuron >= num_outputs;
-
-        match (input_is_hidden, output_is_hidden) {
-            (true, true) => {
-                let input_neuron_i = input_neuron - num_inputs;
-                let output_neuron_i = output_neuron - num_outputs;
-                if input_neuron_i == output_neuron_i {
-                    return;
-                }
-                let input_neuron = &self.hidden[input_neuron_i];
-                let output_neuron = &self.hidden[output_neuron_i];
-                thing_to_do.do_thing(input_neuron, output_neuron);
-            }
-            (true, false) => {
-                let input_neuron_i = input_neuron - num_inputs;
-                let output_neuron_i = output_neuron;
-                let mut i = 0;
-                for cell in self.cells.map().values() {
-                    for output_neuron in cell.outputs.iter() {
-                        if i == output_neuron_i {
-                            let input_neuron = &self.hidden[input_neuron_i];
-                            thing_to_do.do_thing(input_neuron, output_neuron);
-                            return;
-                        }
-                        i += 1;
-                    }
-                }
-            }
-            (false, true) => {
-                let input_neuron_i = input_neuron;
-                let output_neuron_i = output_neuron - num_outputs;
-                let mut i = 0;
-                'outer: for cell in self.cells.map().values() {
-                    for input_neuron in cell.inputs.iter() {
-                        if i == input_neuron_i {
-                            let output_neuron = &self.hidden[output_neuron_i];
-                            thing_to_do.do_thing(input_neuron, output_neuron);
-                            break 'outer;
-                        }
-                        i += 1;
-                    }
-                }
-            }
-            (false, false) => {
-                let input_neuron_i = input_neuron;
-                let output_neuron_i = output_neuron;
-
-                let mut found_input_neuron = None;
-                let mut found_output_neuron = None;
-
-                let mut input_index = 0;
-                let mut output_index = 0;
-                for cell in self.cells.map().values() {
-                    if found_input_neuron.is_some() && found_output_neuron.is_some() {
-                        break;
-                    }
-                    if found_input_neuron.is_none() {
-                        for input_neuron in cell.inputs.iter() {
-                            if input_index == input_neuron_i {
-                                found_input_neuron = Some(input_neuron);
-                                break;
-                            }
-                            input_index += 1;
-                        }
-                    }
-                    if found_output_neuron.is_none() {
-                        for output_neuron in cell.outputs.iter() {
-                            if output_index == output_neuron_i {
-                                found_output_neuron = Some(output_neuron);
-                                break;
-                            }
-                            output_index += 1;
-                        }
-                    }
-                }
-                if let Some(input_neuron) = found_input_neuron
-                    && let Some(output_neuron) = found_output_neuron
-                {
-                    thing_to_do.do_thing(input_neuron, output_neuron);
-                }
-            }
+
+        // a hidden neuron never connects to itself
+        if input_neuron >= num_inputs
+            && output_neuron >= num_outputs
+            && input_neuron - num_inputs == output_neuron - num_outputs
+        {
+            return;
+        }
+
+        let (Some(input_neuron), Some(output_neuron)) = (
+            self.sender(input_neuron, num_inputs),
+            self.receiver(output_neuron, num_outputs),
+        ) else {
+            return;
+        };
+        thing_to_do.do_thing(self.neurons, input_neuron, output_neuron);
+    }
+
+    /// The `i`th neuron that can be read from: every cell input, then every hidden neuron.
+    fn sender(&self, i: usize, num_inputs: usize) -> Option<NeuronId> {
+        match i.checked_sub(num_inputs) {
+            Some(hidden) => self.neurons.hidden().get(hidden).copied(),
+            None => self
+                .cells
+                .map()
+                .values()
+                .flat_map(|cell| &cell.inputs)
+                .nth(i)
+                .copied(),
+        }
+    }
+
+    /// The `i`th neuron that can take input: every cell output, then every hidden neuron.
+    fn receiver(&self, i: usize, num_outputs: usize) -> Option<NeuronId> {
+        match i.checked_sub(num_outputs) {
+            Some(hidden) => self.neurons.hidden().get(hidden).copied(),
+            None => self
+                .cells
+                .map()
+                .values()
+                .flat_map(|cell| &cell.outputs)
+                .nth(i)
+                .copied(),
         }
     }
 }
@@ -159,84 +107,99 @@ pub enum ConnectionTask {
 }
 
 impl ConnectionTask {
-    fn do_thing<Input, Output>(
-        &self,
-        input: &NeuronTopology<Input>,
-        output: &NeuronTopology<Output>,
-    ) where
-        NeuronTopology<Input>: CanBeInput,
-        Output: TakesInput,
-    {
+    fn do_thing(&self, neurons: &mut NeuronArena, input: NeuronId, output: NeuronId) {
         match self {
-            Self::Add => output.add_input(input),
+            Self::Add => {
+                neurons.connect(input, output, 1.);
+            }
         }
     }
 }
 
 pub enum OutputTask {
     MutateWeight,
+    /// Draws a new weight, forgetting the old one
+    ReplaceWeight,
+    /// Nudges a weight by a normally distributed amount, mostly small
+    GaussianPerturbWeight,
+    MutateBias,
     Split,
     MutateActivation,
+    RemoveConnection,
+    /// Disables an enabled connection, or enables a disabled one
+    ToggleConnection,
 }
 
 impl OutputTask {
-    fn do_thing<Output>(
+    fn do_thing(
         &self,
         rng: &mut impl Rng,
-        output: &NeuronTopology<Output>,
-    ) -> OutputTaskReturn
-    where
-        Output: TakesInput,
-    {
+        activations: ActivationSet,
+        neurons: &mut NeuronArena,
+        output: NeuronId,
+    ) {
         match self {
             OutputTask::MutateWeight => {
-                output.for_random_input(rng, |input, rng| {
-                    input.weight += rng.random_range(-1.0..=1.0);
-                });
-                OutputTaskReturn::None
+                if let Some(input) = neurons.random_input(output, rng) {
+                    neurons.connection_mut(input).weight += rng.random_range(-1.0..=1.0);
+                }
+            }
+            OutputTask::ReplaceWeight => {
+                if let Some(input) = neurons.random_input(output, rng) {
+                    neurons.connection_mut(input).weight = activations::random_weight(rng);
+                }
+            }
+            OutputTask::GaussianPerturbWeight => {
+                if let Some(input) = neurons.random_input(output, rng) {
+                    neurons.connection_mut(input).weight += gaussian(rng, WEIGHT_PERTURBATION);
+                }
+            }
+            OutputTask::MutateBias => {
+                if let Some(neuron) = neurons.get_mut(output) {
+                    neuron.bias += rng.random_range(-1.0..=1.0);
+                }
+            }
+            OutputTask::RemoveConnection => {
+                if let Some(input) = neurons.random_input(output, rng) {
+                    neurons.remove_connection(input);
+                }
+            }
+            OutputTask::ToggleConnection => {
+                if let Some(input) = neurons.random_input(output, rng) {
+                    let connection = neurons.connection_mut(input);
+                    connection.enabled = !connection.enabled;
+                }
             }
             OutputTask::MutateActivation => {
-                output.with_mut(|lock| {
-                    lock.set_activation(activations::random_activation(rng));
-                });
-                OutputTaskReturn::None
+                if let Some(neuron) = neurons.get_mut(output) {
+                    neuron.activation = activations.random(rng);
+                }
             }
             OutputTask::Split => {
-                let Some(removed_input) = output.with_mut(|lock| {
-                    if lock.inputs().is_empty() {
-                        return None;
-                    }
-                    let remove = rng.random_range(0..lock.inputs().len());
-                    Some(lock.inputs_mut().swap_remove(remove))
-                }) else {
-                    return OutputTaskReturn::None;
+                let Some(removed_input) = neurons.random_input(output, rng) else {
+                    return;
                 };
-
-                let new_hidden_node = NeuronTopology::hidden(rng);
-                match removed_input.input_type {
-                    NeuronInputType::Hidden(input_for_neuron) => {
-                        if let Some(hidden) = input_for_neuron.upgrade() {
-                            new_hidden_node.add_input(&hidden);
-                        } else {
-                            return OutputTaskReturn::None;
-                        }
-                    }
-                    NeuronInputType::Input(input_for_neuron) => {
-                        if let Some(hidden) = input_for_neuron.upgrade() {
-                            new_hidden_node.add_input(&hidden);
-                        } else {
-                            return OutputTaskReturn::None;
-                        }
-                    }
-                }
-
-                output.add_input(&new_hidden_node);
-                OutputTaskReturn::NewHiddenNode(new_hidden_node)
+                let removed_input = neurons.remove_connection(removed_input);
+
+                let new_hidden_node = neurons.insert(
+                    Neuron::hidden(activations, rng)
+                        .with_innovation(Innovation::split(removed_input.innovation)),
+                );
+                // a delayed edge stays delayed on the way into the split
+                neurons.add_new_connection(Connection {
+                    to: new_hidden_node,
+                    weight: 1.,
+                    ..removed_input
+                });
+                neurons.connect(new_hidden_node, output, 1.);
             }
         }
     }
 }
-enum OutputTaskReturn {
-    None,
-    NewHiddenNode(NeuronTopology<Hidden>),
+
+/// A sample from a normal distribution centered on zero, by the Box-Muller transform.
+fn gaussian(rng: &mut impl Rng, std_dev: f32) -> f32 {
+    let radius = (-2. * rng.random_range(f32::EPSILON..1.).ln()).sqrt();
+    let angle = rng.random_range(0.0..std::f32::consts::TAU);
+    std_dev * radius * angle.cos()
 }
@@ -0,0 +1,327 @@
+use std::ops::Index;
+
+use rand::{Rng, seq::IteratorRandom};
+
+use bevy::platform::collections::HashMap;
+
+use crate::genome::{Connection, Innovation, Neuron, NeuronId, NeuronKind};
+
+/// Every neuron of a genome, and the connections between them.
+///
+/// Neurons live in a slot map keyed by [`NeuronId`], and connections are plain records
+/// between two ids. Removing a neuron removes its connections with it, and cloning the
+/// arena copies the whole graph.
+#[derive(Clone, Debug, Default)]
+pub struct NeuronArena {
+    slots: Vec<Slot>,
+    /// Empty slots, reused before the arena grows
+    free: Vec<u32>,
+    /// Hidden neurons in the order they were added, so mutations can pick one by index
+    hidden: Vec<NeuronId>,
+    connections: Vec<Connection>,
+    markings: HashMap<Innovation, NeuronId>,
+    /// How many times each marking has been derived from, so the next one is quick to find
+    salts: HashMap<Innovation, u32>,
+    /// [`Connection::order`] of the next new connection, past that of every connection so far
+    next_order: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Slot {
+    generation: u32,
+    neuron: Option<Neuron>,
+}
+
+impl NeuronArena {
+    /// Adds `neuron`, keeping its marking unless another neuron has it already.
+    pub fn insert(&mut self, mut neuron: Neuron) -> NeuronId {
+        neuron.innovation = self.fresh_innovation(neuron.innovation);
+        let (kind, innovation) = (neuron.kind, neuron.innovation);
+        let id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.neuron = Some(neuron);
+                NeuronId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    neuron: Some(neuron),
+                });
+                NeuronId {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        };
+        if kind == NeuronKind::Hidden {
+            self.hidden.push(id);
+        }
+        self.markings.insert(innovation, id);
+        id
+    }
+
+    /// `innovation` if no neuron has it, otherwise the first salted marking no neuron has.
+    fn fresh_innovation(&mut self, innovation: Innovation) -> Innovation {
+        if !self.markings.contains_key(&innovation) {
+            return innovation;
+        }
+        let salt = self.salts.entry(innovation).or_default();
+        while self.markings.contains_key(&innovation.salted(*salt)) {
+            *salt += 1;
+        }
+        *salt += 1;
+        innovation.salted(*salt - 1)
+    }
+
+    /// Removes a neuron along with every connection to or from it.
+    pub fn remove(&mut self, id: NeuronId) -> Option<Neuron> {
+        let slot = self
+            .slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?;
+        let neuron = slot.neuron.take()?;
+        slot.generation += 1;
+        self.free.push(id.index);
+        self.markings.remove(&neuron.innovation);
+
+        if neuron.kind == NeuronKind::Hidden
+            && let Some(position) = self.hidden.iter().position(|hidden| *hidden == id)
+        {
+            self.hidden.swap_remove(position);
+        }
+        self.connections
+            .retain(|connection| connection.from != id && connection.to != id);
+        Some(neuron)
+    }
+
+    /// Gives a neuron a new marking, and re-derives the markings of its connections to match.
+    pub fn remark(&mut self, id: NeuronId, innovation: Innovation) {
+        let Some(old) = self.get(id).map(|neuron| neuron.innovation) else {
+            return;
+        };
+        if self.markings.get(&old) == Some(&id) {
+            self.markings.remove(&old);
+        }
+        let innovation = self.fresh_innovation(innovation);
+        if let Some(neuron) = self.get_mut(id) {
+            neuron.innovation = innovation;
+        }
+        self.markings.insert(innovation, id);
+
+        for index in 0..self.connections.len() {
+            let Connection { from, to, .. } = self.connections[index];
+            if from == id || to == id {
+                self.connections[index].innovation =
+                    Innovation::connection(self[from].innovation, self[to].innovation);
+            }
+        }
+    }
+
+    /// Copies a hidden neuron along with every connection into and out of it.
+    ///
+    /// Both copies send at half the original's weights, so the network computes what it did
+    /// before. Returns `None` if `id` isn't a hidden neuron.
+    pub fn duplicate(&mut self, id: NeuronId) -> Option<NeuronId> {
+        let neuron = self
+            .get(id)
+            .filter(|neuron| neuron.kind == NeuronKind::Hidden)?
+            .clone();
+        let innovation = Innovation::duplicate(neuron.innovation);
+        let copy = self.insert(neuron.with_innovation(innovation));
+
+        let incoming: Vec<_> = self.inputs(id).copied().collect();
+        for connection in &mut self.connections {
+            if connection.from == id {
+                connection.weight /= 2.;
+            }
+        }
+        let outgoing: Vec<_> = self
+            .connections
+            .iter()
+            .filter(|connection| connection.from == id)
+            .copied()
+            .collect();
+
+        for connection in incoming {
+            self.add_new_connection(Connection {
+                to: copy,
+                ..connection
+            });
+        }
+        for connection in outgoing {
+            self.add_new_connection(Connection {
+                from: copy,
+                ..connection
+            });
+        }
+        Some(copy)
+    }
+
+    pub fn get(&self, id: NeuronId) -> Option<&Neuron> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?
+            .neuron
+            .as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: NeuronId) -> Option<&mut Neuron> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?
+            .neuron
+            .as_mut()
+    }
+
+    pub fn contains(&self, id: NeuronId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NeuronId, &Neuron)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let id = NeuronId {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            Some((id, slot.neuron.as_ref()?))
+        })
+    }
+
+    /// The neuron with this marking.
+    pub fn find(&self, innovation: Innovation) -> Option<NeuronId> {
+        self.markings.get(&innovation).copied()
+    }
+
+    pub fn hidden(&self) -> &[NeuronId] {
+        &self.hidden
+    }
+
+    /// Makes `to` read from `from`.
+    ///
+    /// Returns `false` without connecting if either neuron is missing, `from` is an output
+    /// or `to` is an input.
+    pub fn connect(&mut self, from: NeuronId, to: NeuronId, weight: f32) -> bool {
+        self.add_new_connection(Connection {
+            from,
+            to,
+            weight,
+            recurrent: false,
+            enabled: true,
+            innovation: Innovation::default(),
+            order: 0,
+        })
+    }
+
+    /// Like [`NeuronArena::connect`], keeping every field of `connection` but its marking.
+    ///
+    /// This is for copying a connection over, so it keeps its [`Connection::order`] too.
+    pub fn add_connection(&mut self, mut connection: Connection) -> bool {
+        let (Some(from), Some(to)) = (self.get(connection.from), self.get(connection.to)) else {
+            return false;
+        };
+        if !from.can_be_input() || !to.takes_input() {
+            return false;
+        }
+        connection.innovation = Innovation::connection(from.innovation, to.innovation);
+        self.next_order = self.next_order.max(connection.order + 1);
+        self.connections.push(connection);
+        true
+    }
+
+    /// Like [`NeuronArena::add_connection`], for a connection that's new to the lineage, so
+    /// it's ordered after every other.
+    pub fn add_new_connection(&mut self, connection: Connection) -> bool {
+        let order = self.next_order;
+        self.add_connection(Connection {
+            order,
+            ..connection
+        })
+    }
+
+    pub fn connections(&self) -> &[Connection] {
+        &self.connections
+    }
+
+    pub fn connection_mut(&mut self, index: usize) -> &mut Connection {
+        &mut self.connections[index]
+    }
+
+    pub fn connections_mut(&mut self) -> &mut [Connection] {
+        &mut self.connections
+    }
+
+    pub fn remove_connection(&mut self, index: usize) -> Connection {
+        self.connections.swap_remove(index)
+    }
+
+    pub fn retain_connections(&mut self, keep: impl FnMut(&Connection) -> bool) {
+        self.connections.retain(keep);
+    }
+
+    /// The connections `to` reads from.
+    pub fn inputs(&self, to: NeuronId) -> impl Iterator<Item = &Connection> {
+        self.connections
+            .iter()
+            .filter(move |connection| connection.to == to)
+    }
+
+    /// Index of a random connection into `to`.
+    pub fn random_input(&self, to: NeuronId, rng: &mut impl Rng) -> Option<usize> {
+        self.connections
+            .iter()
+            .enumerate()
+            .filter(|(_, connection)| connection.to == to)
+            .map(|(index, _)| index)
+            .choose(rng)
+    }
+
+    /// Indexes every connection by the neuron reading it, for walking the graph backwards.
+    pub fn incoming(&self) -> Incoming {
+        self.incoming_where(|_| true)
+    }
+
+    /// Like [`NeuronArena::incoming`], leaving out disabled connections, for building networks.
+    pub fn enabled_incoming(&self) -> Incoming {
+        self.incoming_where(|connection| connection.enabled)
+    }
+
+    fn incoming_where(&self, keep: impl Fn(&Connection) -> bool) -> Incoming {
+        let mut incoming = vec![Vec::new(); self.slots.len()];
+        for (index, connection) in self.connections.iter().enumerate() {
+            if keep(connection) {
+                incoming[connection.to.index as usize].push(index);
+            }
+        }
+        Incoming(incoming)
+    }
+}
+
+impl Index<NeuronId> for NeuronArena {
+    type Output = Neuron;
+
+    /// Panics if `id` was removed.
+    fn index(&self, id: NeuronId) -> &Neuron {
+        self.get(id).expect("neuron was removed")
+    }
+}
+
+/// Connection indices grouped by the neuron they feed, in the order they were made.
+pub struct Incoming(Vec<Vec<usize>>);
+
+impl Incoming {
+    pub fn of(&self, id: NeuronId) -> &[usize] {
+        self.0.get(id.index as usize).map_or(&[], Vec::as_slice)
+    }
+}
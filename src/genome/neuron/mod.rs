@@ -1,76 +1,90 @@
-mod topology;
-pub use topology::*;
+mod arena;
+pub use arena::*;
 
 mod neuron_type;
 pub use neuron_type::*;
 
 #[cfg(test)]
 use {
+    crate::genome::{
+        Innovation,
+        activations::{Activation, ActivationSet},
+    },
     pretty_assertions::assert_eq,
     rand::{Rng, SeedableRng, rngs::StdRng},
 };
 
+#[cfg(test)]
+fn add_hidden(arena: &mut NeuronArena, rng: &mut impl Rng) -> NeuronId {
+    arena.insert(Neuron::hidden(ActivationSet::all(), rng))
+}
+
+#[cfg(test)]
+fn add_output(arena: &mut NeuronArena, rng: &mut impl Rng) -> NeuronId {
+    arena.insert(Neuron::output(ActivationSet::all(), rng))
+}
+
 #[test]
 fn test_input_neuron_creation() {
-    let input = NeuronTopology::input();
+    let mut arena = NeuronArena::default();
+    let input = arena.insert(Neuron::input());
 
-    // Input neurons have unique IDs
-    let id = input.id();
-    assert_ne!(id, uuid::Uuid::nil(), "Input should have a valid ID");
+    assert_eq!(arena.get(input).unwrap().kind, NeuronKind::Input);
 
     // Create another input and verify different ID
-    let input2 = NeuronTopology::input();
-    assert_ne!(
-        input.id(),
-        input2.id(),
-        "Different inputs should have different IDs"
-    );
+    let input2 = arena.insert(Neuron::input());
+    assert_ne!(input, input2, "Different inputs should have different IDs");
 }
 
 #[test]
 fn test_hidden_neuron_creation() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
+    let mut arena = NeuronArena::default();
+    let hidden = add_hidden(&mut arena, &mut rng);
 
     // Hidden neurons start with no inputs
-    hidden.with_ref(|neuron| {
-        assert_eq!(
-            neuron.inputs().len(),
-            0,
-            "Hidden should start with no inputs"
-        );
-        assert_eq!(neuron.bias(), 0.0, "Hidden should start with zero bias");
-    });
+    assert_eq!(
+        arena.inputs(hidden).count(),
+        0,
+        "Hidden should start with no inputs"
+    );
+    assert_eq!(
+        arena.get(hidden).unwrap().bias,
+        0.0,
+        "Hidden should start with zero bias"
+    );
 
     // Hidden neurons have unique IDs
-    let hidden2 = NeuronTopology::hidden(&mut rng);
+    let hidden2 = add_hidden(&mut arena, &mut rng);
     assert_ne!(
-        hidden.id(),
-        hidden2.id(),
+        hidden, hidden2,
         "Different hidden neurons should have different IDs"
     );
+    assert_eq!(arena.hidden(), &[hidden, hidden2]);
 }
 
 #[test]
 fn test_output_neuron_creation() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let output = NeuronTopology::output(&mut rng);
+    let mut arena = NeuronArena::default();
+    let output = add_output(&mut arena, &mut rng);
 
     // Output neurons start with no inputs
-    output.with_ref(|neuron| {
-        assert_eq!(
-            neuron.inputs().len(),
-            0,
-            "Output should start with no inputs"
-        );
-        assert_eq!(neuron.bias(), 0.0, "Output should start with zero bias");
-    });
+    assert_eq!(
+        arena.inputs(output).count(),
+        0,
+        "Output should start with no inputs"
+    );
+    assert_eq!(
+        arena.get(output).unwrap().bias,
+        0.0,
+        "Output should start with zero bias"
+    );
 
     // Output neurons have unique IDs
-    let output2 = NeuronTopology::output(&mut rng);
+    let output2 = add_output(&mut arena, &mut rng);
     assert_ne!(
-        output.id(),
-        output2.id(),
+        output, output2,
         "Different outputs should have different IDs"
     );
 }
@@ -78,491 +92,284 @@ fn test_output_neuron_creation() {
 #[test]
 fn test_add_input_to_hidden() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
-    let input1 = NeuronTopology::input();
-    let input2 = NeuronTopology::input();
-
-    // Add inputs
-    hidden.add_input(&input1);
-    hidden.add_input(&input2);
-
-    // Verify inputs were added
-    hidden.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 2, "Should have 2 inputs");
-
-        // Each input should have default weight of 1.0
-        for input in neuron.inputs() {
-            assert_eq!(input.weight, 1.0, "Default weight should be 1.0");
-            assert!(input.is_alive(), "Input should be alive");
-        }
-    });
+    let mut arena = NeuronArena::default();
+    let hidden = add_hidden(&mut arena, &mut rng);
+    let input1 = arena.insert(Neuron::input());
+    let input2 = arena.insert(Neuron::input());
+
+    assert!(arena.connect(input1, hidden, 1.));
+    assert!(arena.connect(input2, hidden, 1.));
+
+    let inputs: Vec<_> = arena.inputs(hidden).collect();
+    assert_eq!(inputs.len(), 2, "Should have 2 inputs");
+    assert_eq!(inputs[0].from, input1);
+    assert_eq!(inputs[1].from, input2);
+    for input in inputs {
+        assert_eq!(input.weight, 1.0);
+    }
 }
 
 #[test]
 fn test_add_input_to_output() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let output = NeuronTopology::output(&mut rng);
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = NeuronTopology::hidden(&mut rng);
-
-    // Add inputs
-    output.add_input(&hidden1);
-    output.add_input(&hidden2);
-
-    // Verify inputs were added
-    output.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 2, "Should have 2 inputs");
-    });
-}
+    let mut arena = NeuronArena::default();
+    let output = add_output(&mut arena, &mut rng);
+    let hidden1 = add_hidden(&mut arena, &mut rng);
+    let hidden2 = add_hidden(&mut arena, &mut rng);
 
-#[test]
-fn test_hidden_can_take_input_neurons() {
-    let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
-    let input = NeuronTopology::input();
-
-    hidden.add_input(&input);
-
-    hidden.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 1);
-        assert!(matches!(
-            neuron.inputs()[0].input_type,
-            NeuronInputType::Input(_)
-        ));
-    });
+    arena.connect(hidden1, output, 1.);
+    arena.connect(hidden2, output, 1.);
+
+    assert_eq!(arena.inputs(output).count(), 2, "Should have 2 inputs");
 }
 
 #[test]
 fn test_hidden_can_take_hidden_neurons() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = NeuronTopology::hidden(&mut rng);
-
-    hidden1.add_input(&hidden2);
-
-    hidden1.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 1);
-        assert!(matches!(
-            neuron.inputs()[0].input_type,
-            NeuronInputType::Hidden(_)
-        ));
-    });
+    let mut arena = NeuronArena::default();
+    let hidden1 = add_hidden(&mut arena, &mut rng);
+    let hidden2 = add_hidden(&mut arena, &mut rng);
+
+    assert!(arena.connect(hidden2, hidden1, 1.));
+
+    let from = arena.inputs(hidden1).next().unwrap().from;
+    assert_eq!(arena.get(from).unwrap().kind, NeuronKind::Hidden);
 }
 
 #[test]
-fn test_weak_reference_cleanup() {
+fn test_invalid_connections_are_refused() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let output = NeuronTopology::output(&mut rng);
-    let hidden = NeuronTopology::hidden(&mut rng);
-
-    // Add hidden as input to output
-    output.add_input(&hidden);
-
-    // Verify connection exists
-    output.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 1);
-        assert!(neuron.inputs()[0].is_alive());
-    });
-
-    // Drop the hidden neuron
-    drop(hidden);
-
-    // Connection should now be dead
-    output.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 1, "Dead connection still exists");
-        assert!(!neuron.inputs()[0].is_alive(), "Connection should be dead");
-    });
+    let mut arena = NeuronArena::default();
+    let input = arena.insert(Neuron::input());
+    let hidden = add_hidden(&mut arena, &mut rng);
+    let output = add_output(&mut arena, &mut rng);
+
+    // outputs only feed their cell, and inputs are only written by theirs
+    assert!(!arena.connect(output, hidden, 1.));
+    assert!(!arena.connect(hidden, input, 1.));
+
+    // both ends have to exist
+    arena.remove(hidden);
+    assert!(!arena.connect(input, hidden, 1.));
+    assert!(arena.connections().is_empty());
 }
 
 #[test]
-fn test_neuron_input_id_retrieval() {
+fn test_removing_a_neuron_drops_its_connections() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
-    let input = NeuronTopology::input();
-
-    let input_id = input.id();
-    hidden.add_input(&input);
-
-    hidden.with_ref(|neuron| {
-        let connection_id = neuron.inputs()[0].id();
-        assert_eq!(
-            connection_id,
-            Some(input_id),
-            "Should retrieve correct input ID"
-        );
-    });
+    let mut arena = NeuronArena::default();
+    let input = arena.insert(Neuron::input());
+    let hidden = add_hidden(&mut arena, &mut rng);
+    let output = add_output(&mut arena, &mut rng);
+
+    arena.connect(input, hidden, 1.);
+    arena.connect(hidden, output, 1.);
+    arena.connect(input, output, 1.);
+
+    arena.remove(hidden);
+
+    // no dead links are left behind
+    assert_eq!(arena.connections().len(), 1);
+    assert_eq!(arena.inputs(output).next().unwrap().from, input);
+    assert!(arena.hidden().is_empty());
 }
 
 #[test]
-fn test_neuron_input_dead_id() {
+fn test_removed_ids_stay_dead() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let output = NeuronTopology::output(&mut rng);
-    {
-        let hidden = NeuronTopology::hidden(&mut rng);
-        output.add_input(&hidden);
-    } // hidden dropped here
-
-    output.with_ref(|neuron| {
-        let connection_id = neuron.inputs()[0].id();
-        assert_eq!(
-            connection_id, None,
-            "Dead connection should return None for ID"
-        );
-    });
+    let mut arena = NeuronArena::default();
+    let removed = add_hidden(&mut arena, &mut rng);
+    arena.remove(removed);
+
+    // the slot is reused, but the old id doesn't resolve to the new neuron
+    let reused = arena.insert(Neuron::input());
+    assert_eq!(reused.index(), removed.index());
+    assert_ne!(reused, removed);
+    assert!(arena.get(removed).is_none());
+    assert!(arena.remove(removed).is_none());
+    assert_eq!(arena.len(), 1);
 }
 
 #[test]
 fn test_bias_manipulation() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
+    let mut arena = NeuronArena::default();
+    let hidden = add_hidden(&mut arena, &mut rng);
 
-    // Set bias
-    hidden.with_mut(|neuron| {
-        *neuron.bias_mut() = 2.5;
-    });
+    arena.get_mut(hidden).unwrap().bias = 2.5;
 
-    // Verify bias was set
-    hidden.with_ref(|neuron| {
-        assert_eq!(neuron.bias(), 2.5, "Bias should be set to 2.5");
-    });
+    assert_eq!(arena.get(hidden).unwrap().bias, 2.5, "Bias should be 2.5");
 }
 
 #[test]
 fn test_activation_function_setting() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
-
-    // Set different activation functions
-    hidden.with_mut(|neuron| {
-        neuron.set_activation(crate::genome::activations::sigmoid);
-    });
-
-    // Verify activation function works
-    hidden.with_ref(|neuron| {
-        let activation = neuron.activation();
-        // Test sigmoid behavior
-        assert!(
-            (activation(0.0) - 0.5).abs() < 0.001,
-            "Sigmoid(0) should be 0.5"
-        );
-        assert!(activation(10.0) > 0.99, "Sigmoid(10) should be close to 1");
-        assert!(
-            activation(-10.0) < 0.01,
-            "Sigmoid(-10) should be close to 0"
-        );
-    });
-
-    // Change to relu
-    hidden.with_mut(|neuron| {
-        neuron.set_activation(crate::genome::activations::relu);
-    });
-
-    hidden.with_ref(|neuron| {
-        let activation = neuron.activation();
-        assert_eq!(activation(-1.0), 0.0, "ReLU(-1) should be 0");
-        assert_eq!(activation(5.0), 5.0, "ReLU(5) should be 5");
-    });
-
-    // Change to linear
-    hidden.with_mut(|neuron| {
-        neuron.set_activation(crate::genome::activations::linear_activation);
-    });
-
-    hidden.with_ref(|neuron| {
-        let activation = neuron.activation();
-        assert_eq!(activation(-5.0), -5.0, "Linear(-5) should be -5");
-        assert_eq!(activation(3.0), 3.0, "Linear(3) should be 3");
-    });
-}
-
-#[test]
-fn test_random_input_selection() {
-    let mut rng = StdRng::seed_from_u64(42);
-    let hidden = NeuronTopology::hidden(&mut rng);
+    let mut arena = NeuronArena::default();
+    let hidden = add_hidden(&mut arena, &mut rng);
 
-    // Add multiple inputs
-    let inputs: Vec<_> = (0..5).map(|_| NeuronTopology::input()).collect();
-    for input in &inputs {
-        hidden.add_input(input);
-    }
-
-    // Select random input and modify weight
-    let modified = hidden.for_random_input(&mut rng, |input, rng| {
-        input.weight += rng.random_range(-1.0..=1.0);
-        true
-    });
-
-    assert_eq!(modified, Some(true), "Should have modified an input");
-
-    // Verify at least one weight changed
-    hidden.with_ref(|neuron| {
-        let non_default_weights = neuron
-            .inputs()
-            .iter()
-            .filter(|input| (input.weight - 1.0).abs() > 0.0001)
-            .count();
-        assert!(
-            non_default_weights > 0,
-            "At least one weight should have changed"
-        );
-    });
-}
+    arena.get_mut(hidden).unwrap().activation = Activation::Sigmoid;
 
-#[test]
-fn test_random_input_on_empty() {
-    let mut rng = StdRng::seed_from_u64(42);
-    let hidden = NeuronTopology::hidden(&mut rng);
-
-    // Try to select random input when there are none
-    let result = hidden.for_random_input(&mut rng, |input, _rng| {
-        input.weight = 5.0;
-        "modified"
-    });
+    let activation = arena.get(hidden).unwrap().activation;
+    assert_eq!(activation, Activation::Sigmoid);
+    assert!(
+        (activation.apply(0.0) - 0.5).abs() < 0.001,
+        "Sigmoid(0) should be 0.5"
+    );
+    assert!(
+        activation.apply(10.0) > 0.99,
+        "Sigmoid(10) should be close to 1"
+    );
+    assert!(
+        activation.apply(-10.0) < 0.01,
+        "Sigmoid(-10) should be close to 0"
+    );
 
-    assert_eq!(result, None, "Should return None when no inputs exist");
+    arena.get_mut(hidden).unwrap().activation = Activation::Relu;
+    let activation = arena.get(hidden).unwrap().activation;
+    assert_eq!(activation.apply(-1.0), 0.0, "ReLU(-1) should be 0");
+    assert_eq!(activation.apply(5.0), 5.0, "ReLU(5) should be 5");
 }
 
 #[test]
-fn test_weight_mutation() {
+fn test_random_input_selection() {
     let mut rng = StdRng::seed_from_u64(42);
-    let output = NeuronTopology::output(&mut rng);
-
-    // Add inputs
-    for _ in 0..3 {
-        let hidden = NeuronTopology::hidden(&mut rng);
-        output.add_input(&hidden);
+    let mut arena = NeuronArena::default();
+    let hidden = add_hidden(&mut arena, &mut rng);
+    let other = add_hidden(&mut arena, &mut rng);
+
+    for _ in 0..5 {
+        let input = arena.insert(Neuron::input());
+        arena.connect(input, hidden, 1.);
+        arena.connect(input, other, 1.);
     }
 
-    // Mutate weights multiple times
-    for _ in 0..10 {
-        output.for_random_input(&mut rng, |input, rng| {
-            input.weight += rng.random_range(-1.0..=1.0);
-        });
+    for _ in 0..20 {
+        let index = arena.random_input(hidden, &mut rng).unwrap();
+        assert_eq!(arena.connections()[index].to, hidden);
+        arena.connection_mut(index).weight += rng.random_range(-1.0..=1.0);
     }
 
-    // Check that weights have been modified
-    output.with_ref(|neuron| {
-        let modified_count = neuron
-            .inputs()
-            .iter()
-            .filter(|input| (input.weight - 1.0).abs() > 0.0001)
-            .count();
-        assert!(modified_count > 0, "Some weights should be modified");
-    });
+    let changed = arena
+        .inputs(hidden)
+        .filter(|input| (input.weight - 1.0).abs() > 0.0001)
+        .count();
+    assert!(changed > 0, "At least one weight should have changed");
+    assert!(arena.inputs(other).all(|input| input.weight == 1.0));
 }
 
 #[test]
-fn test_can_be_input_trait() {
-    let mut rng = StdRng::seed_from_u64(238102);
-    let input_neuron = NeuronTopology::input();
-    let hidden_neuron = NeuronTopology::hidden(&mut rng);
-
-    // Both Input and Hidden implement CanBeInput
-    let input_type_from_input = input_neuron.to_input_type();
-    let input_type_from_hidden = hidden_neuron.to_input_type();
-
-    // Check they produce correct variants
-    assert!(matches!(input_type_from_input, NeuronInputType::Input(_)));
-    assert!(matches!(input_type_from_hidden, NeuronInputType::Hidden(_)));
-
-    // Test equals method
-    assert!(input_neuron.equals(&input_type_from_input));
-    assert!(!input_neuron.equals(&input_type_from_hidden));
-    assert!(hidden_neuron.equals(&input_type_from_hidden));
-    assert!(!hidden_neuron.equals(&input_type_from_input));
+fn test_random_input_on_empty() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut arena = NeuronArena::default();
+    let hidden = add_hidden(&mut arena, &mut rng);
+
+    assert_eq!(
+        arena.random_input(hidden, &mut rng),
+        None,
+        "Should return None when no inputs exist"
+    );
 }
 
 #[test]
-fn test_neuron_topology_clone() {
+fn test_arena_clone_is_independent() {
     let mut rng = StdRng::seed_from_u64(238102);
-    use std::f32::consts::PI;
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = hidden1.clone();
-
-    // Cloned topology should share the same underlying neuron
-    assert_eq!(hidden1.id(), hidden2.id(), "Clones should share same ID");
-
-    // Modifying through one should affect the other
-    hidden1.with_mut(|neuron| {
-        *neuron.bias_mut() = PI;
-    });
-
-    hidden2.with_ref(|neuron| {
-        assert_eq!(neuron.bias(), PI, "Changes should be visible through clone");
-    });
+    let mut arena = NeuronArena::default();
+    let input = arena.insert(Neuron::input());
+    let hidden = add_hidden(&mut arena, &mut rng);
+    arena.connect(input, hidden, 1.);
+
+    let mut clone = arena.clone();
+    clone.get_mut(hidden).unwrap().bias = std::f32::consts::PI;
+    clone.connection_mut(0).weight = 3.;
+    clone.remove(input);
+
+    // the clone shares ids with the original, but nothing else
+    assert_ne!(arena.get(hidden).unwrap().bias, std::f32::consts::PI);
+    assert_eq!(arena.connections()[0].weight, 1.);
+    assert!(arena.contains(input));
+    assert!(clone.contains(hidden));
 }
 
 #[test]
 fn test_complex_network_construction() {
     let mut rng = StdRng::seed_from_u64(271);
-    // Build a small network to test interconnections
-    let input1 = NeuronTopology::input();
-    let input2 = NeuronTopology::input();
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = NeuronTopology::hidden(&mut rng);
-    let hidden3 = NeuronTopology::hidden(&mut rng);
-    let output1 = NeuronTopology::output(&mut rng);
-    let output2 = NeuronTopology::output(&mut rng);
-
-    // Layer 1: Inputs to first hidden layer
-    hidden1.add_input(&input1);
-    hidden1.add_input(&input2);
-    hidden2.add_input(&input1);
-    hidden2.add_input(&input2);
-
-    // Layer 2: Hidden to hidden
-    hidden3.add_input(&hidden1);
-    hidden3.add_input(&hidden2);
-
-    // Layer 3: Hidden to outputs
-    output1.add_input(&hidden1);
-    output1.add_input(&hidden3);
-    output2.add_input(&hidden2);
-    output2.add_input(&hidden3);
-
-    // Verify structure
-    hidden1.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 2, "hidden1 should have 2 inputs");
-    });
-
-    hidden3.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 2, "hidden3 should have 2 inputs");
-    });
-
-    output1.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 2, "output1 should have 2 inputs");
-    });
-
-    output2.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 2, "output2 should have 2 inputs");
-    });
-}
-
-#[test]
-fn test_upgrade_weak_references() {
-    let mut rng = StdRng::seed_from_u64(238102);
-    let hidden = NeuronTopology::hidden(&mut rng);
-    let input = NeuronTopology::input();
-
-    hidden.add_input(&input);
-
-    // Get the weak reference
-    let weak_ref = hidden.with_ref(|neuron| match &neuron.inputs()[0].input_type {
-        NeuronInputType::Input(weak) => weak.clone(),
-        _ => panic!("Expected input type"),
-    });
-
-    // Should be able to upgrade while input is alive
-    assert!(weak_ref.upgrade().is_some(), "Should upgrade successfully");
-
-    // Drop the input
-    drop(input);
+    let mut arena = NeuronArena::default();
+    let input1 = arena.insert(Neuron::input());
+    let input2 = arena.insert(Neuron::input());
+    let hidden1 = add_hidden(&mut arena, &mut rng);
+    let hidden2 = add_hidden(&mut arena, &mut rng);
+    let hidden3 = add_hidden(&mut arena, &mut rng);
+    let output1 = add_output(&mut arena, &mut rng);
+    let output2 = add_output(&mut arena, &mut rng);
+
+    for (from, to) in [
+        // Layer 1: Inputs to first hidden layer
+        (input1, hidden1),
+        (input2, hidden1),
+        (input1, hidden2),
+        (input2, hidden2),
+        // Layer 2: Hidden to hidden
+        (hidden1, hidden3),
+        (hidden2, hidden3),
+        // Layer 3: Hidden to outputs
+        (hidden1, output1),
+        (hidden3, output1),
+        (hidden2, output2),
+        (hidden3, output2),
+    ] {
+        assert!(arena.connect(from, to, 1.));
+    }
 
-    // Should not be able to upgrade after dropping
-    assert!(
-        weak_ref.upgrade().is_none(),
-        "Should not upgrade after dropping"
-    );
+    for neuron in [hidden1, hidden3, output1, output2] {
+        assert_eq!(arena.inputs(neuron).count(), 2);
+    }
+    assert_eq!(arena.len(), 7);
+    assert_eq!(arena.iter().count(), 7);
 }
 
 #[test]
-fn test_mixed_input_types() {
+fn test_incoming_groups_by_receiver() {
     let mut rng = StdRng::seed_from_u64(238102);
-    let output = NeuronTopology::output(&mut rng);
-    let input1 = NeuronTopology::input();
-    let input2 = NeuronTopology::input();
-    let hidden1 = NeuronTopology::hidden(&mut rng);
-    let hidden2 = NeuronTopology::hidden(&mut rng);
-
-    // Add mixed types of inputs
-    output.add_input(&input1);
-    output.add_input(&hidden1);
-    output.add_input(&input2);
-    output.add_input(&hidden2);
-
-    // Verify all were added
-    output.with_ref(|neuron| {
-        assert_eq!(neuron.inputs().len(), 4, "Should have 4 inputs");
-
-        // Count each type
-        let mut input_count = 0;
-        let mut hidden_count = 0;
-
-        for input in neuron.inputs() {
-            match &input.input_type {
-                NeuronInputType::Input(_) => input_count += 1,
-                NeuronInputType::Hidden(_) => hidden_count += 1,
-            }
-        }
-
-        assert_eq!(input_count, 2, "Should have 2 input neurons");
-        assert_eq!(hidden_count, 2, "Should have 2 hidden neurons");
-    });
+    let mut arena = NeuronArena::default();
+    let output = add_output(&mut arena, &mut rng);
+    let input1 = arena.insert(Neuron::input());
+    let input2 = arena.insert(Neuron::input());
+    let hidden1 = add_hidden(&mut arena, &mut rng);
+    let hidden2 = add_hidden(&mut arena, &mut rng);
+
+    arena.connect(input1, output, 1.);
+    arena.connect(input1, hidden1, 1.);
+    arena.connect(hidden1, output, 1.);
+    arena.connect(input2, output, 1.);
+    arena.connect(hidden2, output, 1.);
+
+    let incoming = arena.incoming();
+    let senders: Vec<_> = incoming
+        .of(output)
+        .iter()
+        .map(|edge| arena.connections()[*edge].from)
+        .collect();
+    assert_eq!(senders, vec![input1, hidden1, input2, hidden2]);
+    assert!(incoming.of(input1).is_empty());
 }
 
 #[test]
-fn test_new_from_raw_parts() {
-    let input1 = NeuronTopology::input();
-    let input2 = NeuronTopology::input();
-
-    let inputs = vec![
-        NeuronInput {
-            input_type: input1.to_input_type(),
-            weight: 0.5,
-        },
-        NeuronInput {
-            input_type: input2.to_input_type(),
-            weight: -0.3,
-        },
-    ];
-
-    let hidden = Hidden::new_from_raw_parts(inputs, 1.5, crate::genome::activations::sigmoid);
-
-    assert_eq!(hidden.inputs().len(), 2);
-    assert_eq!(hidden.inputs()[0].weight, 0.5);
-    assert_eq!(hidden.inputs()[1].weight, -0.3);
-    assert_eq!(hidden.bias(), 1.5);
-}
-
-#[test]
-fn test_thread_safety() {
-    use std::sync::Arc;
-    use std::thread;
-    let mut rng = StdRng::seed_from_u64(238102);
-
-    let hidden = NeuronTopology::hidden(&mut rng);
-    let hidden_arc = Arc::new(hidden);
-
-    let mut handles = vec![];
-
-    // Spawn multiple threads that access the neuron
-    for i in 0..5 {
-        let hidden_clone = Arc::clone(&hidden_arc);
-        let handle = thread::spawn(move || {
-            // Each thread modifies the bias
-            hidden_clone.with_mut(|neuron| {
-                *neuron.bias_mut() += i as f32;
-            });
-
-            // And reads it back
-            hidden_clone.with_ref(|neuron| {
-                let _ = neuron.bias();
-            });
-        });
-        handles.push(handle);
-    }
-
-    // Wait for all threads
-    for handle in handles {
-        handle.join().unwrap();
-    }
-
-    // Final bias should be sum of all additions: 0 + 1 + 2 + 3 + 4 = 10
-    hidden_arc.with_ref(|neuron| {
-        assert_eq!(
-            neuron.bias(),
-            10.0,
-            "Bias should be sum of all thread additions"
-        );
-    });
+fn test_markings_stay_unique() {
+    let mut arena = NeuronArena::default();
+    let marking = Innovation(7);
+
+    let first = arena.insert(Neuron::input().with_innovation(marking));
+    let second = arena.insert(Neuron::input().with_innovation(marking));
+    let third = arena.insert(Neuron::input().with_innovation(marking));
+
+    assert_eq!(arena[first].innovation, marking);
+    assert_eq!(arena[second].innovation, marking.salted(0));
+    assert_eq!(arena[third].innovation, marking.salted(1));
+    assert_eq!(arena.find(marking.salted(1)), Some(third));
+
+    // a removed neuron's marking is free again
+    arena.remove(first);
+    assert_eq!(arena.find(marking), None);
+    let fourth = arena.insert(Neuron::input().with_innovation(marking));
+    assert_eq!(arena[fourth].innovation, marking);
 }
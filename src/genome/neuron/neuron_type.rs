@@ -1,287 +1,113 @@
-use super::NeuronTopology;
-use rand::{Rng, seq::IndexedMutRandom};
-use std::sync::{Arc, RwLock, Weak};
-use uuid::Uuid;
+use std::fmt;
 
-pub trait TopologyNeuron {
-    fn id(&self) -> Uuid;
-}
-
-pub trait CanBeInput {
-    fn id(&self) -> Uuid;
-    fn to_input_type(&self) -> NeuronInputType;
-
-    fn equals(&self, other: &NeuronInputType) -> bool;
-}
-
-pub trait TakesInput: TopologyNeuron {
-    fn new_from_raw_parts(inputs: Vec<NeuronInput>, bias: f32, activation: fn(f32) -> f32) -> Self;
-    fn add_input(&mut self, input: &impl CanBeInput);
-    // returns true if the input was an input of this type prior to removing it
-    //fn remove_input(&mut self, input: &impl CanBeInput) -> Option<NeuronInput>;
-    fn inputs(&self) -> &[NeuronInput];
-    fn inputs_mut(&mut self) -> &mut Vec<NeuronInput>;
-
-    fn bias(&self) -> f32;
-    fn bias_mut(&mut self) -> &mut f32;
-
-    fn activation(&self) -> fn(f32) -> f32;
-    fn set_activation(&mut self, activation: fn(f32) -> f32);
-
-    fn random_input(&mut self, rng: &mut impl Rng) -> Option<&mut NeuronInput> {
-        self.inputs_mut().choose_mut(rng)
-    }
-}
-
-#[derive(Clone, Debug)]
-pub struct Input {
-    id: Uuid,
-}
-impl Default for Input {
-    fn default() -> Self {
-        Self { id: Uuid::new_v4() }
-    }
-}
-
-impl TopologyNeuron for Input {
-    fn id(&self) -> Uuid {
-        self.id
-    }
-}
-
-#[derive(Clone, Debug)]
-pub struct Hidden {
-    id: Uuid,
-    /**
-    Contains
-    Vec<{input_type: Input | Hidden, weight}>
-    */
-    inputs: Vec<NeuronInput>,
-    bias: f32,
-    activation: fn(f32) -> f32,
-}
-#[derive(Clone, Debug)]
-pub struct Output {
-    id: Uuid,
-    inputs: Vec<NeuronInput>,
-    bias: f32,
-    activation: fn(f32) -> f32,
-}
+use bevy::reflect::Reflect;
+use rand::Rng;
 
-impl Output {}
+use crate::genome::{
+    Innovation,
+    activations::{self, Activation, ActivationSet},
+};
 
-#[derive(Clone, Debug)]
-pub struct NeuronInput {
-    pub input_type: NeuronInputType,
-    pub weight: f32,
+/// A key into a [`NeuronArena`](super::NeuronArena).
+///
+/// Ids are never reused: once a neuron is removed, its id stops resolving for good.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Reflect)]
+pub struct NeuronId {
+    pub(super) index: u32,
+    pub(super) generation: u32,
 }
-impl NeuronInput {
-    pub fn is_alive(&self) -> bool {
-        match &self.input_type {
-            NeuronInputType::Hidden(h) => h.upgrade().is_some(),
-            NeuronInputType::Input(i) => i.upgrade().is_some(),
-        }
-    }
 
-    pub fn id(&self) -> Option<Uuid> {
-        match &self.input_type {
-            NeuronInputType::Hidden(h) => {
-                let h = h.upgrade()?;
-                Some(h.id())
-            }
-            NeuronInputType::Input(i) => {
-                let i = i.upgrade()?;
-                Some(i.id())
-            }
-        }
+impl NeuronId {
+    /// The neuron's slot. Unique among the living neurons of one arena.
+    pub fn index(self) -> u32 {
+        self.index
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct NeuronInputInner<T>(Weak<RwLock<T>>);
-impl<T> NeuronInputInner<T> {
-    /// Attempts to upgrade this inner type to the associated neuron topology, if the link is still valid.
-    pub fn upgrade(&self) -> Option<NeuronTopology<T>> {
-        let upgraded = self.0.upgrade()?;
-        Some(NeuronTopology::from_inner(upgraded))
+impl fmt::Display for NeuronId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
     }
 }
 
-#[derive(Clone, Debug)]
-pub enum NeuronInputType {
-    Input(NeuronInputInner<Input>),
-    Hidden(NeuronInputInner<Hidden>),
-}
-impl NeuronInputType {
-    pub fn hidden(topology: &NeuronTopology<Hidden>) -> Self {
-        Self::Hidden(NeuronInputInner(Arc::downgrade(&topology.inner)))
-    }
-    pub fn input(topology: &NeuronTopology<Input>) -> Self {
-        Self::Input(NeuronInputInner(Arc::downgrade(&topology.inner)))
-    }
-}
-
-impl CanBeInput for NeuronTopology<Input> {
-    fn id(&self) -> Uuid {
-        self.id()
-    }
-    fn to_input_type(&self) -> NeuronInputType {
-        NeuronInputType::Input(NeuronInputInner(Arc::downgrade(&self.inner)))
-    }
-    fn equals(&self, other: &NeuronInputType) -> bool {
-        match other {
-            NeuronInputType::Input(other) => {
-                let Some(input) = other.upgrade() else {
-                    return false;
-                };
-                Arc::ptr_eq(&self.inner, &input.inner)
-            }
-            NeuronInputType::Hidden(_) => false,
-        }
-    }
-}
-impl CanBeInput for NeuronTopology<Hidden> {
-    fn id(&self) -> Uuid {
-        self.id()
-    }
-    fn to_input_type(&self) -> NeuronInputType {
-        NeuronInputType::Hidden(NeuronInputInner(Arc::downgrade(&self.inner)))
-    }
-    fn equals(&self, other: &NeuronInputType) -> bool {
-        match other {
-            NeuronInputType::Hidden(other) => {
-                let Some(input) = other.upgrade() else {
-                    return false;
-                };
-                Arc::ptr_eq(&self.inner, &input.inner)
-            }
-            NeuronInputType::Input(_) => false,
-        }
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NeuronKind {
+    /// Written by its cell
+    Input,
+    Hidden,
+    /// Read by its cell
+    Output,
 }
 
-impl TopologyNeuron for Hidden {
-    fn id(&self) -> Uuid {
-        self.id
-    }
+#[derive(Clone, Debug, PartialEq)]
+pub struct Neuron {
+    pub kind: NeuronKind,
+    pub bias: f32,
+    pub activation: Activation,
+    /// Set by the arena on insert, starting from whatever the neuron is given.
+    pub innovation: Innovation,
 }
 
-impl TakesInput for Hidden {
-    fn new_from_raw_parts(inputs: Vec<NeuronInput>, bias: f32, activation: fn(f32) -> f32) -> Self {
+impl Neuron {
+    /// Inputs hold whatever their cell writes, so their bias and activation go unused.
+    pub fn input() -> Self {
         Self {
-            id: Uuid::new_v4(),
-            inputs,
-            bias,
-            activation,
+            kind: NeuronKind::Input,
+            bias: 0.,
+            activation: Activation::Linear,
+            innovation: Innovation::default(),
         }
     }
-    fn add_input(&mut self, input: &impl CanBeInput) {
-        self.inputs.push(NeuronInput {
-            input_type: input.to_input_type(),
-            weight: 1.,
-        })
-    }
-    fn inputs_mut(&mut self) -> &mut Vec<NeuronInput> {
-        &mut self.inputs
-    }
-    // fn remove_input(&mut self, input_to_remove: &impl CanBeInput) -> Option<NeuronInput> {
-    //     if let Some(position) = self
-    //         .inputs
-    //         .iter()
-    //         .position(|input| input_to_remove.equals(&input.input_type))
-    //     {
-    //         Some(self.inputs.swap_remove(position))
-    //     } else {
-    //         None
-    //     }
-    // }
-
-    fn inputs(&self) -> &[NeuronInput] {
-        &self.inputs
-    }
-    fn bias(&self) -> f32 {
-        self.bias
-    }
-    fn bias_mut(&mut self) -> &mut f32 {
-        &mut self.bias
-    }
-    fn activation(&self) -> fn(f32) -> f32 {
-        self.activation
-    }
-    fn set_activation(&mut self, activation: fn(f32) -> f32) {
-        self.activation = activation;
-    }
 
-    fn random_input(&mut self, rng: &mut impl Rng) -> Option<&mut NeuronInput> {
-        self.inputs.choose_mut(rng)
-
-        // if let Some(input) = self.inputs.choose_mut(rng) {
-        //     input.weight += rng.random_range(-1.0..=1.0);
-        // }
+    /// A hidden neuron whose activation is drawn from `activations`.
+    pub fn hidden(activations: ActivationSet, rng: &mut impl Rng) -> Self {
+        Self::random(NeuronKind::Hidden, activations, rng)
     }
-}
 
-impl TopologyNeuron for Output {
-    fn id(&self) -> Uuid {
-        self.id
+    /// An output neuron whose activation is drawn from `activations`.
+    pub fn output(activations: ActivationSet, rng: &mut impl Rng) -> Self {
+        Self::random(NeuronKind::Output, activations, rng)
     }
-}
 
-impl TakesInput for Output {
-    fn new_from_raw_parts(inputs: Vec<NeuronInput>, bias: f32, activation: fn(f32) -> f32) -> Self {
+    fn random(kind: NeuronKind, activations: ActivationSet, rng: &mut impl Rng) -> Self {
         Self {
-            id: Uuid::new_v4(),
-            inputs,
-            bias,
-            activation,
+            kind,
+            bias: activations::random_bias(rng),
+            activation: activations.random(rng),
+            innovation: Innovation::default(),
         }
     }
 
-    fn add_input(&mut self, input: &impl CanBeInput) {
-        self.inputs.push(NeuronInput {
-            input_type: input.to_input_type(),
-            weight: 1.,
-        })
+    /// Asks the arena for this marking, or one derived from it if it's taken.
+    pub fn with_innovation(mut self, innovation: Innovation) -> Self {
+        self.innovation = innovation;
+        self
     }
 
-    fn inputs_mut(&mut self) -> &mut Vec<NeuronInput> {
-        &mut self.inputs
-    }
-    // fn remove_input(&mut self, input_to_remove: &impl CanBeInput) -> Option<NeuronInput> {
-    //     if let Some(position) = self
-    //         .inputs
-    //         .iter()
-    //         .position(|input| input_to_remove.equals(&input.input_type))
-    //     {
-    //         Some(self.inputs.swap_remove(position))
-    //     } else {
-    //         None
-    //     }
-    // }
-    fn bias_mut(&mut self) -> &mut f32 {
-        &mut self.bias
-    }
-    fn set_activation(&mut self, activation: fn(f32) -> f32) {
-        self.activation = activation;
+    pub fn takes_input(&self) -> bool {
+        self.kind != NeuronKind::Input
     }
 
-    fn inputs(&self) -> &[NeuronInput] {
-        &self.inputs
+    pub fn can_be_input(&self) -> bool {
+        self.kind != NeuronKind::Output
     }
+}
 
-    fn bias(&self) -> f32 {
-        self.bias
-    }
-
-    fn activation(&self) -> fn(f32) -> f32 {
-        self.activation
-    }
-
-    fn random_input(&mut self, rng: &mut impl Rng) -> Option<&mut NeuronInput> {
-        self.inputs.choose_mut(rng)
-
-        // if let Some(input) = self.inputs.choose_mut(rng) {
-        //     input.weight += rng.random_range(-1.0..=1.0);
-        // }
-    }
+/// A weighted edge: `to` reads `from`'s value, scaled by `weight`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Connection {
+    pub from: NeuronId,
+    pub to: NeuronId,
+    pub weight: f32,
+    /// Reads `from`'s value from the previous tick, which lets the edge close a cycle.
+    pub recurrent: bool,
+    /// Disabled connections stay in the genome, but carry no signal.
+    pub enabled: bool,
+    /// Derived from the markings of `from` and `to` when the connection is made.
+    pub innovation: Innovation,
+    /// How many connections its lineage had made before this one.
+    ///
+    /// Copies keep it, so relatives agree on which of the genes they share came first, and
+    /// crossover can tell NEAT's disjoint genes from excess ones.
+    pub order: u64,
 }
@@ -0,0 +1,526 @@
+use std::{fmt, fs, io, path::Path};
+
+use bevy::{math::IVec2, platform::collections::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cell::{CellGenome, CellKind},
+    genome::{
+        CellMap, Connection, Genome, Incoming, Innovation, MutationChances, Neuron, NeuronArena,
+        NeuronId, NeuronKind,
+        activations::{Activation, ActivationSet},
+        decycler::CycleMode,
+    },
+};
+
+/// Bumped whenever the on-disk layout changes in a way older files can't be read.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+/// A plain-data copy of a [`Genome`].
+///
+/// Neurons are keyed by their index in the genome's [`NeuronArena`], and every connection
+/// refers to its sender by that key, so the graph can be rebuilt with
+/// [`GenomeSnapshot::into_genome`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenomeSnapshot {
+    pub version: u32,
+    pub cells: Vec<CellSnapshot>,
+    pub hidden: Vec<NeuronSnapshot>,
+    pub mutation: MutationChances,
+    #[serde(default)]
+    pub activations: ActivationSet,
+    #[serde(default)]
+    pub cycles: CycleMode,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CellSnapshot {
+    pub location: (i32, i32),
+    pub kind: CellKind,
+    pub inputs: Vec<u32>,
+    pub outputs: Vec<NeuronSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NeuronSnapshot {
+    pub id: u32,
+    pub bias: f32,
+    pub activation: Activation,
+    pub inputs: Vec<ConnectionSnapshot>,
+    /// Missing from files saved before genomes had markings
+    #[serde(default)]
+    pub innovation: Option<Innovation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConnectionSnapshot {
+    pub from: u32,
+    pub weight: f32,
+    #[serde(default)]
+    pub recurrent: bool,
+    /// Missing from files saved before connections could be disabled
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// Missing from files saved before connections were ordered
+    #[serde(default)]
+    pub order: Option<u64>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Ron(ron::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+    UnknownNeuron(u32),
+    DuplicateNeuron(u32),
+    /// Both neurons exist, but `to` can't read from `from`
+    InvalidConnection {
+        from: u32,
+        to: u32,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Ron(e) => write!(f, "could not write genome: {e}"),
+            Self::Parse(e) => write!(f, "could not read genome: {e}"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "genome file version {v} is not supported (expected {SNAPSHOT_VERSION})"
+            ),
+            Self::UnknownNeuron(id) => write!(f, "connection refers to unknown neuron {id}"),
+            Self::DuplicateNeuron(id) => write!(f, "neuron {id} is defined more than once"),
+            Self::InvalidConnection { from, to } => {
+                write!(f, "neuron {to} can't read from neuron {from}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl From<ron::Error> for SnapshotError {
+    fn from(value: ron::Error) -> Self {
+        Self::Ron(value)
+    }
+}
+impl From<ron::error::SpannedError> for SnapshotError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+impl GenomeSnapshot {
+    pub fn new(genome: &Genome) -> Self {
+        let neurons = &genome.neurons;
+        let incoming = neurons.incoming();
+        let mut cells = genome
+            .cells
+            .map()
+            .iter()
+            .map(|(location, cell)| CellSnapshot {
+                location: (location.x, location.y),
+                kind: cell.kind,
+                inputs: cell.inputs.iter().map(|input| input.index()).collect(),
+                outputs: cell
+                    .outputs
+                    .iter()
+                    .map(|output| NeuronSnapshot::new(neurons, &incoming, *output))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        // hashmap order is arbitrary, so keep files stable between saves
+        cells.sort_by_key(|cell| cell.location);
+
+        let hidden = neurons
+            .hidden()
+            .iter()
+            .map(|hidden| NeuronSnapshot::new(neurons, &incoming, *hidden))
+            .collect();
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            cells,
+            hidden,
+            mutation: genome.mutation.clone(),
+            activations: genome.activations,
+            cycles: genome.cycles,
+        }
+    }
+
+    /// Rebuilds the genome this snapshot was taken from.
+    ///
+    /// Neurons are inserted into a fresh arena, so their ids can differ from the original's.
+    pub fn into_genome(self) -> Result<Genome, SnapshotError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+
+        let mut neurons = NeuronArena::default();
+        let mut ids: HashMap<u32, NeuronId> = HashMap::new();
+        let mut add = |neurons: &mut NeuronArena, id: u32, neuron: Neuron| {
+            let new = neurons.insert(neuron);
+            match ids.insert(id, new) {
+                Some(_) => Err(SnapshotError::DuplicateNeuron(id)),
+                None => Ok(new),
+            }
+        };
+
+        let mut cells = CellMap::with_capacity(self.cells.len());
+        for cell in &self.cells {
+            let location = IVec2::new(cell.location.0, cell.location.1);
+            // cell inputs aren't stored with their marking, and it never changes anyway
+            let marking = |output, slot| Innovation::cell_neuron(location, cell.kind, output, slot);
+            let inputs = cell
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(slot, id)| {
+                    let input = Neuron::input().with_innovation(marking(false, slot));
+                    add(&mut neurons, *id, input)
+                })
+                .collect::<Result<_, _>>()?;
+            let outputs = cell
+                .outputs
+                .iter()
+                .enumerate()
+                .map(|(slot, output)| {
+                    let neuron = output.neuron(NeuronKind::Output, marking(true, slot));
+                    add(&mut neurons, output.id, neuron)
+                })
+                .collect::<Result<_, _>>()?;
+            cells.map_mut().insert(
+                location,
+                CellGenome {
+                    kind: cell.kind,
+                    inputs,
+                    outputs,
+                },
+            );
+        }
+        // hidden neurons can feed each other, so they all need to exist before any are wired.
+        for hidden in &self.hidden {
+            let neuron = hidden.neuron(NeuronKind::Hidden, Innovation::default());
+            add(&mut neurons, hidden.id, neuron)?;
+        }
+
+        let receivers = self
+            .cells
+            .iter()
+            .flat_map(|cell| &cell.outputs)
+            .chain(&self.hidden);
+        for receiver in receivers {
+            let to = ids[&receiver.id];
+            for connection in &receiver.inputs {
+                let from = *ids
+                    .get(&connection.from)
+                    .ok_or(SnapshotError::UnknownNeuron(connection.from))?;
+                let loaded = Connection {
+                    from,
+                    to,
+                    weight: connection.weight,
+                    recurrent: connection.recurrent,
+                    enabled: connection.enabled,
+                    innovation: Innovation::default(),
+                    order: connection.order.unwrap_or_default(),
+                };
+                let wired = match connection.order {
+                    Some(_) => neurons.add_connection(loaded),
+                    None => neurons.add_new_connection(loaded),
+                };
+                if !wired {
+                    return Err(SnapshotError::InvalidConnection {
+                        from: connection.from,
+                        to: receiver.id,
+                    });
+                }
+            }
+        }
+
+        Ok(Genome {
+            cells,
+            neurons,
+            mutation: self.mutation,
+            activations: self.activations,
+            cycles: self.cycles,
+        })
+    }
+
+    pub fn to_ron(&self) -> Result<String, SnapshotError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, SnapshotError> {
+        Ok(ron::from_str(text)?)
+    }
+}
+
+impl NeuronSnapshot {
+    fn new(neurons: &NeuronArena, incoming: &Incoming, id: NeuronId) -> Self {
+        let neuron = neurons.get(id).unwrap();
+        Self {
+            id: id.index(),
+            bias: neuron.bias,
+            activation: neuron.activation,
+            innovation: Some(neuron.innovation),
+            inputs: incoming
+                .of(id)
+                .iter()
+                .map(|edge| {
+                    let connection = &neurons.connections()[*edge];
+                    ConnectionSnapshot {
+                        from: connection.from.index(),
+                        weight: connection.weight,
+                        recurrent: connection.recurrent,
+                        enabled: connection.enabled,
+                        order: Some(connection.order),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The neuron this was taken from, marked `fallback` if the file has no marking.
+    fn neuron(&self, kind: NeuronKind, fallback: Innovation) -> Neuron {
+        Neuron {
+            kind,
+            bias: self.bias,
+            activation: self.activation,
+            innovation: self.innovation.unwrap_or(fallback),
+        }
+    }
+}
+
+impl Genome {
+    pub fn snapshot(&self) -> GenomeSnapshot {
+        GenomeSnapshot::new(self)
+    }
+
+    pub fn to_ron(&self) -> Result<String, SnapshotError> {
+        self.snapshot().to_ron()
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, SnapshotError> {
+        GenomeSnapshot::from_ron(text)?.into_genome()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+impl GenomeSnapshot {
+    /// Renumbers ids in the order they appear, so genomes rebuilt in a fresh arena can be compared.
+    pub fn with_stable_ids(mut self) -> Self {
+        let mut ids = HashMap::new();
+        let mut rename = |id: &mut u32| {
+            let len = ids.len() as u32;
+            *id = *ids.entry(*id).or_insert(len);
+        };
+        for cell in &mut self.cells {
+            cell.inputs.iter_mut().for_each(&mut rename);
+            for output in &mut cell.outputs {
+                rename(&mut output.id);
+                output.inputs.iter_mut().for_each(|c| rename(&mut c.from));
+            }
+        }
+        for hidden in &mut self.hidden {
+            rename(&mut hidden.id);
+            hidden.inputs.iter_mut().for_each(|c| rename(&mut c.from));
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::genome::MutationAction,
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_snapshot_round_trip() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::sandbox(&mut rng);
+    for _ in 0..5 {
+        genome.scramble(&mut rng);
+    }
+
+    let text = genome.to_ron().unwrap();
+    let loaded = Genome::from_ron(&text).unwrap();
+
+    assert_eq!(genome.cell_count(), loaded.cell_count());
+    assert_eq!(genome.hidden_count(), loaded.hidden_count());
+    assert_eq!(genome.mutation, loaded.mutation);
+
+    // the reloaded genome was rebuilt in a fresh arena, so compare everything but ids.
+    assert_eq!(
+        genome.snapshot().with_stable_ids(),
+        loaded.snapshot().with_stable_ids()
+    );
+}
+
+#[test]
+fn test_snapshot_keeps_recurrent_connections() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    genome.set_cycle_mode(CycleMode::Recurrent);
+    let hidden = genome.hidden_neurons()[0];
+    assert!(genome.neurons.add_connection(Connection {
+        from: hidden,
+        to: hidden,
+        weight: 1.,
+        recurrent: true,
+        enabled: true,
+        innovation: Innovation::default(),
+        order: 0,
+    }));
+
+    let loaded = Genome::from_ron(&genome.to_ron().unwrap()).unwrap();
+
+    assert_eq!(loaded.cycle_mode(), CycleMode::Recurrent);
+    let recurrent = |genome: &Genome| {
+        genome
+            .neurons()
+            .connections()
+            .iter()
+            .filter(|c| c.recurrent)
+            .count()
+    };
+    assert_eq!(recurrent(&genome), 1);
+    assert_eq!(recurrent(&genome), recurrent(&loaded));
+}
+
+#[test]
+fn test_snapshot_keeps_disabled_connections() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    genome.neurons.connection_mut(0).enabled = false;
+
+    let loaded = Genome::from_ron(&genome.to_ron().unwrap()).unwrap();
+
+    let disabled = |genome: &Genome| {
+        genome
+            .neurons()
+            .connections()
+            .iter()
+            .filter(|c| !c.enabled)
+            .count()
+    };
+    assert_eq!(disabled(&loaded), 1);
+    assert_eq!(
+        genome.neurons().connections().len(),
+        loaded.neurons().connections().len()
+    );
+}
+
+#[test]
+fn test_snapshot_rebuilds_shared_topology() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::simple_linear(&mut rng);
+
+    let loaded = Genome::from_ron(&genome.to_ron().unwrap()).unwrap();
+    let hidden = loaded.hidden_neurons()[0];
+
+    // the hidden neuron still reads from the eye's inputs
+    let eye_inputs = &loaded.cells().get(&IVec2::new(0, 0)).unwrap().inputs;
+    let senders: Vec<_> = loaded.neurons().inputs(hidden).map(|c| c.from).collect();
+    assert_eq!(&senders, eye_inputs);
+
+    // and the launcher outputs still read from the hidden neuron
+    let launcher = loaded.cells().get(&IVec2::new(1, 0)).unwrap();
+    for output in &launcher.outputs {
+        let senders: Vec<_> = loaded.neurons().inputs(*output).map(|c| c.from).collect();
+        assert_eq!(senders, vec![hidden]);
+    }
+}
+
+#[test]
+fn test_snapshot_preserves_weights_and_biases() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut genome = Genome::simple_linear(&mut rng);
+    for _ in 0..10 {
+        MutationAction::MutateWeight.perform(
+            &mut genome.cells,
+            &mut genome.neurons,
+            genome.activations,
+            &mut rng,
+        );
+    }
+
+    let loaded = Genome::from_ron(&genome.to_ron().unwrap()).unwrap();
+
+    let weights = |genome: &Genome| {
+        let hidden = genome.hidden_neurons()[0];
+        (
+            genome.neurons().get(hidden).unwrap().bias,
+            genome
+                .neurons()
+                .inputs(hidden)
+                .map(|i| i.weight)
+                .collect::<Vec<_>>(),
+        )
+    };
+    assert_eq!(weights(&genome), weights(&loaded));
+}
+
+#[test]
+fn test_snapshot_rejects_unknown_neuron() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::simple_linear(&mut rng);
+    let mut snapshot = genome.snapshot();
+    snapshot.hidden[0].inputs[0].from = u32::MAX;
+
+    assert!(matches!(
+        snapshot.into_genome(),
+        Err(SnapshotError::UnknownNeuron(u32::MAX))
+    ));
+}
+
+#[test]
+fn test_snapshot_rejects_outputs_as_senders() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::simple_linear(&mut rng);
+    let mut snapshot = genome.snapshot();
+    let output = snapshot.cells[1].outputs[0].id;
+    let hidden = snapshot.hidden[0].id;
+    snapshot.hidden[0].inputs[0].from = output;
+
+    assert!(matches!(
+        snapshot.into_genome(),
+        Err(SnapshotError::InvalidConnection { from, to }) if from == output && to == hidden
+    ));
+}
+
+#[test]
+fn test_snapshot_rejects_other_versions() {
+    let mut snapshot = Genome::empty().snapshot();
+    snapshot.version = SNAPSHOT_VERSION + 1;
+
+    assert!(matches!(
+        snapshot.into_genome(),
+        Err(SnapshotError::UnsupportedVersion(_))
+    ));
+}
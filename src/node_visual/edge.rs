@@ -1,7 +1,10 @@
-use bevy::{color::palettes::tailwind::RED_400, platform::collections::HashMap, prelude::*};
+use bevy::{platform::collections::HashMap, prelude::*};
 use uuid::Uuid;
 
-use crate::node_visual::Nid;
+use crate::{
+    genome::{NEGATIVE_SIGNAL, POSITIVE_SIGNAL},
+    node_visual::Nid,
+};
 
 #[derive(Component, Reflect)]
 pub struct Edge {
@@ -47,9 +50,29 @@ pub struct EdgeCircle(Entity);
 #[relationship(relationship_target = EdgeCircle)]
 pub struct EdgeCircleOf(pub Entity);
 
+/// What an edge carried on the last tick.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EdgeSignal {
+    pub weight: f32,
+    /// The sender's value times the weight
+    pub signal: f32,
+}
+
+/// How far along its edge the pulse is, from `0` at the sender to `1` at the receiver.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EdgePulse(pub f32);
+
+impl EdgePulse {
+    /// Moves the pulse along, faster the stronger `signal` is, and starts it over once it
+    /// reaches the receiver. A quiet edge's pulse stays where it is.
+    pub fn advance(&mut self, signal: f32, delta: f32) {
+        self.0 = (self.0 + signal.abs().tanh() * delta / PULSE_SECONDS).fract();
+    }
+}
+
 #[derive(Message)]
 pub struct EdgeUpdates {
-    map: HashMap<Uuid, i32>,
+    map: HashMap<Uuid, EdgeSignal>,
 }
 impl EdgeUpdates {
     pub fn empty() -> Self {
@@ -57,21 +80,37 @@ impl EdgeUpdates {
             map: HashMap::new(),
         }
     }
-    pub fn set(values: impl IntoIterator<Item = (Uuid, i32)>) -> Self {
+    pub fn set(values: impl IntoIterator<Item = (Uuid, EdgeSignal)>) -> Self {
         Self {
             map: values.into_iter().collect(),
         }
     }
 }
 
+/// Seconds a pulse takes to travel from one end of an edge to the other, at full strength
+const PULSE_SECONDS: f32 = 1.2;
+/// Edge thickness at a weight of `1`
+const WEIGHT_THICKNESS: f32 = 2.;
+const MAX_THICKNESS: f32 = 8.;
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, (update_edge_transforms, update_edge_colors));
+    app.add_systems(
+        Update,
+        (update_edge_signals, update_edge_transforms).chain(),
+    );
     app.add_message::<EdgeUpdates>();
 }
 
 fn update_edge_transforms(
     edges: Query<
-        (&mut Transform, &Edge, &EdgeRectangle, &EdgeCircle),
+        (
+            &mut Transform,
+            &Edge,
+            &EdgeSignal,
+            &mut EdgePulse,
+            &EdgeRectangle,
+            &EdgeCircle,
+        ),
         (Without<EdgeRectangleOf>, Without<EdgeCircleOf>),
     >,
     mut rectangles: Query<&mut Transform, (With<EdgeRectangleOf>, Without<Edge>, Without<Nid>)>,
@@ -85,8 +124,10 @@ fn update_edge_transforms(
         ),
     >,
     nodes: Query<&Transform, (With<Nid>, Without<Edge>, Without<EdgeCircleOf>)>,
+    time: Res<Time>,
 ) {
-    for (mut transform, edge, rectangle, circle) in edges {
+    for (mut transform, edge, signal, mut pulse, rectangle, circle) in edges {
+        pulse.advance(signal.signal, time.delta_secs());
         if let Ok(sender_trns) = nodes.get(edge.sender())
             && let Ok(recv_trns) = nodes.get(edge.receiver())
             && let Ok(mut rectangle_trns) = rectangles.get_mut(rectangle.0)
@@ -96,8 +137,12 @@ fn update_edge_transforms(
             let length = val.length();
             if length > 0. {
                 rectangle_trns.scale.x = length;
-                circle_trns.translation.x = length * 0.5 - 5.;
+                circle_trns.translation.x = length * (pulse.0 - 0.5);
             }
+            rectangle_trns.scale.y =
+                (signal.weight.abs() * WEIGHT_THICKNESS).clamp(0.5, MAX_THICKNESS) / LINE_MESH_Y;
+            // a quiet edge has nothing to carry
+            circle_trns.scale = Vec3::splat(signal.signal.abs().tanh());
 
             transform.translation = sender_trns.translation + (Vec3::new(val.x, val.y, 0.) * 0.5);
 
@@ -108,27 +153,53 @@ fn update_edge_transforms(
     }
 }
 
-fn update_edge_colors(
+/// Colors each edge by the sign of its weight, and its pulse by the sign of its signal.
+fn update_edge_signals(
     mut reader: MessageReader<EdgeUpdates>,
-    edges: Query<(&Edge, &MeshMaterial2d<ColorMaterial>)>,
+    mut edges: Query<(&Edge, &mut EdgeSignal, &EdgeRectangle, &EdgeCircle)>,
+    handles: Query<&MeshMaterial2d<ColorMaterial>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for update in reader.read() {
-        info!("In update edge colors");
-        for (edge, material_handle) in edges {
-            let Some(material) = materials.get_mut(&material_handle.0) else {
+    let Some(update) = reader.read().last() else {
+        return;
+    };
+    for (edge, mut signal, rectangle, circle) in &mut edges {
+        *signal = update.map.get(&edge.id).copied().unwrap_or_default();
+
+        for (part, value) in [(rectangle.0, signal.weight), (circle.0, signal.signal)] {
+            let Ok(handle) = handles.get(part) else {
                 continue;
             };
-            let val = update.map.get(&edge.id).copied().unwrap_or_default();
-
-            if val > 0 {
-                info!("COLOR IS RED");
-                material.color = RED_400.into();
-            } else {
-                info!("COLOR IS WHITE");
-                material.color = Color::WHITE;
+            if let Some(material) = materials.get_mut(&handle.0) {
+                material.color = sign_color(value);
             }
         }
-        //todo
     }
 }
+
+fn sign_color(value: f32) -> Color {
+    if value > 0. {
+        POSITIVE_SIGNAL.into()
+    } else if value < 0. {
+        NEGATIVE_SIGNAL.into()
+    } else {
+        Color::WHITE
+    }
+}
+
+#[test]
+fn test_pulses_move_with_their_signal() {
+    let mut quiet = EdgePulse::default();
+    quiet.advance(0., 1.);
+    assert_eq!(quiet, EdgePulse(0.));
+
+    let (mut weak, mut strong) = (EdgePulse::default(), EdgePulse::default());
+    weak.advance(0.1, 0.1);
+    strong.advance(-5., 0.1);
+    assert!(0. < weak.0 && weak.0 < strong.0);
+
+    // around again once it reaches the receiver
+    let mut pulse = EdgePulse(0.9);
+    pulse.advance(100., PULSE_SECONDS * 0.2);
+    assert!((pulse.0 - 0.1).abs() < 1e-4);
+}
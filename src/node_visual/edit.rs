@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{
+    genome::{BrainEdit, NeuronId, activations::Activation},
+    node_visual::{Edge, EdgeRectangleOf, EntityGraphMap, Nid},
+    organism::{ActiveOrganism, EditBrain, Organism, apply_brain_edits},
+    settings::Keybinds,
+};
+
+/// How much a weight or bias changes per key press
+const EDIT_STEP: f32 = 0.1;
+/// Weight of connections drawn by dragging from one node to another
+const NEW_CONNECTION_WEIGHT: f32 = 1.;
+
+/// The node or edge the next edit applies to.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub enum BrainSelection {
+    #[default]
+    Nothing,
+    Neuron(NeuronId),
+    Connection {
+        from: NeuronId,
+        to: NeuronId,
+    },
+}
+
+/// Describes the selection, and the keys that edit it.
+#[derive(Component)]
+pub struct SelectionText;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<BrainSelection>();
+    app.add_systems(
+        Update,
+        (edit_selection.before(apply_brain_edits), show_selection),
+    );
+}
+
+pub(super) fn select_neuron(
+    click: On<Pointer<Click>>,
+    nodes: Query<&Nid>,
+    mut selection: ResMut<BrainSelection>,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    if let Ok(node) = nodes.get(click.entity) {
+        *selection = BrainSelection::Neuron(node.0);
+    }
+}
+
+pub(super) fn select_connection(
+    click: On<Pointer<Click>>,
+    rectangles: Query<&EdgeRectangleOf>,
+    edges: Query<&Edge>,
+    map: Res<EntityGraphMap>,
+    mut selection: ResMut<BrainSelection>,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    let Ok(edge) = rectangles
+        .get(click.entity)
+        .and_then(|rectangle| edges.get(rectangle.0))
+    else {
+        return;
+    };
+    if let (Some(from), Some(to)) = (map.get_id(&edge.sender()), map.get_id(&edge.receiver())) {
+        *selection = BrainSelection::Connection {
+            from: *from,
+            to: *to,
+        };
+    }
+}
+
+/// Dropping one node onto another makes the second read from the first.
+pub(super) fn connect_neurons(
+    drop: On<Pointer<DragDrop>>,
+    nodes: Query<&Nid>,
+    organism: Single<Entity, With<ActiveOrganism>>,
+    mut edits: MessageWriter<EditBrain>,
+) {
+    if drop.button != PointerButton::Primary {
+        return;
+    }
+    let (Ok(from), Ok(to)) = (nodes.get(drop.dropped), nodes.get(drop.entity)) else {
+        return;
+    };
+    edits.write(EditBrain {
+        organism: *organism,
+        edit: BrainEdit::Connect {
+            from: from.0,
+            to: to.0,
+            weight: NEW_CONNECTION_WEIGHT,
+        },
+    });
+}
+
+fn edit_selection(
+    input: Res<ButtonInput<KeyCode>>,
+    keybinds: Res<Keybinds>,
+    organism: Single<(Entity, &Organism), With<ActiveOrganism>>,
+    mut selection: ResMut<BrainSelection>,
+    mut edits: MessageWriter<EditBrain>,
+) {
+    let (entity, organism) = *organism;
+    let neurons = organism.genome().neurons();
+    let step = if input.just_pressed(keybinds.key_edit_increase) {
+        EDIT_STEP
+    } else if input.just_pressed(keybinds.key_edit_decrease) {
+        -EDIT_STEP
+    } else {
+        0.
+    };
+    let delete = input.just_pressed(keybinds.key_edit_delete);
+
+    let edit = match *selection {
+        BrainSelection::Nothing => None,
+        BrainSelection::Neuron(neuron) => {
+            // it was removed, by this editor or anything else
+            let Some(current) = neurons.get(neuron) else {
+                *selection = BrainSelection::Nothing;
+                return;
+            };
+            if delete {
+                Some(BrainEdit::RemoveHidden(neuron))
+            } else if step != 0. {
+                Some(BrainEdit::SetBias {
+                    neuron,
+                    bias: current.bias + step,
+                })
+            } else if input.just_pressed(keybinds.key_edit_activation) {
+                Some(BrainEdit::SetActivation {
+                    neuron,
+                    activation: next_activation(current.activation),
+                })
+            } else {
+                None
+            }
+        }
+        BrainSelection::Connection { from, to } => {
+            let Some(current) = neurons.inputs(to).find(|c| c.from == from) else {
+                *selection = BrainSelection::Nothing;
+                return;
+            };
+            if delete {
+                Some(BrainEdit::Disconnect { from, to })
+            } else if step != 0. {
+                Some(BrainEdit::SetWeight {
+                    from,
+                    to,
+                    weight: current.weight + step,
+                })
+            } else {
+                None
+            }
+        }
+    };
+
+    if let Some(edit) = edit {
+        edits.write(EditBrain {
+            organism: entity,
+            edit,
+        });
+    }
+}
+
+fn next_activation(activation: Activation) -> Activation {
+    Activation::iter()
+        .cycle()
+        .skip_while(|other| *other != activation)
+        .nth(1)
+        .unwrap_or(activation)
+}
+
+fn show_selection(
+    selection: Res<BrainSelection>,
+    keybinds: Res<Keybinds>,
+    organism: Option<Single<&Organism, With<ActiveOrganism>>>,
+    texts: Query<&mut Text, With<SelectionText>>,
+) {
+    let neurons = organism
+        .as_ref()
+        .map(|organism| organism.genome().neurons());
+    let description = match (*selection, neurons) {
+        (BrainSelection::Neuron(neuron), Some(neurons)) => neurons.get(neuron).map(|current| {
+            format!(
+                "Neuron {neuron} ({:?}): bias {:.2}, {}\n{:?}/{:?} bias, {:?} activation, {:?} remove",
+                current.kind,
+                current.bias,
+                current.activation,
+                keybinds.key_edit_increase,
+                keybinds.key_edit_decrease,
+                keybinds.key_edit_activation,
+                keybinds.key_edit_delete,
+            )
+        }),
+        (BrainSelection::Connection { from, to }, Some(neurons)) => neurons
+            .inputs(to)
+            .find(|c| c.from == from)
+            .map(|current| {
+                format!(
+                    "{from} -> {to}: weight {:.2}\n{:?}/{:?} weight, {:?} remove",
+                    current.weight,
+                    keybinds.key_edit_increase,
+                    keybinds.key_edit_decrease,
+                    keybinds.key_edit_delete,
+                )
+            }),
+        _ => None,
+    };
+    let description = description
+        .unwrap_or_else(|| "Click a node or edge to edit it, or drag between nodes".to_string());
+
+    for mut text in texts {
+        if text.0 != description {
+            text.0 = description.clone();
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    cell::Cells,
+    cpu_net::{Brains, Cell, CpuNetwork},
+    genome::{NeuronId, NeuronKind, layers},
+    node_visual::{EntityGraphMap, Nid},
+    organism::ActiveOrganism,
+    settings::Keybinds,
+};
+
+/// How the brain viewer arranges its nodes.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum GraphLayout {
+    /// Inputs on the left, outputs on the right, and hidden neurons in columns between them
+    #[default]
+    Layered,
+    /// Nodes push away from each other and from edges, a little every frame
+    Force,
+}
+
+impl GraphLayout {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Layered => Self::Force,
+            Self::Force => Self::Layered,
+        }
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GraphLayout>();
+    app.add_systems(
+        Update,
+        (
+            toggle_layout,
+            arrange_layers
+                .run_if(resource_changed::<GraphLayout>.and(resource_equals(GraphLayout::Layered))),
+        )
+            .chain(),
+    );
+}
+
+/// Horizontal distance between two columns
+pub const COLUMN_SPACING: f32 = 280.;
+/// Vertical distance between two nodes in a column
+pub const ROW_SPACING: f32 = 90.;
+
+/// Where each neuron of `network` goes in a layered layout, centered on the origin.
+///
+/// Input neurons take the leftmost column, grouped by cell in the order `cells` come in.
+/// Outputs take the rightmost, still grouped by cell, but cells and the outputs within
+/// them are sorted to line up with what feeds them. See [`layers`] for the rest.
+///
+/// Recurrent connections are left out of the depth, so loops don't stretch the layout.
+pub fn layered<'a>(
+    network: &CpuNetwork,
+    cells: impl IntoIterator<Item = &'a Cell>,
+) -> HashMap<NeuronId, Vec2> {
+    let cells: Vec<&Cell> = cells.into_iter().collect();
+    let count = network.num_neurons();
+    let mut is_output = vec![false; count];
+    for cell in &cells {
+        for output in cell.output_neurons() {
+            is_output[*output] = true;
+        }
+    }
+
+    let mut order = Vec::with_capacity(count);
+    let mut groups = Vec::with_capacity(count);
+    for (i, cell) in cells.iter().enumerate() {
+        order.extend_from_slice(cell.input_neurons());
+        groups.resize(order.len(), i);
+    }
+    order.extend((network.num_inputs()..count).filter(|neuron| !is_output[*neuron]));
+    groups.resize(order.len(), cells.len());
+    for (i, cell) in cells.iter().enumerate() {
+        order.extend_from_slice(cell.output_neurons());
+        groups.resize(order.len(), i);
+    }
+    let mut slots = vec![None; count];
+    for (slot, neuron) in order.iter().enumerate() {
+        slots[*neuron] = Some(slot);
+    }
+    let kinds: Vec<NeuronKind> = order
+        .iter()
+        .map(|neuron| {
+            if is_output[*neuron] {
+                NeuronKind::Output
+            } else if network.activation(*neuron).is_some() {
+                NeuronKind::Hidden
+            } else {
+                NeuronKind::Input
+            }
+        })
+        .collect();
+
+    // neurons only read from neurons before them, except through recurrent connections
+    let mut edges = Vec::new();
+    for neuron in 0..count {
+        for (source, _) in network.inputs(neuron) {
+            if source < neuron
+                && let (Some(from), Some(to)) = (slots[source], slots[neuron])
+            {
+                edges.push((from, to));
+            }
+        }
+    }
+
+    let grid = layers(&kinds, &groups, &edges);
+    let left = grid.iter().map(|at| at.x).fold(0., f32::max) * COLUMN_SPACING * 0.5;
+    order
+        .iter()
+        .zip(grid)
+        .map(|(neuron, at)| {
+            (
+                network.id(*neuron),
+                Vec2::new(at.x * COLUMN_SPACING - left, at.y * ROW_SPACING),
+            )
+        })
+        .collect()
+}
+
+fn toggle_layout(
+    input: Res<ButtonInput<KeyCode>>,
+    keybinds: Res<Keybinds>,
+    mut layout: ResMut<GraphLayout>,
+) {
+    if input.just_pressed(keybinds.key_toggle_layout) {
+        *layout = layout.toggled();
+        info!("Brain layout: {:?}", *layout);
+    }
+}
+
+/// Puts every node back in its column, undoing whatever the force layout did.
+fn arrange_layers(
+    organism: Single<(Entity, &Cells), With<ActiveOrganism>>,
+    brains: Res<Brains>,
+    cells: Query<&Cell>,
+    graph_map: Res<EntityGraphMap>,
+    mut nodes: Query<&mut Transform, With<Nid>>,
+) {
+    let (entity, organism) = *organism;
+    let Some((network, _)) = brains.get(entity) else {
+        return;
+    };
+    let locations = layered(
+        network,
+        organism
+            .cells()
+            .iter()
+            .filter_map(|cell| cells.get(*cell).ok()),
+    );
+    for (id, location) in locations {
+        let Some(node) = graph_map.get_entity(&id) else {
+            continue;
+        };
+        if let Ok(mut transform) = nodes.get_mut(*node) {
+            transform.translation.x = location.x;
+            transform.translation.y = location.y;
+        }
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::{cell::CellKind, genome::Genome},
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[cfg(test)]
+fn layout_of(genome: &Genome) -> (CpuNetwork, Vec<Cell>, HashMap<NeuronId, Vec2>) {
+    let (network, cells) = CpuNetwork::new(genome);
+    let mut cells: Vec<(IVec2, Cell)> = cells.into_iter().collect();
+    cells.sort_by_key(|(location, _)| (location.x, location.y));
+    let cells: Vec<Cell> = cells.into_iter().map(|(_, cell)| cell).collect();
+    let locations = layered(&network, &cells);
+    (network, cells, locations)
+}
+
+#[test]
+fn test_columns_run_from_inputs_to_outputs() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    // a second hidden neuron behind the first pushes the outputs a column further out
+    let first = genome.hidden_neurons()[0];
+    let second = genome.add_hidden(&mut rng);
+    genome.neurons.connect(first, second, 1.);
+    for output in genome.cells.get(&IVec2::new(1, 0)).unwrap().outputs.clone() {
+        genome.neurons.connect(second, output, 1.);
+    }
+
+    let (network, cells, locations) = layout_of(&genome);
+    let x = |neuron: usize| locations[&network.id(neuron)].x;
+    let (eye, launcher) = (&cells[0], &cells[1]);
+
+    let input = x(eye.input_neurons()[0]);
+    let output = x(launcher.output_neurons()[0]);
+    assert_eq!(input, -output, "The layout is centered");
+    assert_eq!(locations[&first].x, input + COLUMN_SPACING);
+    assert_eq!(locations[&second].x, input + COLUMN_SPACING * 2.);
+    assert_eq!(output, input + COLUMN_SPACING * 3.);
+    for neuron in launcher.output_neurons() {
+        assert_eq!(x(*neuron), output);
+    }
+}
+
+#[test]
+fn test_inputs_are_grouped_by_cell() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::from_cells(
+        vec![
+            (CellKind::Eye, IVec2::new(0, 0)),
+            (CellKind::Data, IVec2::new(1, 0)),
+            (CellKind::Eye, IVec2::new(2, 0)),
+        ],
+        &mut rng,
+    );
+
+    let (network, cells, locations) = layout_of(&genome);
+    let heights: Vec<Vec<f32>> = cells
+        .iter()
+        .map(|cell| {
+            cell.input_neurons()
+                .iter()
+                .map(|neuron| locations[&network.id(*neuron)].y)
+                .collect()
+        })
+        .collect();
+
+    // top to bottom, one cell after the other
+    let flat: Vec<f32> = heights.concat();
+    assert!(flat.windows(2).all(|pair| pair[0] > pair[1]));
+    assert_eq!(flat.len(), 2 + 4 + 2);
+}
+
+#[test]
+fn test_crossed_edges_are_untangled() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::from_cells(
+        vec![
+            (CellKind::Eye, IVec2::new(0, 0)),
+            (CellKind::Eye, IVec2::new(1, 0)),
+            (CellKind::Foot, IVec2::new(2, 0)),
+        ],
+        &mut rng,
+    );
+    let upper = genome.cells.get(&IVec2::new(0, 0)).unwrap().inputs.clone();
+    let lower = genome.cells.get(&IVec2::new(1, 0)).unwrap().inputs.clone();
+    let outputs = genome.cells.get(&IVec2::new(2, 0)).unwrap().outputs.clone();
+    // each hidden neuron reads the eye the other one is next to, and feeds the other output
+    for (inputs, output) in [(&lower, outputs[0]), (&upper, outputs[1])] {
+        let hidden = genome.add_hidden(&mut rng);
+        for input in inputs {
+            genome.neurons.connect(*input, hidden, 1.);
+        }
+        genome.neurons.connect(hidden, output, 1.);
+    }
+
+    let (network, _, locations) = layout_of(&genome);
+    let y = |id: NeuronId| locations[&id].y;
+    let edges: Vec<(NeuronId, NeuronId)> = genome
+        .neurons()
+        .connections()
+        .iter()
+        .map(|c| (c.from, c.to))
+        .collect();
+    for (a_from, a_to) in &edges {
+        for (b_from, b_to) in &edges {
+            let same_column = locations[a_from].x == locations[b_from].x
+                && locations[a_to].x == locations[b_to].x;
+            if same_column {
+                assert!(
+                    (y(*a_from) - y(*b_from)) * (y(*a_to) - y(*b_to)) >= 0.,
+                    "Edges shouldn't cross"
+                );
+            }
+        }
+    }
+    assert_eq!(locations.len(), network.num_neurons());
+}
@@ -1,15 +1,20 @@
 mod edge;
-use std::collections::HashMap;
-
 pub use edge::*;
 
 mod node;
 pub use node::*;
 
+mod layout;
+pub use layout::*;
+
+mod edit;
+pub use edit::*;
+
 use bevy::{
     asset::uuid::Uuid,
     camera::visibility::RenderLayers,
     color::palettes::tailwind::{BLUE_400, GREEN_400, RED_400},
+    platform::collections::{HashMap, HashSet},
     prelude::*,
 };
 use bimap::BiMap;
@@ -17,7 +22,8 @@ use bimap::BiMap;
 use crate::{
     camera::RenderLayer,
     cell::Cells,
-    cpu_net::{Cell, CpuNeuron},
+    cpu_net::{Brains, Cell, CpuNetwork},
+    genome::NeuronId,
     organism::ActiveOrganism,
 };
 
@@ -28,30 +34,34 @@ const MIN_DISTANCE: f32 = 140.;
 
 #[derive(Resource, Default)]
 pub struct EntityGraphMap {
-    entity_map: BiMap<Entity, Uuid>,
+    entity_map: BiMap<Entity, NeuronId>,
     /// contains the (sender, receiver), edge id
-    connections: HashMap<(Uuid, Uuid), Uuid>, //connection_map
+    connections: HashMap<(NeuronId, NeuronId), Uuid>, //connection_map
 }
 impl EntityGraphMap {
-    pub fn insert(&mut self, entity: Entity, id: Uuid) {
+    pub fn insert(&mut self, entity: Entity, id: NeuronId) {
         self.entity_map.insert(entity, id);
     }
-    pub fn get_uuid(&self, entity: &Entity) -> Option<&Uuid> {
+    pub fn get_id(&self, entity: &Entity) -> Option<&NeuronId> {
         self.entity_map.get_by_left(entity)
     }
-    pub fn get_entity(&self, uuid: &Uuid) -> Option<&Entity> {
-        self.entity_map.get_by_right(uuid)
+    pub fn get_entity(&self, id: &NeuronId) -> Option<&Entity> {
+        self.entity_map.get_by_right(id)
     }
     fn remove(&mut self, entity: &Entity) {
         self.entity_map.remove_by_left(entity);
     }
+    fn remove_conn(&mut self, sender: NeuronId, receiver: NeuronId) {
+        self.connections.remove(&(sender, receiver));
+    }
     fn clear(&mut self) {
         self.entity_map.clear();
+        self.connections.clear();
     }
-    pub fn get(&self, sender: Uuid, receiver: Uuid) -> Option<Uuid> {
+    pub fn get(&self, sender: NeuronId, receiver: NeuronId) -> Option<Uuid> {
         self.connections.get(&(sender, receiver)).copied()
     }
-    pub fn insert_conn(&mut self, sender: Uuid, receiver: Uuid) -> Uuid {
+    pub fn insert_conn(&mut self, sender: NeuronId, receiver: NeuronId) -> Uuid {
         let id = Uuid::new_v4();
         self.connections.insert((sender, receiver), id);
         id
@@ -61,184 +71,503 @@ impl EntityGraphMap {
 #[derive(Component)]
 pub struct GraphComponent;
 
+/// Meshes shared by every node and edge, so rebuilding the graph doesn't add new ones.
+#[derive(Resource)]
+struct GraphMeshes {
+    node: Handle<Mesh>,
+    line: Handle<Mesh>,
+    pulse: Handle<Mesh>,
+}
+
+impl FromWorld for GraphMeshes {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self {
+            node: meshes.add(Circle::new(NODE_RADIUS)),
+            line: meshes.add(Rectangle::new(LINE_MESH_X, LINE_MESH_Y)),
+            pulse: meshes.add(Circle::new(10.)),
+        }
+    }
+}
+
+/// The brain the graph was last built from.
+#[derive(Resource, Default)]
+struct ShownBrain {
+    organism: Option<Entity>,
+    /// See [`Brains::revision`]
+    revision: u64,
+}
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((node::plugin, edge::plugin));
+    app.add_plugins((node::plugin, edge::plugin, layout::plugin, edit::plugin));
     app.init_resource::<EntityGraphMap>();
+    app.init_resource::<ShownBrain>();
+    app.init_resource::<GraphMeshes>();
 
-    app.add_systems(PreUpdate, spawn_new_nodes);
+    app.add_systems(
+        PreUpdate,
+        (
+            sync_graph,
+            send_signal_updates.run_if(resource_changed::<Brains>),
+        )
+            .chain(),
+    );
 }
 
-fn spawn_new_nodes(
+/// Keeps the graph in step with the selected organism's brain.
+///
+/// Selecting another organism tears the graph down and builds it again. A new network for
+/// the same organism only spawns and despawns what changed, leaving everything else where it
+/// is. Once nothing is selected, or the selected organism has no brain yet, the graph is
+/// cleared.
+fn sync_graph(
     mut commands: Commands,
-    organism: Single<&Cells, With<ActiveOrganism>>,
+    organism: Option<Single<(Entity, &Cells), With<ActiveOrganism>>>,
+    brains: Res<Brains>,
     cells: Query<&Cell>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    graph: Query<Entity, With<GraphComponent>>,
+    nodes: Query<(Entity, &Nid, &NodeValueText)>,
+    edges: Query<(Entity, &Edge)>,
+    mut texts: Query<&mut Text2d>,
+    meshes: Res<GraphMeshes>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut map: ResMut<EntityGraphMap>,
+    mut shown: ResMut<ShownBrain>,
+    mut selection: ResMut<BrainSelection>,
+    mut layout: ResMut<GraphLayout>,
 ) {
-    let circle = meshes.add(Circle::new(NODE_RADIUS));
-
-    let mut x = 0.;
-    let mut y = -20.;
+    let Some(organism) = organism else {
+        if shown.organism.take().is_some() {
+            clear_graph(commands.reborrow(), &graph, &mut map);
+            *selection = BrainSelection::Nothing;
+        }
+        return;
+    };
+    let (entity, organism) = *organism;
+    let (Some((network, _)), Some(revision)) = (brains.get(entity), brains.revision(entity)) else {
+        // the new selection's brain isn't built yet, so don't leave the last one up meanwhile
+        if shown.organism.is_some_and(|shown| shown != entity) {
+            clear_graph(commands.reborrow(), &graph, &mut map);
+            *selection = BrainSelection::Nothing;
+            shown.organism = None;
+        }
+        return;
+    };
+    if shown.organism == Some(entity) && shown.revision == revision {
+        return;
+    }
+    let organism_cells: Vec<&Cell> = organism
+        .cells()
+        .iter()
+        .filter_map(|cell| cells.get(*cell).ok())
+        .collect();
 
-    for cell in organism.cells() {
-        let Ok(cell) = cells.get(*cell) else {
-            warn!("CELL MISSING CELL");
-            continue;
-        };
+    let (neurons, connections) = drawn_graph(network, organism_cells.iter().copied());
+    let mut names = HashMap::new();
+    for cell in &organism_cells {
         for (i, neuron) in cell.output_neurons().iter().enumerate() {
-            let name = format!("{:?} Output {i}", cell.kind());
-            neuron_spawner(
-                commands.reborrow(),
-                &circle,
-                materials.as_mut(),
-                &mut x,
-                &mut y,
-                map.as_mut(),
-                neuron,
-                name,
+            names.insert(*neuron, format!("{:?} Output {i}", cell.kind()));
+        }
+    }
+    let name_of = |neuron: usize| {
+        names
+            .get(&neuron)
+            .cloned()
+            .unwrap_or_else(|| neuron_name(network, neuron))
+    };
+
+    if shown.organism != Some(entity) {
+        clear_graph(commands.reborrow(), &graph, &mut map);
+        *selection = BrainSelection::Nothing;
+    } else {
+        for (edge_entity, edge) in &edges {
+            let ends = (
+                map.get_id(&edge.sender()).copied(),
+                map.get_id(&edge.receiver()).copied(),
             );
+            if let (Some(from), Some(to)) = ends
+                && connections.contains(&(from, to))
+            {
+                continue;
+            }
+            commands.entity(edge_entity).despawn();
+            if let (Some(from), Some(to)) = ends {
+                map.remove_conn(from, to);
+            }
+        }
+        for (node_entity, id, _) in &nodes {
+            if !neurons.contains(&id.0) {
+                commands.entity(node_entity).despawn();
+                map.remove(&node_entity);
+            }
+        }
+        // a kept neuron may have a new activation
+        for neuron in network.num_inputs()..network.num_neurons() {
+            if let Some(node) = map.get_entity(&network.id(neuron))
+                && let Ok((_, _, labels)) = nodes.get(*node)
+                && let Ok(mut name) = texts.get_mut(labels.name)
+            {
+                name.0 = name_of(neuron);
+            }
         }
     }
+    shown.organism = Some(entity);
+    shown.revision = revision;
+    // new nodes are placed in the new layout, so the old ones have to follow
+    layout.set_changed();
 
-    for cell in organism.cells() {
-        let Ok(cell) = cells.get(*cell) else {
-            continue;
-        };
+    let locations = layout::layered(network, organism_cells.iter().copied());
 
-        for neuron in cell.output_neurons() {
-            edge_spawner(
+    // anything can be new, even behind neurons that are already drawn
+    for neuron in 0..network.num_neurons() {
+        let id = network.id(neuron);
+        if !neurons.contains(&id) || map.get_entity(&id).is_some() {
+            continue;
+        }
+        spawn_neuron(
+            commands.reborrow(),
+            &meshes.node,
+            materials.as_mut(),
+            &locations,
+            map.as_mut(),
+            id,
+            name_of(neuron),
+        );
+    }
+    for neuron in 0..network.num_neurons() {
+        let id = network.id(neuron);
+        for (input, _) in network.inputs(neuron) {
+            let input_id = network.id(input);
+            // parallel connections share the first one's edge
+            if !connections.contains(&(input_id, id)) || map.get(input_id, id).is_some() {
+                continue;
+            }
+            spawn_edge(
                 commands.reborrow(),
-                meshes.as_mut(),
+                &meshes,
                 materials.as_mut(),
                 map.as_mut(),
-                neuron,
+                input_id,
+                id,
             );
         }
     }
 }
 
-fn neuron_spawner(
+/// Sends the values of the selected brain's last tick to its nodes and edges.
+fn send_signal_updates(
+    organism: Single<Entity, With<ActiveOrganism>>,
+    brains: Res<Brains>,
+    map: Res<EntityGraphMap>,
+    mut nodes: MessageWriter<NodeUpdates>,
+    mut edges: MessageWriter<EdgeUpdates>,
+) {
+    let Some((network, values)) = brains.get(*organism) else {
+        return;
+    };
+
+    nodes.write(NodeUpdates::set(
+        (0..network.num_neurons()).map(|neuron| (network.id(neuron), values[neuron])),
+    ));
+    edges.write(EdgeUpdates::set(edge_signals(
+        network,
+        values,
+        |sender, receiver| map.get(sender, receiver),
+    )));
+}
+
+/// What each drawn edge carried, given the value of every neuron.
+///
+/// An edge stands for every connection between its two neurons, so parallel connections
+/// add up.
+fn edge_signals(
+    network: &CpuNetwork,
+    values: &[f32],
+    edge: impl Fn(NeuronId, NeuronId) -> Option<Uuid>,
+) -> HashMap<Uuid, EdgeSignal> {
+    let mut signals: HashMap<Uuid, EdgeSignal> = HashMap::new();
+    for neuron in 0..network.num_neurons() {
+        let id = network.id(neuron);
+        for (source, weight) in network.inputs(neuron) {
+            if let Some(edge) = edge(network.id(source), id) {
+                let signal = signals.entry(edge).or_default();
+                signal.weight += weight;
+                signal.signal += values[source] * weight;
+            }
+        }
+    }
+    signals
+}
+
+fn clear_graph(
+    mut commands: Commands,
+    graph: &Query<Entity, With<GraphComponent>>,
+    map: &mut EntityGraphMap,
+) {
+    for entity in graph {
+        commands.entity(entity).despawn();
+    }
+    map.clear();
+}
+
+/// The neurons and connections drawn for `network`: everything that leads to an output.
+fn drawn_graph<'a>(
+    network: &CpuNetwork,
+    cells: impl IntoIterator<Item = &'a Cell>,
+) -> (HashSet<NeuronId>, HashSet<(NeuronId, NeuronId)>) {
+    let mut stack: Vec<usize> = cells
+        .into_iter()
+        .flat_map(|cell| cell.output_neurons())
+        .copied()
+        .collect();
+    let mut neurons = HashSet::new();
+    let mut connections = HashSet::new();
+    while let Some(neuron) = stack.pop() {
+        let id = network.id(neuron);
+        if !neurons.insert(id) {
+            continue;
+        }
+        for (input, _) in network.inputs(neuron) {
+            connections.insert((network.id(input), id));
+            stack.push(input);
+        }
+    }
+    (neurons, connections)
+}
+
+fn neuron_name(network: &CpuNetwork, neuron: usize) -> String {
+    match network.activation(neuron) {
+        Some(activation) => format!("Node ({activation})"),
+        None => "Node".to_string(),
+    }
+}
+
+fn spawn_neuron(
     mut commands: Commands,
     circle: &Handle<Mesh>,
     materials: &mut Assets<ColorMaterial>,
-    x: &mut f32,
-    y: &mut f32,
+    locations: &HashMap<NeuronId, Vec2>,
     map: &mut EntityGraphMap,
-    neuron: &CpuNeuron,
+    id: NeuronId,
     name: String,
 ) {
-    let id = neuron.id();
-    if map.get_entity(&id).is_none() {
-        let neuron_entity = commands
-            .spawn((
-                GraphComponent,
-                RenderLayers::from(RenderLayer::NODE_VISUAL),
-                Nid(id),
-                Mesh2d(circle.clone()),
-                MeshMaterial2d(materials.add(Color::WHITE)),
-                Transform::from_xyz(*x, *y, NODE_LAYER),
-            ))
-            .id();
-
-        map.insert(neuron_entity, id);
-
-        let name = commands
-            .spawn((
-                Text2d::new(name),
-                RenderLayers::from(RenderLayer::NODE_VISUAL),
-                TextColor(RED_400.into()),
-                ChildOf(neuron_entity),
-            ))
-            .id();
-
-        let value = commands
-            .spawn((
-                Text2d::new("VALUE"),
-                RenderLayers::from(RenderLayer::NODE_VISUAL),
-                Transform::from_xyz(0., -20., 0.),
-                TextColor(BLUE_400.into()),
-                ChildOf(neuron_entity),
-            ))
-            .id();
-
-        commands
-            .entity(neuron_entity)
-            .insert(NodeValueText { name, value });
-        *x += 12.;
-        if *y > 0. {
-            *y += 6.;
-        } else {
-            *y -= 6.;
-        }
-        *y *= -1.;
-    }
+    let location = locations.get(&id).copied().unwrap_or_default();
+    let neuron_entity = commands
+        .spawn((
+            GraphComponent,
+            RenderLayers::from(RenderLayer::NODE_VISUAL),
+            Nid(id),
+            Mesh2d(circle.clone()),
+            MeshMaterial2d(materials.add(Color::WHITE)),
+            Transform::from_xyz(location.x, location.y, NODE_LAYER),
+            Pickable::default(),
+        ))
+        .observe(edit::select_neuron)
+        .observe(edit::connect_neurons)
+        .id();
 
-    neuron.on_inputs(|input_neuron| {
-        neuron_spawner(
-            commands.reborrow(),
-            circle,
-            materials,
-            x,
-            y,
-            map,
-            input_neuron,
-            "Node".to_string(),
-        );
-    });
+    map.insert(neuron_entity, id);
+
+    let name = commands
+        .spawn((
+            Text2d::new(name),
+            RenderLayers::from(RenderLayer::NODE_VISUAL),
+            TextColor(RED_400.into()),
+            ChildOf(neuron_entity),
+        ))
+        .id();
+
+    let value = commands
+        .spawn((
+            Text2d::new("VALUE"),
+            RenderLayers::from(RenderLayer::NODE_VISUAL),
+            Transform::from_xyz(0., -20., 0.),
+            TextColor(BLUE_400.into()),
+            ChildOf(neuron_entity),
+        ))
+        .id();
+
+    commands
+        .entity(neuron_entity)
+        .insert(NodeValueText { name, value });
 }
 
-fn edge_spawner(
+/// Draws the edge from `sender` to `receiver`, whose nodes must already be spawned.
+fn spawn_edge(
     mut commands: Commands,
-    meshes: &mut Assets<Mesh>,
+    meshes: &GraphMeshes,
     materials: &mut Assets<ColorMaterial>,
     map: &mut EntityGraphMap,
-    neuron: &CpuNeuron,
+    sender: NeuronId,
+    receiver: NeuronId,
 ) {
-    let neuron_id = neuron.id();
-    let neuron_e = *map.get_entity(&neuron.id()).unwrap();
-
-    let mut new_edges = Vec::new();
-    neuron.on_inputs(|input_neuron| {
-        let receives_from_id = input_neuron.id();
-        if map.get(receives_from_id, neuron_id).is_none() {
-            let Some(receives_from) = map.get_entity(&receives_from_id).copied() else {
-                return;
-            };
-            let connection_id = map.insert_conn(receives_from_id, neuron_id);
-
-            let edge = commands
-                .spawn((
-                    GraphComponent,
-                    RenderLayers::from(RenderLayer::NODE_VISUAL),
-                    Edge::new(connection_id, receives_from, neuron_e),
-                    // Mesh2d(meshes.add(Rectangle::new(LINE_MESH_X, LINE_MESH_Y))),
-                    // MeshMaterial2d(materials.add(Color::WHITE)),
-                    Transform::from_xyz(0., 0., EDGE_LAYER),
-                    InheritedVisibility::VISIBLE,
-                ))
-                .id();
-
-            commands.spawn((
-                EdgeRectangleOf(edge),
-                RenderLayers::from(RenderLayer::NODE_VISUAL),
-                Mesh2d(meshes.add(Rectangle::new(LINE_MESH_X, LINE_MESH_Y))),
-                MeshMaterial2d(materials.add(Color::WHITE)),
-                ChildOf(edge),
-            ));
-            commands.spawn((
-                EdgeCircleOf(edge),
-                RenderLayers::from(RenderLayer::NODE_VISUAL),
-                Mesh2d(meshes.add(Circle::new(10.))),
-                MeshMaterial2d(materials.add(Color::from(GREEN_400))),
-                ChildOf(edge),
-            ));
-
-            map.insert(edge, connection_id);
-
-            new_edges.push(edge);
-        }
+    let (Some(sender_e), Some(receiver_e)) = (
+        map.get_entity(&sender).copied(),
+        map.get_entity(&receiver).copied(),
+    ) else {
+        return;
+    };
+    let connection_id = map.insert_conn(sender, receiver);
+
+    let edge = commands
+        .spawn((
+            GraphComponent,
+            RenderLayers::from(RenderLayer::NODE_VISUAL),
+            Edge::new(connection_id, sender_e, receiver_e),
+            EdgeSignal::default(),
+            EdgePulse::default(),
+            Transform::from_xyz(0., 0., EDGE_LAYER),
+            InheritedVisibility::VISIBLE,
+        ))
+        .id();
+
+    commands
+        .spawn((
+            EdgeRectangleOf(edge),
+            RenderLayers::from(RenderLayer::NODE_VISUAL),
+            Mesh2d(meshes.line.clone()),
+            MeshMaterial2d(materials.add(Color::WHITE)),
+            Pickable::default(),
+            ChildOf(edge),
+        ))
+        .observe(edit::select_connection);
+    commands.spawn((
+        EdgeCircleOf(edge),
+        RenderLayers::from(RenderLayer::NODE_VISUAL),
+        Mesh2d(meshes.pulse.clone()),
+        MeshMaterial2d(materials.add(Color::from(GREEN_400))),
+        ChildOf(edge),
+    ));
+}
 
-        edge_spawner(commands.reborrow(), meshes, materials, map, input_neuron);
+#[cfg(test)]
+use {
+    crate::{cell::CellOf, genome::Genome},
+    bevy::ecs::system::RunSystemOnce,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+/// A world with everything [`sync_graph`] needs, and the organism it shows
+#[cfg(test)]
+fn graph_world() -> (World, Entity) {
+    let mut world = World::new();
+    world.init_resource::<Assets<Mesh>>();
+    world.init_resource::<Assets<ColorMaterial>>();
+    world.init_resource::<GraphMeshes>();
+    world.init_resource::<Brains>();
+    world.init_resource::<EntityGraphMap>();
+    world.init_resource::<ShownBrain>();
+    world.init_resource::<BrainSelection>();
+    world.init_resource::<GraphLayout>();
+    let organism = world.spawn(ActiveOrganism).id();
+    (world, organism)
+}
+
+/// Gives `organism` a brain compiled from `genome` and syncs the graph to it.
+#[cfg(test)]
+fn show_genome(world: &mut World, organism: Entity, genome: &Genome) {
+    let old_cells: Vec<Entity> = world
+        .query_filtered::<Entity, With<CellOf>>()
+        .iter(world)
+        .collect();
+    for cell in old_cells {
+        world.despawn(cell);
+    }
+    let (network, cells) = CpuNetwork::new(genome);
+    for cell in cells.into_values() {
+        world.spawn((cell, CellOf(organism)));
+    }
+    world.resource_mut::<Brains>().insert(organism, network);
+    world.run_system_once(sync_graph).unwrap();
+}
+
+#[test]
+fn test_parallel_connections_share_an_edge() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+    let output = genome.cells().get(&IVec2::new(1, 0)).unwrap().outputs[0];
+    genome.neurons.connect(hidden, output, 2.);
+
+    let (network, _) = CpuNetwork::new(&genome);
+    let values: Vec<f32> = (0..network.len()).map(|value| value as f32).collect();
+    let edge = Uuid::from_u128(1);
+    let signals = edge_signals(&network, &values, |sender, receiver| {
+        (sender == hidden && receiver == output).then_some(edge)
     });
+
+    let sender = (0..network.num_neurons())
+        .find(|neuron| network.id(*neuron) == hidden)
+        .unwrap();
+    assert_eq!(
+        signals[&edge],
+        EdgeSignal {
+            weight: 3.,
+            signal: values[sender] * 3.,
+        }
+    );
+}
+
+#[test]
+fn test_new_connections_behind_drawn_neurons_are_drawn() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let hidden = genome.hidden_neurons()[0];
+    let input = genome.cells().get(&IVec2::ZERO).unwrap().inputs[0];
+
+    let (mut world, organism) = graph_world();
+
+    show_genome(&mut world, organism, &genome);
+    let edges = world.resource::<EntityGraphMap>().connections.len();
+    assert!(
+        world
+            .resource::<EntityGraphMap>()
+            .get_entity(&hidden)
+            .is_some()
+    );
+
+    // hidden -> output is already drawn, so the new neuron sits behind it
+    let extra = genome.add_hidden(&mut rng);
+    genome.neurons.connect(input, extra, 1.);
+    genome.neurons.connect(extra, hidden, 1.);
+    show_genome(&mut world, organism, &genome);
+
+    let map = world.resource::<EntityGraphMap>();
+    let extra_node = *map.get_entity(&extra).unwrap();
+    assert!(map.get(input, extra).is_some());
+    assert!(map.get(extra, hidden).is_some());
+    assert_eq!(map.connections.len(), edges + 2);
+    assert!(world.get::<Nid>(extra_node).is_some());
+    assert_eq!(
+        world
+            .query_filtered::<(), With<Edge>>()
+            .iter(&world)
+            .count(),
+        edges + 2
+    );
+}
+
+#[test]
+fn test_output_labels_survive_a_new_revision() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut genome = Genome::simple_linear(&mut rng);
+    let output = genome.cells().get(&IVec2::new(1, 0)).unwrap().outputs[0];
+    let (mut world, organism) = graph_world();
+    let label = |world: &World| {
+        let node = *world
+            .resource::<EntityGraphMap>()
+            .get_entity(&output)
+            .unwrap();
+        let name = world.get::<NodeValueText>(node).unwrap().name;
+        world.get::<Text2d>(name).unwrap().0.clone()
+    };
+
+    show_genome(&mut world, organism, &genome);
+    assert_eq!(label(&world), "Launcher Output 0");
+
+    for connection in genome.neurons.connections_mut() {
+        connection.weight = 2.;
+    }
+    show_genome(&mut world, organism, &genome);
+    assert_eq!(label(&world), "Launcher Output 0");
 }
@@ -1,18 +1,23 @@
-use bevy::{color::palettes::tailwind::RED_400, platform::collections::HashMap, prelude::*};
-use uuid::Uuid;
+use bevy::{platform::collections::HashMap, prelude::*};
 
 use crate::{
-    cpu_net::Cell,
-    node_visual::{Edge, EntityGraphMap},
+    cell::CellOf,
+    cpu_net::{Brains, Cell},
+    genome::{NEGATIVE_SIGNAL, NeuronId, POSITIVE_SIGNAL},
+    node_visual::{Edge, EntityGraphMap, GraphLayout},
 };
 
 #[derive(Component, Reflect)]
-pub struct Nid(pub Uuid);
+pub struct Nid(pub NeuronId);
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (space_out_nodes, update_node_colors, update_node_text),
+        (
+            space_out_nodes.run_if(resource_equals(GraphLayout::Force)),
+            update_node_colors,
+            update_node_text,
+        ),
     );
 
     app.add_message::<NodeUpdates>();
@@ -20,7 +25,7 @@ pub(super) fn plugin(app: &mut App) {
 
 #[derive(Message)]
 pub struct NodeUpdates {
-    map: HashMap<Uuid, i32>,
+    map: HashMap<NeuronId, f32>,
 }
 impl NodeUpdates {
     pub fn empty() -> Self {
@@ -28,47 +33,58 @@ impl NodeUpdates {
             map: HashMap::new(),
         }
     }
-    pub fn set(values: impl IntoIterator<Item = (Uuid, i32)>) -> Self {
+    pub fn set(values: impl IntoIterator<Item = (NeuronId, f32)>) -> Self {
         Self {
             map: values.into_iter().collect(),
         }
     }
 }
 
+/// Fades from white at `0` toward [`POSITIVE_SIGNAL`] or [`NEGATIVE_SIGNAL`].
+///
+/// Values are squashed with `tanh`, so anything past about `±3` gets the full color.
+pub fn signal_color(value: f32) -> Color {
+    let strength = value.tanh();
+    let end = if strength < 0. {
+        NEGATIVE_SIGNAL
+    } else {
+        POSITIVE_SIGNAL
+    };
+    Srgba::WHITE.mix(&end, strength.abs()).into()
+}
+
 pub const NODE_RADIUS: f32 = 20.;
 
 const MIN_DISTANCE: f32 = 140.;
 
 struct LineInfo {
-    n1: Uuid,
-    n2: Uuid,
+    n1: NeuronId,
+    n2: NeuronId,
     from: Vec2,
     to: Vec2,
     length: f32,
 }
 
+/// Where the nodes on screen are this frame, and the edges between them.
 struct NodeLocationMap {
-    inner: HashMap<Uuid, Vec2>,
+    inner: HashMap<NeuronId, Vec2>,
     lines: Vec<LineInfo>,
 }
 
 impl NodeLocationMap {
-    fn set_current(&mut self, node_id: Uuid, loc: Vec2) {
-        /*
-        if there is a value in inner, and the entity was just added,
-        do not set the value.
-        if there was no value in inner, set the value.
-        */
-        self.inner.insert(node_id, loc);
-
-        //self.inner.insert(node_id, (loc, e));
+    /// Replaces every location, so nodes that are gone stop pushing the others around.
+    fn set_nodes(&mut self, nodes: impl IntoIterator<Item = (NeuronId, Vec2)>) {
+        self.inner.clear();
+        self.inner.extend(nodes);
     }
 
-    fn set_edges<'a>(&mut self, edges: impl IntoIterator<Item = (&'a Uuid, &'a Uuid)>) {
+    fn set_edges<'a>(&mut self, edges: impl IntoIterator<Item = (&'a NeuronId, &'a NeuronId)>) {
         self.lines.clear();
         for (sender, recv) in edges {
-            let sender_loc = self.inner.get(sender).unwrap();
-            let recv_loc = self.inner.get(recv).unwrap();
+            let (Some(sender_loc), Some(recv_loc)) = (self.inner.get(sender), self.inner.get(recv))
+            else {
+                continue;
+            };
             let length = (*recv_loc - *sender_loc).length();
             self.lines.push(LineInfo {
                 n1: *sender,
@@ -147,7 +163,7 @@ impl NodeLocationMap {
         }
     }
 
-    fn iter(&self) -> impl Iterator<Item = (&Uuid, &Vec2)> {
+    fn iter(&self) -> impl Iterator<Item = (&NeuronId, &Vec2)> {
         self.inner.iter()
     }
 }
@@ -167,16 +183,14 @@ fn space_out_nodes(
     mut map: Local<NodeLocationMap>,
     graph_map: Res<EntityGraphMap>,
 ) {
-    for (entity, id) in &nodes {
-        let Ok(transform) = transforms.get(entity) else {
-            continue;
-        };
-        map.set_current(id.0, transform.translation.xy());
-    }
+    map.set_nodes(nodes.iter().filter_map(|(entity, id)| {
+        let transform = transforms.get(entity).ok()?;
+        Some((id.0, transform.translation.xy()))
+    }));
 
     map.set_edges(edges.iter().filter_map(|edge| {
-        let recv = graph_map.get_uuid(&edge.receiver())?;
-        let send = graph_map.get_uuid(&edge.sender())?;
+        let recv = graph_map.get_id(&edge.receiver())?;
+        let send = graph_map.get_id(&edge.sender())?;
         Some((send, recv))
     }));
 
@@ -199,20 +213,15 @@ fn update_node_colors(
     nodes: Query<(&Nid, &MeshMaterial2d<ColorMaterial>)>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for update in reader.read() {
-        for (node, material_handle) in nodes {
-            let Some(material) = materials.get_mut(&material_handle.0) else {
-                continue;
-            };
-            let val = update.map.get(&node.0).copied().unwrap_or_default();
-
-            if val > 0 {
-                material.color = RED_400.into();
-            } else {
-                material.color = Color::WHITE;
-            }
-        }
-        //todo
+    let Some(update) = reader.read().last() else {
+        return;
+    };
+    for (node, material_handle) in nodes {
+        let Some(material) = materials.get_mut(&material_handle.0) else {
+            continue;
+        };
+        let val = update.map.get(&node.0).copied().unwrap_or_default();
+        material.color = signal_color(val);
     }
 }
 #[derive(Component)]
@@ -224,21 +233,30 @@ pub struct NodeValueText {
 fn update_node_text(
     map: Res<EntityGraphMap>,
     nodes: Query<&NodeValueText>,
-    cells: Query<&Cell>,
+    cells: Query<(&Cell, &CellOf)>,
+    brains: Res<Brains>,
     mut text: Query<&mut Text2d>,
 ) {
-    for cell in cells {
+    for (cell, cell_of) in cells {
+        let Some((network, values)) = brains.get(cell_of.0) else {
+            continue;
+        };
         for (i, output_neuron) in cell.output_neurons().iter().enumerate() {
-            let id = output_neuron.id();
+            let id = network.id(*output_neuron);
             if let Some(entity) = map.get_entity(&id)
                 && let Ok(texts) = nodes.get(*entity)
             {
                 if let Ok(mut name) = text.get_mut(texts.name) {
-                    name.0 = format!("{:?}\nOutput Neuron {}", cell.kind(), i);
+                    name.0 = match network.activation(*output_neuron) {
+                        Some(activation) => {
+                            format!("{:?}\nOutput Neuron {} ({activation})", cell.kind(), i)
+                        }
+                        None => format!("{:?}\nOutput Neuron {}", cell.kind(), i),
+                    };
                 }
 
                 if let Ok(mut value) = text.get_mut(texts.value) {
-                    let node_val = output_neuron.process();
+                    let node_val = values[*output_neuron];
                     value.0 = format!("{node_val}");
                 }
 
@@ -247,7 +265,7 @@ fn update_node_text(
         }
 
         for (i, input_neuron) in cell.input_neurons().iter().enumerate() {
-            let id = input_neuron.id();
+            let id = network.id(*input_neuron);
             if let Some(entity) = map.get_entity(&id)
                 && let Ok(texts) = nodes.get(*entity)
             {
@@ -256,7 +274,7 @@ fn update_node_text(
                 }
 
                 if let Ok(mut value) = text.get_mut(texts.value) {
-                    let node_val = input_neuron.process();
+                    let node_val = values[*input_neuron];
                     value.0 = format!("{node_val}");
                 }
 
@@ -266,3 +284,15 @@ fn update_node_text(
     }
     //todo
 }
+
+#[test]
+fn test_signal_colors_diverge_from_white() {
+    assert_eq!(signal_color(0.).to_srgba(), Srgba::WHITE);
+    assert_eq!(signal_color(100.).to_srgba(), POSITIVE_SIGNAL);
+    assert_eq!(signal_color(-100.).to_srgba(), NEGATIVE_SIGNAL);
+
+    // weaker values are paler
+    let weak = signal_color(0.2).to_srgba();
+    let strong = signal_color(1.).to_srgba();
+    assert!(weak.blue > strong.blue);
+}
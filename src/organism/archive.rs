@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::{
+    cell::{CellLocation, Cells},
+    cpu_net::{Brains, Cell},
+    genome::Genome,
+    organism::{ActiveOrganism, Organism, SpawnOrganism},
+    settings::Keybinds,
+};
+
+/// Where saved organisms are written, relative to the working directory.
+pub const ORGANISM_DIR: &str = "organisms";
+/// Where brain diagrams are written, relative to the working directory.
+pub const DIAGRAM_DIR: &str = "diagrams";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            save_active_organism,
+            export_active_brain,
+            load_dropped_organisms,
+        ),
+    );
+}
+
+fn save_active_organism(
+    input: Res<ButtonInput<KeyCode>>,
+    keybinds: Res<Keybinds>,
+    organism: Option<Single<(Entity, &Organism), With<ActiveOrganism>>>,
+) {
+    if !input.just_pressed(keybinds.key_save_organism) {
+        return;
+    }
+    let Some(organism) = organism else {
+        info!("No organism selected to save");
+        return;
+    };
+    let (entity, organism) = *organism;
+
+    let dir = PathBuf::from(ORGANISM_DIR);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        error!("Could not create {}: {e}", dir.display());
+        return;
+    }
+    let path = dir.join(format!("organism-{}.ron", entity.index()));
+    match organism.genome().save(&path) {
+        Ok(()) => info!("Saved organism to {}", path.display()),
+        Err(e) => error!("Could not save organism to {}: {e}", path.display()),
+    }
+}
+
+/// Writes the selected organism's running network, with its last values, as DOT and SVG.
+fn export_active_brain(
+    input: Res<ButtonInput<KeyCode>>,
+    keybinds: Res<Keybinds>,
+    organism: Option<Single<(Entity, &Cells), With<ActiveOrganism>>>,
+    cells: Query<(&Cell, &CellLocation)>,
+    brains: Res<Brains>,
+) {
+    if !input.just_pressed(keybinds.key_export_brain) {
+        return;
+    }
+    let Some(organism) = organism else {
+        info!("No organism selected to export");
+        return;
+    };
+    let (entity, organism) = *organism;
+    let Some((network, values)) = brains.get(entity) else {
+        info!("The selected organism has no brain yet");
+        return;
+    };
+    let diagram = network.diagram(
+        organism
+            .cells()
+            .iter()
+            .filter_map(|cell| cells.get(*cell).ok())
+            .map(|(cell, location)| (location.0, cell)),
+        Some(values),
+    );
+
+    let dir = PathBuf::from(DIAGRAM_DIR);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        error!("Could not create {}: {e}", dir.display());
+        return;
+    }
+    for (extension, text) in [("dot", diagram.to_dot()), ("svg", diagram.to_svg())] {
+        let path = dir.join(format!("brain-{}.{extension}", entity.index()));
+        match std::fs::write(&path, text) {
+            Ok(()) => info!("Exported brain to {}", path.display()),
+            Err(e) => error!("Could not export brain to {}: {e}", path.display()),
+        }
+    }
+}
+
+/// Dropping a saved organism onto the window spawns it at the origin.
+fn load_dropped_organisms(
+    mut drops: MessageReader<FileDragAndDrop>,
+    mut spawns: MessageWriter<SpawnOrganism>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        match Genome::load(path_buf) {
+            Ok(genome) => {
+                info!("Loaded organism from {}", path_buf.display());
+                spawns.write(SpawnOrganism::new(genome, Vec2::ZERO));
+            }
+            Err(e) => error!("Could not load {}: {e}", path_buf.display()),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use crate::{
+    cell::{CellLocation, Cells},
+    cpu_net::{Brains, Cell, CpuNetwork},
+    genome::BrainEdit,
+    organism::Organism,
+};
+
+/// Changes an organism's genome by hand, rebuilding its brain to match.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct EditBrain {
+    pub organism: Entity,
+    pub edit: BrainEdit,
+}
+
+/// Sent after an organism's brain is rebuilt from its genome.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct BrainRebuilt {
+    pub organism: Entity,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<EditBrain>().add_message::<BrainRebuilt>();
+    app.add_systems(Update, apply_brain_edits);
+}
+
+/// Applies every edit, then recompiles each edited organism's network once.
+///
+/// The new network starts from zero, so anything its data cells remembered is lost.
+pub fn apply_brain_edits(
+    mut edits: MessageReader<EditBrain>,
+    mut organisms: Query<(&mut Organism, &Cells)>,
+    mut cells: Query<(&mut Cell, &CellLocation)>,
+    mut brains: ResMut<Brains>,
+    mut rebuilt: MessageWriter<BrainRebuilt>,
+) {
+    let mut edited = HashSet::new();
+    for EditBrain { organism, edit } in edits.read() {
+        let Ok((mut target, _)) = organisms.get_mut(*organism) else {
+            continue;
+        };
+        if target.genome_mut().edit(*edit) {
+            edited.insert(*organism);
+        } else {
+            warn!("Could not apply {edit:?}");
+        }
+    }
+
+    for organism in edited {
+        let Ok((target, body)) = organisms.get(organism) else {
+            continue;
+        };
+        let (network, mut compiled) = CpuNetwork::new(target.genome());
+        for cell in body.cells() {
+            if let Ok((mut cell, location)) = cells.get_mut(*cell)
+                && let Some(compiled) = compiled.remove(&location.0)
+            {
+                *cell = compiled;
+            }
+        }
+        brains.insert(organism, network);
+        rebuilt.write(BrainRebuilt { organism });
+    }
+}
@@ -0,0 +1,316 @@
+use bevy::prelude::*;
+
+use crate::{
+    cell::Cells,
+    cpu_net::Cell,
+    game::grid::{Tile, WorldGrid},
+    organism::{Organism, OrganismSet, cell_position},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<EnergySettings>();
+
+    app.add_systems(
+        FixedUpdate,
+        (pay_upkeep, age_organisms, kill_organisms)
+            .chain()
+            .after(OrganismSet::ProcessOutput),
+    );
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct EnergySettings {
+    /// Energy a freshly spawned organism starts with
+    pub starting_energy: f32,
+    /// Energy gained from eating one food tile
+    pub food_energy: f32,
+    /// How much energy each cell of an organism lets it store
+    pub max_energy_per_cell: f32,
+    /// Seconds each cell adds to an organism's lifespan
+    pub lifespan_per_cell: f32,
+    /// Whether a dead organism's cells turn into food
+    pub leave_food_on_death: bool,
+}
+
+impl Default for EnergySettings {
+    fn default() -> Self {
+        Self {
+            starting_energy: 10.,
+            food_energy: 5.,
+            max_energy_per_cell: 10.,
+            lifespan_per_cell: 30.,
+            leave_food_on_death: true,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct Energy {
+    current: f32,
+    max: f32,
+}
+
+impl Energy {
+    pub fn new(current: f32, max: f32) -> Self {
+        Self {
+            current: current.min(max),
+            max,
+        }
+    }
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+    /// Adds energy, up to the maximum.
+    pub fn gain(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+    /// Removes energy, and returns false if there is none left.
+    pub fn spend(&mut self, amount: f32) -> bool {
+        self.current -= amount;
+        !self.is_depleted()
+    }
+    pub fn is_depleted(&self) -> bool {
+        self.current <= 0.
+    }
+}
+
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct Lifespan {
+    age: f32,
+    max: f32,
+}
+
+impl Lifespan {
+    pub fn new(max: f32) -> Self {
+        Self { age: 0., max }
+    }
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+    pub fn is_expired(&self) -> bool {
+        self.age >= self.max
+    }
+}
+
+/// The vitals an organism with `num_cells` cells spawns with.
+pub fn vitals_for(settings: &EnergySettings, num_cells: usize) -> (Energy, Lifespan) {
+    let num_cells = num_cells.max(1) as f32;
+    (
+        Energy::new(
+            settings.starting_energy,
+            settings.max_energy_per_cell * num_cells,
+        ),
+        Lifespan::new(settings.lifespan_per_cell * num_cells),
+    )
+}
+
+fn pay_upkeep(organisms: Query<(&mut Energy, &Cells)>, cells: Query<&Cell>, time: Res<Time>) {
+    let delta = time.delta_secs();
+    for (mut energy, organism_cells) in organisms {
+        let upkeep: f32 = cells
+            .iter_many(organism_cells.cells())
+            .map(|cell| cell.kind().upkeep())
+            .sum();
+        energy.spend(upkeep * delta);
+    }
+}
+
+fn age_organisms(organisms: Query<&mut Lifespan>, time: Res<Time>) {
+    let delta = time.delta_secs();
+    for mut lifespan in organisms {
+        lifespan.age += delta;
+    }
+}
+
+fn kill_organisms(
+    mut commands: Commands,
+    mut grid: ResMut<WorldGrid>,
+    settings: Res<EnergySettings>,
+    organisms: Query<(Entity, &Energy, &Lifespan, &Transform, Option<&Cells>), With<Organism>>,
+    cells: Query<&Transform, Without<Organism>>,
+) {
+    for (organism, energy, lifespan, organism_transform, organism_cells) in organisms {
+        let has_cells = organism_cells.is_some_and(|cells| !cells.cells().is_empty());
+        if has_cells && !energy.is_depleted() && !lifespan.is_expired() {
+            continue;
+        }
+        if settings.leave_food_on_death
+            && let Some(organism_cells) = organism_cells
+        {
+            for transform in cells.iter_many(organism_cells.cells()) {
+                let pos = grid.world_to_tile(cell_position(organism_transform, transform));
+                if grid.tile(pos) == Tile::Empty {
+                    grid.set_tile(pos, Tile::Food);
+                }
+            }
+        }
+        commands.entity(organism).despawn();
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::{
+        cell::{CellKind, CellOf},
+        cpu_net::CpuNetwork,
+        genome::Genome,
+        organism::{SpeciesRegistry, SpeciesSettings},
+    },
+    bevy::ecs::system::RunSystemOnce,
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+    std::time::Duration,
+};
+
+/// A world with an empty grid, where `seconds` just went by
+#[cfg(test)]
+fn world_after(seconds: u64, settings: EnergySettings) -> World {
+    let mut world = World::new();
+    let mut time = Time::<()>::default();
+    time.advance_by(Duration::from_secs(seconds));
+    world.insert_resource(time);
+    world.insert_resource(WorldGrid::new(20, 20));
+    world.insert_resource(settings);
+    world
+}
+
+/// An organism at `location` with a cell at each of `cells`, relative to it
+#[cfg(test)]
+fn spawn_organism(
+    world: &mut World,
+    location: Vec2,
+    energy: Energy,
+    lifespan: Lifespan,
+    cells: &[Vec2],
+) -> Entity {
+    let genome = Genome::empty();
+    let species = SpeciesRegistry::default().join(&genome, &SpeciesSettings::default());
+    let organism = world
+        .spawn((
+            Organism::new(genome, species),
+            Transform::from_translation(location.extend(0.)),
+            energy,
+            lifespan,
+        ))
+        .id();
+    for cell in cells {
+        world.spawn((
+            CellOf(organism),
+            Transform::from_translation(cell.extend(0.)),
+        ));
+    }
+    organism
+}
+
+#[test]
+fn test_energy_gain_is_capped() {
+    let mut energy = Energy::new(5., 8.);
+    energy.gain(10.);
+    assert_eq!(energy.current(), 8.);
+}
+
+#[test]
+fn test_energy_spend_depletes() {
+    let mut energy = Energy::new(1., 8.);
+    assert!(energy.spend(0.5));
+    assert!(!energy.spend(0.5));
+    assert!(energy.is_depleted());
+}
+
+#[test]
+fn test_vitals_scale_with_cells() {
+    let settings = EnergySettings::default();
+    let (energy, lifespan) = vitals_for(&settings, 3);
+    assert_eq!(energy.max(), settings.max_energy_per_cell * 3.);
+    assert_eq!(lifespan.max(), settings.lifespan_per_cell * 3.);
+    assert!(!lifespan.is_expired());
+}
+
+#[test]
+fn test_upkeep_is_the_sum_of_cell_costs() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let genome = Genome::from_cells(
+        vec![(CellKind::Eye, IVec2::ZERO), (CellKind::Foot, IVec2::X)],
+        &mut rng,
+    );
+    let (_, cells) = CpuNetwork::new(&genome);
+
+    let mut world = world_after(2, EnergySettings::default());
+    let organism = world.spawn(Energy::new(10., 10.)).id();
+    for cell in cells.into_values() {
+        world.spawn((cell, CellOf(organism)));
+    }
+
+    world.run_system_once(pay_upkeep).unwrap();
+
+    let upkeep = CellKind::Eye.upkeep() + CellKind::Foot.upkeep();
+    assert_eq!(
+        world.get::<Energy>(organism).unwrap().current(),
+        10. - upkeep * 2.
+    );
+}
+
+#[test]
+fn test_starving_organisms_die_and_turn_into_food() {
+    let mut world = world_after(0, EnergySettings::default());
+    let cells = [Vec2::ZERO, Vec2::new(1., 0.)];
+    let starving = spawn_organism(
+        &mut world,
+        Vec2::new(3., 3.),
+        Energy::new(0., 10.),
+        Lifespan::new(100.),
+        &cells,
+    );
+    let fed = spawn_organism(
+        &mut world,
+        Vec2::new(-3., -3.),
+        Energy::new(5., 10.),
+        Lifespan::new(100.),
+        &cells,
+    );
+
+    world.run_system_once(kill_organisms).unwrap();
+
+    assert!(world.get_entity(starving).is_err());
+    assert!(world.get_entity(fed).is_ok());
+    let grid = world.resource::<WorldGrid>();
+    assert_eq!(grid.tile(IVec2::new(3, 3)), Tile::Food);
+    assert_eq!(grid.tile(IVec2::new(4, 3)), Tile::Food);
+    assert_eq!(grid.food_count(), 2);
+}
+
+#[test]
+fn test_old_age_kills() {
+    let settings = EnergySettings {
+        leave_food_on_death: false,
+        ..default()
+    };
+    let mut world = world_after(1, settings);
+    let old = spawn_organism(
+        &mut world,
+        Vec2::ZERO,
+        Energy::new(10., 10.),
+        Lifespan::new(1.),
+        &[Vec2::ZERO],
+    );
+    let young = spawn_organism(
+        &mut world,
+        Vec2::new(5., 5.),
+        Energy::new(10., 10.),
+        Lifespan::new(2.),
+        &[Vec2::ZERO],
+    );
+
+    world.run_system_once(age_organisms).unwrap();
+    world.run_system_once(kill_organisms).unwrap();
+
+    assert!(world.get_entity(old).is_err());
+    assert!(world.get_entity(young).is_ok());
+    assert_eq!(world.resource::<WorldGrid>().food_count(), 0);
+}
@@ -3,8 +3,25 @@ pub use spawn::*;
 
 mod ui;
 
+mod archive;
+
+mod energy;
+pub use energy::*;
+
+mod reproduction;
+pub use reproduction::*;
+
+mod physics;
+pub use physics::*;
+
+mod species;
+pub use species::*;
+
+mod editing;
+pub use editing::*;
+
 use crate::{
-    cpu_net::Cell,
+    cpu_net::Brains,
     genome::Genome, //old_genome::Genome,
 };
 use bevy::prelude::*;
@@ -21,25 +38,69 @@ pub struct ActiveOrganism;
 #[derive(Component)]
 pub struct Organism {
     genome: Genome,
+    species: SpeciesId,
 }
 impl Organism {
-    pub fn new(genome: Genome) -> Self {
-        Self { genome }
+    pub fn new(genome: Genome, species: SpeciesId) -> Self {
+        Self { genome, species }
+    }
+    pub fn genome(&self) -> &Genome {
+        &self.genome
+    }
+    /// The brain keeps running the old genome until it's rebuilt, see [`EditBrain`].
+    pub fn genome_mut(&mut self) -> &mut Genome {
+        &mut self.genome
+    }
+    pub fn species(&self) -> SpeciesId {
+        self.species
     }
 }
 
 pub fn plugin(app: &mut App) {
     app.configure_sets(
-        Update,
+        FixedUpdate,
         (OrganismSet::ProcessInput, OrganismSet::ProcessOutput).chain(),
     );
 
-    app.add_plugins((spawn::plugin, ui::plugin));
-    app.add_systems(PostUpdate, reset_cells);
+    app.add_plugins((
+        spawn::plugin,
+        ui::plugin,
+        archive::plugin,
+        energy::plugin,
+        reproduction::plugin,
+        physics::plugin,
+        species::plugin,
+        editing::plugin,
+    ));
+    app.init_resource::<Brains>();
+    app.add_observer(forget_brain);
+    app.add_systems(
+        FixedUpdate,
+        (
+            prepare_brains.before(OrganismSet::ProcessInput),
+            process_brains
+                .after(OrganismSet::ProcessInput)
+                .before(OrganismSet::ProcessOutput),
+        ),
+    );
+}
+
+/// Where a cell is in the world.
+///
+/// Simulation systems use this instead of [`GlobalTransform`], which only catches up once a frame.
+pub fn cell_position(organism: &Transform, cell: &Transform) -> Vec2 {
+    organism.transform_point(cell.translation).xy()
+}
+
+fn forget_brain(ev: On<Remove, Organism>, mut brains: ResMut<Brains>) {
+    brains.remove(ev.entity);
+}
+
+fn prepare_brains(mut brains: ResMut<Brains>) {
+    brains.compact();
+    brains.clear_inputs();
 }
 
-fn reset_cells(cells: Query<&Cell>) {
-    cells.par_iter().for_each(|cell| {
-        cell.reset();
-    });
+fn process_brains(mut brains: ResMut<Brains>) {
+    brains.process();
 }
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use crate::{
+    cell::{CellOf, Cells},
+    game::grid::WorldGrid,
+    organism::{Organism, OrganismSet},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PhysicsSettings>();
+
+    app.add_systems(
+        FixedUpdate,
+        move_organisms.after(OrganismSet::ProcessOutput),
+    );
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct PhysicsSettings {
+    /// Force a foot pushes with at full output
+    pub foot_force: f32,
+    /// Top speed of a single-cell organism, in tiles per second. Heavier organisms are slower.
+    pub max_speed: f32,
+    /// Top rotation speed of a single-cell organism, in radians per second
+    pub max_angular_speed: f32,
+    /// Fraction of velocity lost every second
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            foot_force: 4.,
+            max_speed: 4.,
+            max_angular_speed: std::f32::consts::PI,
+            linear_damping: 2.,
+            angular_damping: 3.,
+        }
+    }
+}
+
+/// Force and torque gathered from an organism's feet this tick.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+pub struct Thrust {
+    pub force: Vec2,
+    pub torque: f32,
+}
+
+impl Thrust {
+    /// Pushes with `force` at `lever`, the offset from the organism's center.
+    pub fn push_at(&mut self, force: Vec2, lever: Vec2) {
+        self.force += force;
+        self.torque += lever.perp_dot(force);
+    }
+}
+
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+}
+
+impl Body {
+    /// Applies `thrust` to a body made of cells at `offsets` from its center.
+    pub fn accelerate(
+        &mut self,
+        thrust: Thrust,
+        offsets: &[Vec2],
+        settings: &PhysicsSettings,
+        delta: f32,
+    ) {
+        let mass = offsets.len().max(1) as f32;
+        // each cell is a unit square, which alone has an inertia of 1/6
+        let inertia: f32 = offsets
+            .iter()
+            .map(|offset| offset.length_squared() + 1. / 6.)
+            .sum::<f32>()
+            .max(1. / 6.);
+
+        self.velocity += thrust.force / mass * delta;
+        self.angular_velocity += thrust.torque / inertia * delta;
+
+        self.velocity *= (-settings.linear_damping * delta).exp();
+        self.angular_velocity *= (-settings.angular_damping * delta).exp();
+
+        self.velocity = self.velocity.clamp_length_max(settings.max_speed / mass);
+        let max_angular = settings.max_angular_speed / mass;
+        self.angular_velocity = self.angular_velocity.clamp(-max_angular, max_angular);
+    }
+}
+
+fn move_organisms(
+    organisms: Query<(Entity, &mut Transform, &mut Body, &mut Thrust, &Cells), With<Organism>>,
+    cells: Query<&Transform, (With<CellOf>, Without<Organism>)>,
+    owners: Query<&CellOf>,
+    grid: Res<WorldGrid>,
+    settings: Res<PhysicsSettings>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (organism, mut transform, mut body, mut thrust, organism_cells) in organisms {
+        let offsets: Vec<Vec2> = cells
+            .iter_many(organism_cells.cells())
+            .map(|cell| cell.translation.xy())
+            .collect();
+
+        let applied = std::mem::take(&mut *thrust);
+        body.accelerate(applied, &offsets, &settings, delta);
+
+        let blocked = |translation: Vec2, rotation: Quat| {
+            offsets.iter().any(|offset| {
+                let world = translation + (rotation * offset.extend(0.)).xy();
+                let pos = grid.world_to_tile(world);
+                !grid.tile(pos).is_passable()
+                    || grid
+                        .occupant(pos)
+                        .is_some_and(|cell| owners.get(cell).is_ok_and(|of| of.0 != organism))
+            })
+        };
+
+        let translation = transform.translation.xy() + body.velocity * delta;
+        let rotation = transform.rotation * Quat::from_rotation_z(body.angular_velocity * delta);
+
+        if !blocked(translation, rotation) {
+            transform.translation.x = translation.x;
+            transform.translation.y = translation.y;
+            transform.rotation = rotation;
+        } else if !blocked(translation, transform.rotation) {
+            body.angular_velocity = 0.;
+            transform.translation.x = translation.x;
+            transform.translation.y = translation.y;
+        } else if !blocked(transform.translation.xy(), rotation) {
+            body.velocity = Vec2::ZERO;
+            transform.rotation = rotation;
+        } else {
+            *body = Body::default();
+        }
+    }
+}
+
+#[cfg(test)]
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_off_center_thrust_turns() {
+    let mut thrust = Thrust::default();
+    // a foot to the right pushing up turns counter clockwise
+    thrust.push_at(Vec2::Y, Vec2::X);
+    assert_eq!(thrust.torque, 1.);
+
+    // feet on both sides pushing up cancel out
+    thrust.push_at(Vec2::Y, Vec2::NEG_X);
+    assert_eq!(thrust.torque, 0.);
+    assert_eq!(thrust.force, Vec2::new(0., 2.));
+}
+
+#[test]
+fn test_heavier_bodies_are_slower() {
+    let settings = PhysicsSettings::default();
+    let thrust = Thrust {
+        force: Vec2::X * 1000.,
+        torque: 0.,
+    };
+
+    let mut light = Body::default();
+    light.accelerate(thrust, &[Vec2::ZERO], &settings, 1.);
+    let mut heavy = Body::default();
+    heavy.accelerate(thrust, &[Vec2::ZERO, Vec2::X, Vec2::Y], &settings, 1.);
+
+    assert!((light.velocity.length() - settings.max_speed).abs() < 0.001);
+    assert!((heavy.velocity.length() - settings.max_speed / 3.).abs() < 0.001);
+}
+
+#[test]
+fn test_body_comes_to_rest() {
+    let settings = PhysicsSettings::default();
+    let mut body = Body {
+        velocity: Vec2::X,
+        angular_velocity: 1.,
+    };
+    for _ in 0..100 {
+        body.accelerate(Thrust::default(), &[Vec2::ZERO], &settings, 0.1);
+    }
+    assert!(body.velocity.length() < 0.001);
+    assert!(body.angular_velocity.abs() < 0.001);
+}
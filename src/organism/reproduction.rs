@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    game::{WorldRng, grid::WorldGrid},
+    genome::{CellMap, Contiguity, Direction, Genome},
+    organism::{Energy, EnergySettings, Organism, OrganismSet, SpawnOrganism},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReproductionSettings>();
+
+    app.add_systems(FixedUpdate, reproduce.after(OrganismSet::ProcessOutput));
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct ReproductionSettings {
+    /// Fraction of its maximum energy an organism needs before it reproduces
+    pub energy_threshold: f32,
+    /// Energy spent on reproducing, on top of what the child starts with
+    pub energy_cost: f32,
+    /// What happens to bodies in separate pieces, whether a child mutated that way, an
+    /// organism was spawned that way, or it lost the cells holding it together
+    pub contiguity: Contiguity,
+}
+
+impl Default for ReproductionSettings {
+    fn default() -> Self {
+        Self {
+            energy_threshold: 0.8,
+            energy_cost: 2.,
+            contiguity: Contiguity::default(),
+        }
+    }
+}
+
+/// Where an organism came from.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct Lineage {
+    /// May no longer exist
+    pub parent: Option<Entity>,
+    pub generation: u32,
+}
+
+fn reproduce(
+    mut organisms: Query<(Entity, &Organism, &mut Energy, &Transform)>,
+    grid: Res<WorldGrid>,
+    settings: Res<ReproductionSettings>,
+    energy_settings: Res<EnergySettings>,
+    mut spawns: MessageWriter<SpawnOrganism>,
+    mut rng: ResMut<WorldRng>,
+) {
+    let cost = energy_settings.starting_energy + settings.energy_cost;
+
+    for (entity, organism, mut energy, transform) in &mut organisms {
+        if energy.current() < energy.max() * settings.energy_threshold || energy.current() <= cost {
+            continue;
+        }
+
+        let mut child = organism.genome().clone();
+        child.scramble_with(settings.contiguity, &mut **rng);
+        if child.cells().is_empty() {
+            continue;
+        }
+
+        let origin = grid.world_to_tile(transform.translation.xy());
+        let Some(spot) = find_offspring_spot(&grid, origin, organism.genome(), &child, &mut **rng)
+        else {
+            continue;
+        };
+
+        let children = match settings.contiguity {
+            Contiguity::Split => child.fragments(),
+            Contiguity::Ignore | Contiguity::Reject => vec![child],
+        };
+        // every piece is a child to pay for, and they're born together or not at all
+        let total = cost * children.len() as f32;
+        if energy.current() <= total {
+            continue;
+        }
+        energy.spend(total);
+        // the pieces keep their cells where they were, so they fit wherever the whole child does
+        for child in children {
+            spawns.write(SpawnOrganism::offspring(
+                child,
+                grid.tile_to_world(spot),
+                entity,
+            ));
+        }
+    }
+}
+
+/// Finds an origin next to the parent where every cell of the child lands on a free tile.
+pub fn find_offspring_spot(
+    grid: &WorldGrid,
+    parent_origin: IVec2,
+    parent: &Genome,
+    child: &Genome,
+    rng: &mut impl Rng,
+) -> Option<IVec2> {
+    let distance = reach(parent.cells()) + reach(child.cells()) + 1;
+
+    Direction::random_order(rng)
+        .into_iter()
+        .map(|direction| parent_origin + direction.vec() * distance)
+        .find(|origin| {
+            child
+                .cells()
+                .map()
+                .keys()
+                .all(|location| grid.is_free(*origin + *location))
+        })
+}
+
+/// How far the furthest cell sits from the organism's origin.
+fn reach(cells: &CellMap) -> i32 {
+    cells
+        .map()
+        .keys()
+        .map(|location| location.x.abs().max(location.y.abs()))
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+use {
+    crate::{cell::CellKind, game::grid::Tile},
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_offspring_spot_clears_parent() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let grid = WorldGrid::walled(40, 40);
+    let genome = Genome::from_cells(
+        vec![
+            (CellKind::Mouth, IVec2::new(0, 0)),
+            (CellKind::Eye, IVec2::new(2, -1)),
+        ],
+        &mut rng,
+    );
+
+    let spot = find_offspring_spot(&grid, IVec2::ZERO, &genome, &genome, &mut rng).unwrap();
+    assert_eq!(spot.x.abs().max(spot.y.abs()), 5);
+}
+
+#[test]
+fn test_offspring_spot_avoids_walls() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut grid = WorldGrid::walled(40, 40);
+    for direction in [IVec2::X, IVec2::NEG_X, IVec2::Y] {
+        grid.set_tile(direction, Tile::Wall);
+    }
+    let genome = Genome::from_cells(vec![(CellKind::Mouth, IVec2::ZERO)], &mut rng);
+
+    for _ in 0..10 {
+        let spot = find_offspring_spot(&grid, IVec2::ZERO, &genome, &genome, &mut rng);
+        assert_eq!(spot, Some(IVec2::NEG_Y));
+    }
+}
+
+#[test]
+fn test_offspring_spot_none_when_boxed_in() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let grid = WorldGrid::walled(3, 3);
+    let genome = Genome::from_cells(vec![(CellKind::Mouth, IVec2::ZERO)], &mut rng);
+
+    assert_eq!(
+        find_offspring_spot(&grid, IVec2::ZERO, &genome, &genome, &mut rng),
+        None
+    );
+}
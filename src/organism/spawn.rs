@@ -1,89 +1,298 @@
 use bevy::prelude::*;
 
 use crate::{
-    cell::{CellAssets, CellKind, CellOf, DataCell, Eye, Foot, Launcher},
-    cpu_net::CpuNetwork,
-    genome::Genome,
-    organism::Organism,
+    cell::{
+        CellAssets, CellHealth, CellKind, CellLocation, CellOf, Cells, DataCell, Eye, Foot,
+        Launcher, Mouth,
+    },
+    cpu_net::{Brains, CpuNetwork},
+    genome::{Contiguity, Genome, components_of},
+    organism::{
+        Body, Energy, EnergySettings, Lineage, Organism, OrganismSet, ReproductionSettings,
+        SpeciesRegistry, SpeciesSettings, Thrust, vitals_for,
+    },
 };
 
 #[derive(Message)]
 pub struct SpawnOrganism {
     genome: Genome,
     location: Vec2,
+    parent: Option<Entity>,
+    piece: Option<Piece>,
 }
+
+/// What a piece of a split organism carries on with.
+struct Piece {
+    rotation: Quat,
+    energy: f32,
+    lineage: Lineage,
+}
+
 impl SpawnOrganism {
-    /// The receiving system will create offspring from this genome.
+    /// Spawns an organism with exactly this genome.
     pub fn new(genome: Genome, location: Vec2) -> Self {
-        Self { genome, location }
+        Self {
+            genome,
+            location,
+            parent: None,
+            piece: None,
+        }
+    }
+    /// Spawns a child of `parent`. The genome should already be mutated.
+    pub fn offspring(genome: Genome, location: Vec2, parent: Entity) -> Self {
+        Self {
+            genome,
+            location,
+            parent: Some(parent),
+            piece: None,
+        }
+    }
+    /// Spawns one piece of an organism that came apart, where the organism was.
+    ///
+    /// The piece takes over the organism's lineage, and `energy` of what it had left.
+    pub fn piece(genome: Genome, organism: &Transform, energy: f32, lineage: Lineage) -> Self {
+        Self {
+            genome,
+            location: organism.translation.xy(),
+            parent: lineage.parent,
+            piece: Some(Piece {
+                rotation: organism.rotation,
+                energy,
+                lineage,
+            }),
+        }
     }
 }
 
 pub(super) fn plugin(app: &mut App) {
     app.add_message::<SpawnOrganism>();
-    app.add_systems(Update, spawn_genomes);
+    app.add_systems(FixedUpdate, spawn_genomes);
+    app.add_systems(
+        FixedUpdate,
+        split_broken_bodies.after(OrganismSet::ProcessOutput),
+    );
 }
 
 fn spawn_genomes(
     mut msgs: MessageReader<SpawnOrganism>,
     mut commands: Commands,
     assets: Res<CellAssets>,
+    energy_settings: Res<EnergySettings>,
+    lineages: Query<&Lineage>,
+    mut brains: ResMut<Brains>,
+    species_settings: Res<SpeciesSettings>,
+    mut species: ResMut<SpeciesRegistry>,
+    reproduction: Res<ReproductionSettings>,
 ) {
     for msg in msgs.read() {
-        let organism = commands
-            .spawn((
-                Name::new("Organism"),
-                Organism::new(msg.genome.clone()),
-                InheritedVisibility::VISIBLE,
-                Pickable::default(),
-                Transform::from_xyz(msg.location.x, msg.location.y, 0.),
-            ))
-            .observe(super::ui::set_active)
-            .id();
-
-        let cpu_net = CpuNetwork::new(&msg.genome);
-
-        for (location, cell) in cpu_net.cells {
-            let kind = cell.kind();
-            let mut commands = commands.spawn((
-                cell,
-                ChildOf(organism),
-                CellOf(organism),
-                Pickable::default(),
-                Transform::from_xyz(location.x as f32, location.y as f32, 0.),
-                Mesh2d(assets.cell.clone()),
-            ));
+        // bodies already in pieces, like saved ones, come apart as they're spawned
+        let genomes = match reproduction.contiguity {
+            Contiguity::Split => msg.genome.fragments(),
+            Contiguity::Ignore | Contiguity::Reject => vec![msg.genome.clone()],
+        };
+        for genome in genomes {
+            spawn_genome(
+                commands.reborrow(),
+                msg,
+                genome,
+                &assets,
+                &energy_settings,
+                &lineages,
+                &mut brains,
+                &species_settings,
+                &mut species,
+            );
+        }
+    }
+}
+
+fn spawn_genome(
+    mut commands: Commands,
+    msg: &SpawnOrganism,
+    genome: Genome,
+    assets: &CellAssets,
+    energy_settings: &EnergySettings,
+    lineages: &Query<&Lineage>,
+    brains: &mut Brains,
+    species_settings: &SpeciesSettings,
+    species: &mut SpeciesRegistry,
+) {
+    let (mut energy, lifespan) = vitals_for(energy_settings, genome.cells().len());
+    let mut lineage = Lineage {
+        parent: msg.parent,
+        generation: msg
+            .parent
+            .and_then(|parent| lineages.get(parent).ok())
+            .map(|lineage| lineage.generation + 1)
+            .unwrap_or(0),
+    };
+    let mut transform = Transform::from_xyz(msg.location.x, msg.location.y, 0.);
+    if let Some(piece) = &msg.piece {
+        energy = Energy::new(piece.energy, energy.max());
+        lineage = piece.lineage;
+        transform.rotation = piece.rotation;
+    }
+
+    let (network, cells) = CpuNetwork::new(&genome);
+    let species = species.join(&genome, species_settings);
+    let organism = commands
+        .spawn((
+            Name::new("Organism"),
+            Organism::new(genome, species),
+            energy,
+            lifespan,
+            lineage,
+            Body::default(),
+            Thrust::default(),
+            InheritedVisibility::VISIBLE,
+            Pickable::default(),
+            transform,
+        ))
+        .observe(super::ui::set_active)
+        .id();
+    brains.insert(organism, network);
+
+    for (location, cell) in cells {
+        let kind = cell.kind();
+        let mut commands = commands.spawn((
+            cell,
+            ChildOf(organism),
+            CellOf(organism),
+            CellLocation(location),
+            CellHealth::default(),
+            Pickable::default(),
+            Transform::from_xyz(location.x as f32, location.y as f32, 0.),
+            Mesh2d(assets.cell.clone()),
+        ));
 
-            match kind {
-                CellKind::Foot => {
-                    commands.insert((
-                        Name::new("Collagen"),
-                        Foot::default(),
-                        MeshMaterial2d(assets.white.clone()),
-                    ));
-                }
-                CellKind::Data => {
-                    commands.insert((
-                        Name::new("Data Cell"),
-                        DataCell::default(),
-                        MeshMaterial2d(assets.yellow.clone()),
-                    ));
-                }
-                CellKind::Launcher => {
-                    commands.insert((
-                        Name::new("Launcher Cell"),
-                        Launcher::default(),
-                        MeshMaterial2d(assets.red.clone()),
-                    ));
-                }
-                CellKind::Eye => {
-                    commands.insert((
-                        Name::new("Eye Cell"),
-                        Eye::default(),
-                        MeshMaterial2d(assets.sky.clone()),
-                    ));
-                }
+        match kind {
+            CellKind::Foot => {
+                commands.insert((
+                    Name::new("Collagen"),
+                    Foot::default(),
+                    MeshMaterial2d(assets.white.clone()),
+                ));
+            }
+            CellKind::Data => {
+                commands.insert((
+                    Name::new("Data Cell"),
+                    DataCell::default(),
+                    MeshMaterial2d(assets.yellow.clone()),
+                ));
+            }
+            CellKind::Launcher => {
+                commands.insert((
+                    Name::new("Launcher Cell"),
+                    Launcher::default(),
+                    MeshMaterial2d(assets.red.clone()),
+                ));
+            }
+            CellKind::Eye => {
+                commands.insert((
+                    Name::new("Eye Cell"),
+                    Eye::default(),
+                    MeshMaterial2d(assets.sky.clone()),
+                ));
+            }
+            CellKind::Mouth => {
+                commands.insert((
+                    Name::new("Mouth Cell"),
+                    Mouth::default(),
+                    MeshMaterial2d(assets.pink.clone()),
+                ));
             }
         }
     }
 }
+
+/// Splits organisms whose surviving cells no longer touch, when bodies are set to split.
+///
+/// Each piece keeps the part of the brain that still reaches it, and a share of the
+/// organism's energy by how many cells it has.
+fn split_broken_bodies(
+    mut commands: Commands,
+    organisms: Query<(Entity, &Organism, &Cells, &Transform, &Energy, &Lineage), Changed<Cells>>,
+    cells: Query<&CellLocation>,
+    settings: Res<ReproductionSettings>,
+    mut spawns: MessageWriter<SpawnOrganism>,
+) {
+    if settings.contiguity != Contiguity::Split {
+        return;
+    }
+    for (entity, organism, organism_cells, transform, energy, lineage) in organisms {
+        let alive: Vec<IVec2> = cells
+            .iter_many(organism_cells.cells())
+            .map(|location| location.0)
+            .collect();
+        if components_of(alive.iter().copied()).len() <= 1 {
+            continue;
+        }
+
+        let mut genome = organism.genome().clone();
+        genome.retain_cells(|location| alive.contains(&location));
+        commands.entity(entity).despawn();
+        for piece in genome.fragments() {
+            let share = piece.cells().len() as f32 / alive.len() as f32;
+            spawns.write(SpawnOrganism::piece(
+                piece,
+                transform,
+                energy.current() * share,
+                *lineage,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+use {
+    bevy::ecs::{message::Messages, system::RunSystemOnce},
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_bodies_split_when_the_middle_dies() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let genome = Genome::from_cells(
+        (0..3).map(|x| (CellKind::Foot, IVec2::new(x, 0))).collect(),
+        &mut rng,
+    );
+    let species = SpeciesRegistry::default().join(&genome, &SpeciesSettings::default());
+
+    let mut world = World::new();
+    world.insert_resource(ReproductionSettings {
+        contiguity: Contiguity::Split,
+        ..default()
+    });
+    world.init_resource::<Messages<SpawnOrganism>>();
+    let lineage = Lineage {
+        parent: None,
+        generation: 3,
+    };
+    let organism = world
+        .spawn((
+            Organism::new(genome, species),
+            Transform::default(),
+            Energy::new(30., 100.),
+            lineage,
+        ))
+        .id();
+    // the middle cell has already died
+    for x in [0, 2] {
+        world.spawn((CellOf(organism), CellLocation(IVec2::new(x, 0))));
+    }
+
+    world.run_system_once(split_broken_bodies).unwrap();
+
+    assert!(world.get_entity(organism).is_err());
+    let pieces: Vec<_> = world
+        .resource_mut::<Messages<SpawnOrganism>>()
+        .drain()
+        .collect();
+    assert_eq!(pieces.len(), 2);
+    for spawn in pieces {
+        assert_eq!(spawn.genome.cells().len(), 1);
+        let piece = spawn.piece.unwrap();
+        assert_eq!(piece.energy, 15.);
+        assert_eq!(piece.lineage, lineage);
+    }
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::{
+    genome::{Compatibility, Genome},
+    organism::Organism,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SpeciesSettings>();
+    app.init_resource::<SpeciesRegistry>();
+    app.add_observer(leave_species);
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct SpeciesSettings {
+    /// Largest [`Genome::distance`] from a species' representative that still joins it
+    pub threshold: f32,
+    pub compatibility: Compatibility,
+}
+
+impl Default for SpeciesSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 1.,
+            compatibility: Compatibility::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Reflect)]
+pub struct SpeciesId(u32);
+
+impl fmt::Display for SpeciesId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Species {}", self.0)
+    }
+}
+
+pub struct Species {
+    id: SpeciesId,
+    /// A member's genome, which newcomers are compared against. Starts as the founder's,
+    /// and moves on to a living member whenever one leaves.
+    representative: Genome,
+    members: usize,
+}
+
+impl Species {
+    pub fn id(&self) -> SpeciesId {
+        self.id
+    }
+    pub fn representative(&self) -> &Genome {
+        &self.representative
+    }
+    /// How many living organisms belong to this species
+    pub fn members(&self) -> usize {
+        self.members
+    }
+}
+
+/// Every species with living members, oldest first.
+#[derive(Resource, Default)]
+pub struct SpeciesRegistry {
+    species: Vec<Species>,
+    next_id: u32,
+}
+
+impl SpeciesRegistry {
+    /// Adds a member to the first species `genome` is compatible with, founding a new one if
+    /// there is none.
+    pub fn join(&mut self, genome: &Genome, settings: &SpeciesSettings) -> SpeciesId {
+        let existing = self.species.iter_mut().find(|species| {
+            genome.distance(&species.representative, &settings.compatibility) <= settings.threshold
+        });
+        if let Some(species) = existing {
+            species.members += 1;
+            return species.id;
+        }
+
+        let id = SpeciesId(self.next_id);
+        self.next_id += 1;
+        info!("{id} founded");
+        self.species.push(Species {
+            id,
+            representative: genome.clone(),
+            members: 1,
+        });
+        id
+    }
+
+    /// Removes a member, dropping the species once nobody is left.
+    pub fn leave(&mut self, id: SpeciesId) {
+        let Some(index) = self.species.iter().position(|species| species.id == id) else {
+            return;
+        };
+        let species = &mut self.species[index];
+        species.members = species.members.saturating_sub(1);
+        if species.members == 0 {
+            info!("{id} went extinct");
+            self.species.remove(index);
+        }
+    }
+
+    /// Compares newcomers to `genome` from now on, if the species is still around.
+    pub fn set_representative(&mut self, id: SpeciesId, genome: &Genome) {
+        if let Some(species) = self.species.iter_mut().find(|species| species.id == id) {
+            species.representative = genome.clone();
+        }
+    }
+
+    pub fn get(&self, id: SpeciesId) -> Option<&Species> {
+        self.species.iter().find(|species| species.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Species> {
+        self.species.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.species.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.species.is_empty()
+    }
+}
+
+/// Leaves the species, handing its representative to a member that's still alive, so it
+/// doesn't drift from the species it stands for.
+fn leave_species(
+    ev: On<Remove, Organism>,
+    organisms: Query<(Entity, &Organism)>,
+    mut registry: ResMut<SpeciesRegistry>,
+) {
+    let Ok((_, organism)) = organisms.get(ev.entity) else {
+        return;
+    };
+    let id = organism.species();
+    registry.leave(id);
+    let successor = organisms
+        .iter()
+        .find(|(entity, other)| *entity != ev.entity && other.species() == id);
+    if let Some((_, successor)) = successor {
+        registry.set_representative(id, successor.genome());
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::cell::CellKind,
+    pretty_assertions::assert_eq,
+    rand::{SeedableRng, rngs::StdRng},
+};
+
+#[test]
+fn test_relatives_share_a_species() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let settings = SpeciesSettings::default();
+    let mut registry = SpeciesRegistry::default();
+    let genome = Genome::sandbox(&mut rng);
+
+    let parent = registry.join(&genome, &settings);
+    let mut child = genome.clone();
+    for connection in child.neurons.connections_mut() {
+        connection.weight += 0.1;
+    }
+    let child = registry.join(&child, &settings);
+
+    assert_eq!(parent, child);
+    assert_eq!(registry.len(), 1);
+    assert_eq!(registry.get(parent).unwrap().members(), 2);
+}
+
+#[test]
+fn test_distant_genomes_found_species() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let settings = SpeciesSettings::default();
+    let mut registry = SpeciesRegistry::default();
+    let genome = Genome::sandbox(&mut rng);
+
+    let first = registry.join(&genome, &settings);
+    // a completely different body plan
+    let other = Genome::from_cells(
+        vec![
+            (CellKind::Foot, IVec2::new(5, 5)),
+            (CellKind::Foot, IVec2::new(6, 5)),
+        ],
+        &mut rng,
+    );
+    let second = registry.join(&other, &settings);
+
+    assert_ne!(first, second);
+    assert_eq!(registry.len(), 2);
+    assert_eq!(
+        registry.iter().map(Species::id).collect::<Vec<_>>(),
+        vec![first, second]
+    );
+}
+
+#[test]
+fn test_species_go_extinct_with_their_last_member() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let settings = SpeciesSettings::default();
+    let mut registry = SpeciesRegistry::default();
+    let genome = Genome::sandbox(&mut rng);
+
+    let id = registry.join(&genome, &settings);
+    registry.join(&genome, &settings);
+    registry.leave(id);
+    assert_eq!(registry.get(id).unwrap().members(), 1);
+
+    registry.leave(id);
+    assert!(registry.get(id).is_none());
+    assert!(registry.is_empty());
+
+    // ids aren't reused, even once a species is gone
+    assert_ne!(registry.join(&genome, &settings), id);
+}
+
+#[test]
+fn test_representative_moves_to_a_living_member() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let settings = SpeciesSettings::default();
+    let mut registry = SpeciesRegistry::default();
+    let founder = Genome::sandbox(&mut rng);
+    let mut child = founder.clone();
+    for connection in child.neurons.connections_mut() {
+        connection.weight += 0.1;
+    }
+    let id = registry.join(&founder, &settings);
+    assert_eq!(registry.join(&child, &settings), id);
+
+    let mut world = World::new();
+    world.insert_resource(registry);
+    world.add_observer(leave_species);
+    let first = world.spawn(Organism::new(founder, id)).id();
+    world.spawn(Organism::new(child.clone(), id));
+    world.despawn(first);
+
+    let registry = world.resource::<SpeciesRegistry>();
+    let species = registry.get(id).unwrap();
+    assert_eq!(species.members(), 1);
+    let weights = |genome: &Genome| {
+        genome
+            .neurons()
+            .connections()
+            .iter()
+            .map(|connection| connection.weight)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(weights(species.representative()), weights(&child));
+}
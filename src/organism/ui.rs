@@ -7,11 +7,13 @@ use bevy::{
 
 use crate::{
     camera::{NodeCamera, RenderLayer},
+    node_visual::SelectionText,
     organism::ActiveOrganism,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(spawn_organism_ui);
+    app.add_systems(Update, close_organism_ui);
 }
 
 pub(super) fn set_active(
@@ -34,6 +36,20 @@ pub(super) fn set_active(
     commands.trigger(SpawnOrganismUi);
 }
 
+/// Closes the brain viewer once nothing is selected, like when the selected organism dies.
+fn close_organism_ui(
+    mut commands: Commands,
+    actives: Query<(), With<ActiveOrganism>>,
+    visuals: Query<Entity, With<CellVisual>>,
+) {
+    if !actives.is_empty() {
+        return;
+    }
+    for visual in visuals {
+        commands.entity(visual).despawn();
+    }
+}
+
 #[derive(Event)]
 pub struct SpawnOrganismUi;
 
@@ -61,6 +77,7 @@ fn spawn_organism_ui(
             CellVisual,
             NodeCamera,
             Camera2d,
+            MeshPickingCamera,
             Camera {
                 target: image_handle.clone().into(),
                 ..default()
@@ -85,6 +102,17 @@ fn spawn_organism_ui(
             },
             BorderColor::all(Color::WHITE),
             ViewportNode::new(camera),
+            children![(
+                SelectionText,
+                Text::default(),
+                TextFont::from_font_size(16.),
+                Node {
+                    position_type: PositionType::Absolute,
+                    bottom: px(10),
+                    left: px(10),
+                    ..default()
+                },
+            )],
         ))
         .observe(on_drag_viewport);
 }
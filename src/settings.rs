@@ -15,6 +15,13 @@ pub struct Keybinds {
     pub button_rotate: MouseButton,
     pub key_rotate_left: KeyCode,
     pub key_rotate_right: KeyCode,
+    pub key_save_organism: KeyCode,
+    pub key_export_brain: KeyCode,
+    pub key_toggle_layout: KeyCode,
+    pub key_edit_increase: KeyCode,
+    pub key_edit_decrease: KeyCode,
+    pub key_edit_activation: KeyCode,
+    pub key_edit_delete: KeyCode,
 
     #[cfg(feature = "dev")]
     pub debug_toggle: KeyCode,
@@ -37,6 +44,13 @@ impl Keybinds {
             button_rotate: MouseButton::Middle,
             key_rotate_left: KeyCode::KeyU,
             key_rotate_right: KeyCode::KeyO,
+            key_save_organism: KeyCode::KeyP,
+            key_export_brain: KeyCode::KeyB,
+            key_toggle_layout: KeyCode::KeyG,
+            key_edit_increase: KeyCode::ArrowUp,
+            key_edit_decrease: KeyCode::ArrowDown,
+            key_edit_activation: KeyCode::ArrowRight,
+            key_edit_delete: KeyCode::Delete,
             #[cfg(feature = "dev")]
             debug_toggle: KeyCode::KeyY,
             #[cfg(feature = "dev")]